@@ -0,0 +1,167 @@
+// 2025 Steven Chiacchira
+use crate::automata::{Automaton, AutomatonRule};
+use crate::matrix::{BitOrder, ToroidalBinaryMatrix, ToroidalBitMatrix};
+use rand::{Rng, RngCore};
+use std::collections::HashMap;
+
+/// Number of rows and columns of the random states sampled by [`reachability_sample`], the size
+/// of the Talos automata.
+pub const REACHABILITY_SAMPLE_DIMS: (usize, usize) = (16, 16);
+
+/// Number of most common attractors counted by [`ReachabilityReport::top_attractors`].
+pub const TOP_ATTRACTORS: usize = 8;
+
+/// Normal quantile of the 95% confidence intervals of a [`ProportionEstimate`].
+const CONFIDENCE_Z: f64 = 1.96;
+
+/// Fraction of sampled states with some property, with a 95% Wilson score confidence interval.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProportionEstimate {
+    /// Fraction of the samples with the property.
+    pub fraction: f64,
+    /// Lower end of the confidence interval.
+    pub lower: f64,
+    /// Upper end of the confidence interval.
+    pub upper: f64,
+}
+
+impl ProportionEstimate {
+    /// Estimates the proportion from `successes` out of `samples`. Without samples, nothing is
+    /// known and the interval spans 0 to 1.
+    fn new(successes: usize, samples: usize) -> Self {
+        if samples == 0 {
+            return Self {
+                fraction: 0.0,
+                lower: 0.0,
+                upper: 1.0,
+            };
+        }
+
+        let n = samples as f64;
+        let p = successes as f64 / n;
+        let z2 = CONFIDENCE_Z * CONFIDENCE_Z;
+        let center = (p + z2 / (2.0 * n)) / (1.0 + z2 / n);
+        let half_width =
+            CONFIDENCE_Z * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt() / (1.0 + z2 / n);
+        Self {
+            fraction: p,
+            lower: (center - half_width).max(0.0),
+            upper: (center + half_width).min(1.0),
+        }
+    }
+}
+
+/// A cycle of states reached by sampled automata, together with every cycle equivalent to it
+/// under translation, rotation and reflection, which the rule cannot tell apart.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attractor {
+    /// Smallest [`ToroidalBitMatrix::canonical_form`] of the states of the cycle, as written by
+    /// [`ToroidalBinaryMatrix::to_bytes`], which names the cycle whichever of its states or
+    /// equivalent states a sample first reached.
+    pub state: Vec<u8>,
+    /// Number of distinct states equivalent to `state`, see [`ToroidalBitMatrix::orbit_size`].
+    pub orbit_size: usize,
+    /// Number of generations after which each state of the cycle recurs.
+    pub period: u32,
+    /// Number of samples which fell into the cycle or an equivalent one.
+    pub samples: usize,
+}
+
+/// Result of [`reachability_sample`], describing where random states of a rule end up.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReachabilityReport {
+    /// Number of random states sampled.
+    pub samples: usize,
+    /// Samples which fell into a cycle within the generations allowed.
+    pub converged: ProportionEstimate,
+    /// Samples which fell into one of the [`TOP_ATTRACTORS`] most common attractors.
+    pub top_attractors: ProportionEstimate,
+    /// Every attractor reached, most common first.
+    pub attractors: Vec<Attractor>,
+}
+
+impl ReachabilityReport {
+    /// Returns whether the rule collapses, meaning that even the lower end of the confidence
+    /// interval of [`ReachabilityReport::top_attractors`] exceeds `threshold`. A collapsing rule
+    /// leads most keys into the same few orbits, so their keystreams repeat one another.
+    pub fn collapses(&self, threshold: f64) -> bool {
+        self.top_attractors.lower > threshold
+    }
+}
+
+/// Estimates how often random 16x16 states under `rule` fall into a small set of attractors.
+/// Each of `samples` states has every cell alive with probability one half and is advanced until
+/// it falls into a cycle or `generations` generations pass, see [`Automaton::find_cycle`].
+/// Samples reaching the same cycle from different states, or cycles equivalent under
+/// translation, rotation and reflection, count towards the same [`Attractor`]. The rule is
+/// applied with the Moore neighborhood, which treats every neighbor alike, so equivalent cycles
+/// are equally likely and grouping them is what shows a rule collapsing into few orbits.
+pub fn reachability_sample(
+    rule: &AutomatonRule,
+    samples: usize,
+    generations: u32,
+) -> ReachabilityReport {
+    reachability_sample_with_rng(rule, samples, generations, &mut rand::rng())
+}
+
+/// Estimates reachability as [`reachability_sample`] does, drawing the states from `rng` so that
+/// a seeded `rng` gives a reproducible report.
+pub fn reachability_sample_with_rng<R>(
+    rule: &AutomatonRule,
+    samples: usize,
+    generations: u32,
+    rng: &mut R,
+) -> ReachabilityReport
+where
+    R: RngCore + ?Sized,
+{
+    let (rows, cols) = REACHABILITY_SAMPLE_DIMS;
+    let mut attractors = HashMap::<Vec<u8>, Attractor>::new();
+    let mut converged = 0;
+    for _ in 0..samples {
+        let table = (0..rows)
+            .map(|_| (0..cols).map(|_| rng.random_bool(0.5)).collect())
+            .collect();
+        // the table is a non-empty rectangle
+        let automaton = Automaton::new(ToroidalBitMatrix::<u32>::new(table).unwrap(), rule);
+        let Some(cycle) = automaton.find_cycle(generations) else {
+            continue;
+        };
+        converged += 1;
+
+        let mut walker = automaton;
+        walker.iter_rule(cycle.start);
+        let mut canonical = walker.get_state().canonical_form();
+        for _ in 1..cycle.period {
+            walker.iter_rule(1);
+            let candidate = walker.get_state().canonical_form();
+            if candidate.to_words(BitOrder::MsbFirst) < canonical.to_words(BitOrder::MsbFirst) {
+                canonical = candidate;
+            }
+        }
+        attractors
+            .entry(canonical.to_bytes())
+            .or_insert_with(|| Attractor {
+                state: canonical.to_bytes(),
+                orbit_size: canonical.orbit_size(),
+                period: cycle.period,
+                samples: 0,
+            })
+            .samples += 1;
+    }
+
+    let mut attractors = attractors.into_values().collect::<Vec<Attractor>>();
+    attractors.sort_by(|a, b| b.samples.cmp(&a.samples).then(a.state.cmp(&b.state)));
+    let top = attractors
+        .iter()
+        .take(TOP_ATTRACTORS)
+        .map(|attractor| attractor.samples)
+        .sum();
+
+    ReachabilityReport {
+        samples,
+        converged: ProportionEstimate::new(converged, samples),
+        top_attractors: ProportionEstimate::new(top, samples),
+        attractors,
+    }
+}
@@ -0,0 +1,118 @@
+// 2025 Steven Chiacchira
+use crate::automata::KeystreamSource;
+use crate::matrix::{MatrixOpError, ToroidalBinaryMatrix};
+
+/// A pair of systems in which a `driver` perturbs a `primary` system. Each generation both
+/// systems advance one generation, then the state of the driver is xored into the state of the
+/// primary. The composite exposes the state of the primary, so recovering it does not reveal the
+/// state of either system on its own.
+///
+/// Any two [`KeystreamSource`]s with states of the same shape may be combined, such as an
+/// [`Automaton`](crate::automata::Automaton) driven by an
+/// [`ElementaryAutomaton`](crate::automata::ElementaryAutomaton).
+#[derive(Clone, Debug)]
+pub struct CompositeAutomaton<P, D>
+where
+    P: KeystreamSource,
+    D: KeystreamSource<State = P::State>,
+{
+    primary: P,
+    driver: D,
+}
+
+impl<P, D> CompositeAutomaton<P, D>
+where
+    P: KeystreamSource,
+    D: KeystreamSource<State = P::State>,
+{
+    /// Creates a new [`CompositeAutomaton`] in which `driver` perturbs `primary`, returning a
+    /// [`MatrixOpError`] if their states have different shapes.
+    pub fn new(primary: P, driver: D) -> Result<Self, MatrixOpError> {
+        let (primary_state, driver_state) = (primary.get_state(), driver.get_state());
+        let shapes = (
+            (primary_state.get_rows(), primary_state.get_cols()),
+            (driver_state.get_rows(), driver_state.get_cols()),
+        );
+        if shapes.0 != shapes.1 {
+            return Err(MatrixOpError::DimensionMismatch {
+                lhs: shapes.0,
+                rhs: shapes.1,
+            });
+        }
+
+        Ok(Self { primary, driver })
+    }
+    /// Returns the system whose state is exposed.
+    pub fn get_primary(&self) -> &P {
+        &self.primary
+    }
+    /// Returns the system perturbing the primary system.
+    pub fn get_driver(&self) -> &D {
+        &self.driver
+    }
+}
+
+impl<P, D> KeystreamSource for CompositeAutomaton<P, D>
+where
+    P: KeystreamSource,
+    D: KeystreamSource<State = P::State>,
+{
+    type State = P::State;
+
+    fn evolve(&mut self, generations: u32) {
+        for _ in 0..generations {
+            self.primary.evolve(1);
+            self.driver.evolve(1);
+            // the shapes were checked on creation
+            self.primary
+                .get_state_mut()
+                .bitwise_xor(self.driver.get_state())
+                .unwrap();
+        }
+    }
+    fn get_generation(&self) -> u64 {
+        self.primary.get_generation()
+    }
+    fn get_state(&self) -> &P::State {
+        self.primary.get_state()
+    }
+    fn get_state_mut(&mut self) -> &mut P::State {
+        self.primary.get_state_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::automata::{ElementaryAutomaton, RULE_30};
+    use crate::matrix::ToroidalBitMatrix;
+
+    fn ring(cells: &str) -> ToroidalBitMatrix {
+        ToroidalBitMatrix::from_bits(1, cells.len(), cells.chars().map(|c| c == '#')).unwrap()
+    }
+
+    #[test]
+    fn driver_is_xored_into_primary() {
+        let primary = ElementaryAutomaton::new(ring("...#...."), RULE_30);
+        let driver = ElementaryAutomaton::new(ring("#......."), RULE_30);
+        let mut composite = CompositeAutomaton::new(primary, driver).unwrap();
+        composite.evolve(1);
+        // "..###..." xor "##.....#"
+        assert_eq!(composite.get_state(), &ring("#####..#"));
+        assert_eq!(composite.get_driver().get_state(), &ring("##.....#"));
+        assert_eq!(composite.get_generation(), 1);
+    }
+
+    #[test]
+    fn mismatched_shapes_are_rejected() {
+        let primary = ElementaryAutomaton::new(ring("...#...."), RULE_30);
+        let driver = ElementaryAutomaton::new(ring("#......"), RULE_30);
+        assert!(matches!(
+            CompositeAutomaton::new(primary, driver),
+            Err(MatrixOpError::DimensionMismatch {
+                lhs: (1, 8),
+                rhs: (1, 7)
+            })
+        ));
+    }
+}
@@ -0,0 +1,106 @@
+// 2025 Steven Chiacchira
+use crate::automata::KeystreamSource;
+use crate::matrix::{ToroidalBinaryMatrix, ToroidalBoolMatrix};
+
+/// Wolfram code of rule 30, an elementary rule whose center column has long been used as a
+/// pseudo-random sequence.
+pub const RULE_30: u8 = 30;
+
+/// Object defining a 1D, binary elementary cellular automaton over the cells of a
+/// [`ToroidalBinaryMatrix`], read in row-major order as a single ring: the last cell of each row
+/// is followed by the first cell of the next, and the last cell of the state by the first.
+///
+/// Each generation, a cell takes bit `4 * left + 2 * center + right` of the Wolfram code `rule`,
+/// where `left` and `right` are the cells before and after it on the ring and `center` is the cell
+/// itself.
+/// Ex: under [`RULE_30`], a single alive cell on a ring of 8
+/// ```txt
+/// ...#....
+/// ..###...
+/// .##..#..
+/// ```
+#[derive(Clone, Debug)]
+pub struct ElementaryAutomaton<T = ToroidalBoolMatrix> {
+    rule: u8,
+    state: T,
+    /// Number of generations the rule has been applied since creation.
+    generation: u64,
+}
+
+impl<T> ElementaryAutomaton<T>
+where
+    T: ToroidalBinaryMatrix + Clone,
+{
+    /// Creates a new [`ElementaryAutomaton`] from a `state` and the Wolfram code `rule`.
+    pub fn new(state: T, rule: u8) -> Self {
+        Self {
+            rule,
+            state,
+            generation: 0,
+        }
+    }
+    /// Returns the Wolfram code of the rule followed by the automaton.
+    pub fn get_rule(&self) -> u8 {
+        self.rule
+    }
+    /// Applies the rule to every cell of the ring once.
+    fn step(&mut self) {
+        let cells = self.state.iter_cells().collect::<Vec<bool>>();
+        let n_cells = cells.len();
+        let next = (0..n_cells).map(|i| {
+            let left = cells[(i + n_cells - 1) % n_cells] as u8;
+            let right = cells[(i + 1) % n_cells] as u8;
+            let pattern = 4 * left + 2 * cells[i] as u8 + right;
+            (self.rule >> pattern) & 1 == 1
+        });
+        self.state.set_cells(next);
+        self.generation += 1;
+    }
+}
+
+impl<T> KeystreamSource for ElementaryAutomaton<T>
+where
+    T: ToroidalBinaryMatrix + Clone,
+{
+    type State = T;
+
+    fn evolve(&mut self, generations: u32) {
+        for _ in 0..generations {
+            self.step();
+        }
+    }
+    fn get_generation(&self) -> u64 {
+        self.generation
+    }
+    fn get_state(&self) -> &T {
+        &self.state
+    }
+    fn get_state_mut(&mut self) -> &mut T {
+        &mut self.state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matrix::ToroidalBitMatrix;
+
+    fn ring(cells: &str) -> ToroidalBitMatrix {
+        ToroidalBitMatrix::from_bits(2, cells.len() / 2, cells.chars().map(|c| c == '#')).unwrap()
+    }
+
+    #[test]
+    fn rule_30_wraps_around_the_ring() {
+        let mut automaton = ElementaryAutomaton::new(ring("...#...."), RULE_30);
+        automaton.evolve(1);
+        assert_eq!(automaton.get_state(), &ring("..###..."));
+        automaton.evolve(1);
+        assert_eq!(automaton.get_state(), &ring(".##..#.."));
+        assert_eq!(automaton.get_generation(), 2);
+
+        // the neighbors of the first cell are the last cell and the second
+        let mut automaton = ElementaryAutomaton::new(ring("#......."), RULE_30);
+        automaton.evolve(1);
+        assert_eq!(automaton.get_state(), &ring("##.....#"));
+    }
+}
@@ -0,0 +1,681 @@
+// 2025 Steven Chiacchira
+use crate::matrix::{
+    MatrixIndex, ToroidalBinaryMatrix, ToroidalBoolMatrix, ToroidalByteMatrix, FALSE_CHAR,
+    MOORE_OFFSETS, TRUE_CHAR, VON_NEUMANN_OFFSETS,
+};
+use crate::parse::{format_hex_state, parse_hex_state, HexStateError};
+use std::collections::HashMap;
+use std::mem;
+use std::str::FromStr;
+use std::{error, fmt};
+
+pub mod analysis;
+mod composite;
+mod elementary;
+
+pub use composite::*;
+pub use elementary::*;
+
+/// Largest number of offsets in a [`Neighborhood`], the largest count the `born` and `dies`
+/// tables of an [`AutomatonRule`] cover.
+pub const MAX_NEIGHBORHOOD_SIZE: usize = 8;
+
+/// Offsets of the eight cells a knight's move away from a cell.
+const KNIGHT_OFFSETS: [MatrixIndex; 8] = [
+    MatrixIndex::new(-2, -1),
+    MatrixIndex::new(-2, 1),
+    MatrixIndex::new(-1, -2),
+    MatrixIndex::new(-1, 2),
+    MatrixIndex::new(1, -2),
+    MatrixIndex::new(1, 2),
+    MatrixIndex::new(2, -1),
+    MatrixIndex::new(2, 1),
+];
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+/// Simple struct defining how an [`Automaton`] will change from one state to the next.
+/// Rules can also be parsed from and written as rulestrings, see [`AutomatonRule::from_str`].
+pub struct AutomatonRule {
+    /// A 9-element array of booleans. If the ith element is `true`, then a dead cell with `i`
+    /// alive neighbors will become alive. The neighbors are those of the automaton's
+    /// [`Neighborhood`].
+    /// ex. the `born` array `[true, true, false, false, false, false, false, false, false]`
+    /// specifies that only cells with 0 or 1 neighboring alive cells will become alive.
+    pub born: [bool; 9],
+    /// A 9-element array of booleans. If the ith element is `true`, then a living cell with `i`
+    /// alive neighbors will die.
+    /// ex. the `dies` array `[true, true, false, false, false, false, false, false, false]`
+    /// specifies that only cells with 0 or 1 neighboring alive cells will die.
+    pub dies: [bool; 9],
+}
+
+/// Error arising from parsing an [`AutomatonRule`] from a rulestring.
+#[derive(Debug)]
+pub enum RuleParseError {
+    /// A rulestring must have the form `B<counts>/S<counts>`.
+    InvalidFormat(),
+    /// Each count must be a digit from 0 to [`MAX_NEIGHBORHOOD_SIZE`]. Holds the first character
+    /// which was not.
+    InvalidCount(char),
+}
+
+impl fmt::Display for RuleParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuleParseError::InvalidFormat() => {
+                write!(f, "rule is not of the form B<counts>/S<counts>")
+            }
+            RuleParseError::InvalidCount(c) => {
+                write!(f, "rule has invalid neighbor count {:?}", c)
+            }
+        }
+    }
+}
+
+impl error::Error for RuleParseError {}
+
+/// Parses a rule written in the `B/S` notation of Life-like automata: the counts after `B` are
+/// those at which a dead cell is born, and the counts after `S` those at which a living cell
+/// survives, so every other count kills it. Letters may be lowercase.
+/// Ex:
+/// `"B3/S23"` is Conway's Game of Life, and `"B23456/S234"` is
+/// [`TALOS_RULE`](crate::encrypt::TALOS_RULE).
+impl FromStr for AutomatonRule {
+    type Err = RuleParseError;
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        let (born, survives) = string
+            .trim()
+            .split_once('/')
+            .ok_or(RuleParseError::InvalidFormat())?;
+        let counts = |part: &str, prefix: char| {
+            let digits = part
+                .strip_prefix(prefix)
+                .or_else(|| part.strip_prefix(prefix.to_ascii_lowercase()))
+                .ok_or(RuleParseError::InvalidFormat())?;
+            let mut table = [false; MAX_NEIGHBORHOOD_SIZE + 1];
+            for c in digits.chars() {
+                match c.to_digit(10) {
+                    Some(count) if count as usize <= MAX_NEIGHBORHOOD_SIZE => {
+                        table[count as usize] = true
+                    }
+                    _ => return Err(RuleParseError::InvalidCount(c)),
+                }
+            }
+            Ok(table)
+        };
+
+        Ok(Self {
+            born: counts(born, 'B')?,
+            dies: counts(survives, 'S')?.map(|survives| !survives),
+        })
+    }
+}
+
+/// Writes the rule in the `B/S` notation read by [`AutomatonRule::from_str`].
+impl fmt::Display for AutomatonRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let counts = |table: [bool; MAX_NEIGHBORHOOD_SIZE + 1]| {
+            (0..table.len())
+                .filter(|count| table[*count])
+                .map(|count| count.to_string())
+                .collect::<String>()
+        };
+        write!(
+            f,
+            "B{}/S{}",
+            counts(self.born),
+            counts(self.dies.map(|dies| !dies))
+        )
+    }
+}
+
+/// Error arising from the construction of a [`Neighborhood`].
+#[derive(Debug)]
+pub enum NeighborhoodError {
+    /// A neighborhood may hold at most [`MAX_NEIGHBORHOOD_SIZE`] offsets. Holds the number of
+    /// offsets given.
+    TooManyOffsets(usize),
+}
+
+impl fmt::Display for NeighborhoodError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NeighborhoodError::TooManyOffsets(len) => write!(
+                f,
+                "neighborhood has {} offsets but at most {} are allowed",
+                len, MAX_NEIGHBORHOOD_SIZE
+            ),
+        }
+    }
+}
+
+impl error::Error for NeighborhoodError {}
+
+/// A cycle of states found by [`Automaton::find_cycle`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cycle {
+    /// Number of generations after which the first state of the cycle is reached.
+    pub start: u32,
+    /// Number of generations after which each state of the cycle recurs.
+    pub period: u32,
+}
+
+/// Error arising from [`Automaton::evolve_to_density`].
+#[derive(Debug)]
+pub enum DensityError {
+    /// The density must come within the tolerance of the target in the generations allowed.
+    /// Holds the density after the last of them.
+    NotReached(f64),
+}
+
+impl fmt::Display for DensityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DensityError::NotReached(density) => write!(
+                f,
+                "density {:.3} did not reach the target in the generations allowed",
+                density
+            ),
+        }
+    }
+}
+
+impl error::Error for DensityError {}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// The cells whose alive count an [`Automaton`] looks up in its [`AutomatonRule`], given as
+/// `(row, col)` offsets from the cell being updated. Offsets wrap around the torus.
+/// ex.
+/// the offsets `[(-1, 0), (1, 0)]` count the cells directly above and below each cell, so the
+/// count is at most 2.
+///
+/// An offset may be listed more than once, in which case the cell it refers to is counted once per
+/// listing. Defaults to the [Moore neighborhood](https://en.wikipedia.org/wiki/Moore_neighborhood).
+pub struct Neighborhood {
+    offsets: Vec<MatrixIndex>,
+}
+
+impl Neighborhood {
+    /// Creates a neighborhood from a list of `offsets`. Returns a [`NeighborhoodError`] if there
+    /// are more than [`MAX_NEIGHBORHOOD_SIZE`] offsets.
+    pub fn new<I>(offsets: I) -> Result<Self, NeighborhoodError>
+    where
+        I: IntoIterator,
+        I::Item: Into<MatrixIndex>,
+    {
+        let offsets = offsets
+            .into_iter()
+            .map(Into::into)
+            .collect::<Vec<MatrixIndex>>();
+        if offsets.len() > MAX_NEIGHBORHOOD_SIZE {
+            return Err(NeighborhoodError::TooManyOffsets(offsets.len()));
+        }
+
+        Ok(Self { offsets })
+    }
+    /// Creates the [Moore neighborhood](https://en.wikipedia.org/wiki/Moore_neighborhood) of the
+    /// eight cells surrounding a cell.
+    pub fn moore() -> Self {
+        Self {
+            offsets: MOORE_OFFSETS.to_vec(),
+        }
+    }
+    /// Creates the [von Neumann
+    /// neighborhood](https://en.wikipedia.org/wiki/Von_Neumann_neighborhood) of the four cells
+    /// orthogonally adjacent to a cell.
+    pub fn von_neumann() -> Self {
+        Self {
+            offsets: VON_NEUMANN_OFFSETS.to_vec(),
+        }
+    }
+    /// Creates the neighborhood of the eight cells a knight's move away from a cell.
+    pub fn knight() -> Self {
+        Self {
+            offsets: KNIGHT_OFFSETS.to_vec(),
+        }
+    }
+    /// Returns the offsets of the neighborhood.
+    pub fn get_offsets(&self) -> &[MatrixIndex] {
+        &self.offsets
+    }
+    /// Returns the number of alive cells in the neighborhood of every element of `matrix`, in
+    /// row-major order.
+    pub fn counts_of<T>(&self, matrix: &T) -> ToroidalByteMatrix
+    where
+        T: ToroidalBinaryMatrix,
+    {
+        if self.offsets == MOORE_OFFSETS {
+            return ToroidalByteMatrix::neighbor_counts_of(matrix);
+        }
+
+        let counts = matrix
+            .enumerate_cells()
+            .map(|(idx, _)| {
+                self.offsets
+                    .iter()
+                    .map(|offset| matrix.at(idx + *offset) as u8)
+                    .sum()
+            })
+            .collect();
+        // the counts have one entry per element of the matrix
+        ToroidalByteMatrix::from_storage(matrix.get_rows(), matrix.get_cols(), counts).unwrap()
+    }
+}
+
+impl Default for Neighborhood {
+    fn default() -> Self {
+        Self::moore()
+    }
+}
+
+/// Alive neighbor counts of every cell of an [`Automaton`], kept up to date as cells change, so
+/// that a generation only revisits cells whose state or count changed during the previous one.
+/// Every other cell was left unchanged by the rule with the same state and count, and so is
+/// again.
+#[derive(Clone, Debug)]
+struct NeighborCache {
+    counts: ToroidalByteMatrix,
+    /// Cells which may change in the next generation, each listed once.
+    active: Vec<MatrixIndex>,
+    /// Whether each cell, in row-major order, is listed in `active`.
+    listed: Vec<bool>,
+}
+
+impl NeighborCache {
+    /// Counts the neighbors of every cell of `state`, listing every cell as active.
+    fn new<T>(state: &T, neighborhood: &Neighborhood) -> Self
+    where
+        T: ToroidalBinaryMatrix,
+    {
+        let active = state
+            .enumerate_cells()
+            .map(|(idx, _)| idx)
+            .collect::<Vec<MatrixIndex>>();
+        Self {
+            counts: neighborhood.counts_of(state),
+            listed: vec![true; active.len()],
+            active,
+        }
+    }
+    /// Lists the cell at the wrapped `idx` as active.
+    fn mark(&mut self, idx: MatrixIndex) {
+        let cols = self.counts.get_cols();
+        let idx = idx.wrapped(self.counts.get_rows(), cols);
+        let listed = &mut self.listed[idx.row as usize * cols + idx.col as usize];
+        if !*listed {
+            *listed = true;
+            self.active.push(idx);
+        }
+    }
+    /// Records that the cell at `idx` became `alive`, updating the count of every cell with `idx`
+    /// in its neighborhood and listing them and `idx` as active.
+    fn cell_changed(&mut self, idx: MatrixIndex, alive: bool, neighborhood: &Neighborhood) {
+        self.mark(idx);
+        for offset in neighborhood.get_offsets() {
+            let neighbor = idx - *offset;
+            if alive {
+                self.counts[neighbor] += 1;
+            } else {
+                self.counts[neighbor] -= 1;
+            }
+            self.mark(neighbor);
+        }
+    }
+    /// Takes the active cells, leaving none listed.
+    fn take_active(&mut self) -> Vec<MatrixIndex> {
+        self.listed.fill(false);
+        mem::take(&mut self.active)
+    }
+    /// Translates the counts and active cells `dx` columns and `dy` rows along with the state, as
+    /// [`ToroidalBinaryMatrix::shift`] does.
+    fn shift(&mut self, dx: isize, dy: isize) {
+        let (rows, cols) = (self.counts.get_rows(), self.counts.get_cols());
+        let mut shifted = vec![0_u8; rows * cols];
+        for (idx, count) in self.counts.enumerate_cells() {
+            let idx = idx.offset(dy, dx).wrapped(rows, cols);
+            shifted[idx.row as usize * cols + idx.col as usize] = count;
+        }
+        // the storage has one entry per element of the counts
+        self.counts = ToroidalByteMatrix::from_storage(rows, cols, shifted).unwrap();
+        for idx in self.take_active() {
+            self.mark(idx.offset(dy, dx));
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+/// Object defining a 2D, binary cellular automaton
+/// This CA implementation assumes that the geometry of the cell-space is toroidal. The state may
+/// be stored in any [`ToroidalBinaryMatrix`], defaulting to a [`ToroidalBoolMatrix`].
+pub struct Automaton<T = ToroidalBoolMatrix> {
+    rule: AutomatonRule,
+    state: T,
+    /// Translation `(dx, dy)` applied to the cell-space after each generation. `dx` shifts
+    /// columns and `dy` shifts rows, wrapping around the torus.
+    translation: (isize, isize),
+    /// Cells whose alive count is looked up in `rule`.
+    neighborhood: Neighborhood,
+    /// Number of generations the rule has been applied since creation.
+    generation: u64,
+    /// Whether neighbor counts are kept between generations, see
+    /// [`Automaton::with_neighbor_cache`].
+    cache_neighbors: bool,
+    /// The kept neighbor counts, built by the next generation when missing.
+    neighbor_cache: Option<NeighborCache>,
+}
+
+impl<T> Automaton<T>
+where
+    T: ToroidalBinaryMatrix + Clone,
+{
+    /// Creates a new [`Automaton`] instance from a `state` represented as a
+    /// [`ToroidalBinaryMatrix`] and an [`AutomatonRule`] `rule`.
+    pub fn new(state: T, rule: &AutomatonRule) -> Self {
+        Automaton {
+            state,
+            rule: rule.clone(),
+            translation: (0, 0),
+            neighborhood: Neighborhood::default(),
+            generation: 0,
+            cache_neighbors: false,
+            neighbor_cache: None,
+        }
+    }
+    /// Creates a new [`Automaton`] with rule `rule` from a `rows` by `cols` state written by
+    /// [`Automaton::to_hex`]. Translation and neighborhood take their defaults and must be set
+    /// again if they were changed.
+    /// Ex:
+    /// the 2 by 4 state `"a5"` specifies the state
+    /// ```txt
+    /// #.#.
+    /// .#.#
+    /// ```
+    /// see [`parse_hex_state`].
+    pub fn from_hex(
+        rows: usize,
+        cols: usize,
+        string: &str,
+        rule: &AutomatonRule,
+    ) -> Result<Self, HexStateError> {
+        Ok(Self::new(parse_hex_state(rows, cols, string)?, rule))
+    }
+    /// Returns the state of the [`Automaton`] as a single line of lowercase hexadecimal text,
+    /// see [`format_hex_state`]. The shape, rule, translation and neighborhood are not included.
+    pub fn to_hex(&self) -> String {
+        format_hex_state(&self.state)
+    }
+    /// Sets a translation of `dx` columns and `dy` rows to be applied to the cell-space after each
+    /// application of the rule, returning the modified [`Automaton`]. Cells shifted past the edge
+    /// of the cell-space wrap around to the opposite edge.
+    pub fn with_translation(mut self, dx: isize, dy: isize) -> Self {
+        self.translation = (dx, dy);
+        self
+    }
+    /// Sets the per-generation translation of the [`Automaton`]. See
+    /// [`Automaton::with_translation`].
+    pub fn set_translation(&mut self, dx: isize, dy: isize) {
+        self.translation = (dx, dy);
+    }
+    /// Returns the per-generation translation `(dx, dy)` of the [`Automaton`].
+    pub fn get_translation(&self) -> (isize, isize) {
+        self.translation
+    }
+    /// Sets the [`Neighborhood`] whose alive count is looked up in the rule, returning the
+    /// modified [`Automaton`]. Defaults to the Moore neighborhood.
+    pub fn with_neighborhood(mut self, neighborhood: Neighborhood) -> Self {
+        self.set_neighborhood(neighborhood);
+        self
+    }
+    /// Sets the [`Neighborhood`] of the [`Automaton`]. See [`Automaton::with_neighborhood`].
+    pub fn set_neighborhood(&mut self, neighborhood: Neighborhood) {
+        self.neighborhood = neighborhood;
+        self.neighbor_cache = None;
+    }
+    /// Returns the [`Neighborhood`] of the [`Automaton`].
+    pub fn get_neighborhood(&self) -> &Neighborhood {
+        &self.neighborhood
+    }
+    /// Sets whether the alive neighbor count of every cell is kept between generations and
+    /// updated only around the cells which change, returning the modified [`Automaton`]. Each
+    /// generation then revisits only the cells whose state or count changed during the previous
+    /// one, which is much faster for rules where little of the state is active, such as most
+    /// states of Life after a few hundred generations, at the cost of a byte per cell. The states
+    /// reached are the same either way. Disabled by default.
+    pub fn with_neighbor_cache(mut self, cached: bool) -> Self {
+        self.set_neighbor_cache(cached);
+        self
+    }
+    /// Sets whether neighbor counts are kept between generations. See
+    /// [`Automaton::with_neighbor_cache`].
+    pub fn set_neighbor_cache(&mut self, cached: bool) {
+        self.cache_neighbors = cached;
+        self.neighbor_cache = None;
+    }
+    /// Returns whether neighbor counts are kept between generations.
+    pub fn has_neighbor_cache(&self) -> bool {
+        self.cache_neighbors
+    }
+    /// Iterates the [`Automaton`]'s rule `iterations` times.
+    pub fn iter_rule(&mut self, iterations: u32) {
+        if self.cache_neighbors {
+            for _ in 0..iterations {
+                self.step_cached();
+            }
+            self.generation += iterations as u64;
+            return;
+        }
+        let (dx, dy) = self.translation;
+
+        let mut copy = self.state.clone();
+        for _ in 0..iterations {
+            let neighbor_counts = self.neighborhood.counts_of(&self.state);
+            let next_cells = self
+                .state
+                .iter_cells()
+                .zip(neighbor_counts.iter_cells())
+                .map(|(alive, n_alive_neighbors)| {
+                    if alive {
+                        !self.rule.dies[n_alive_neighbors as usize]
+                    } else {
+                        self.rule.born[n_alive_neighbors as usize]
+                    }
+                });
+            copy.set_cells(next_cells);
+            if (dx, dy) != (0, 0) {
+                copy.shift(dx, dy);
+            }
+
+            mem::swap(&mut copy, &mut self.state);
+        }
+        self.generation += iterations as u64;
+    }
+    /// Applies the rule once to the cells listed as active by the neighbor cache, building the
+    /// cache first if it is missing.
+    fn step_cached(&mut self) {
+        let cache = self
+            .neighbor_cache
+            .get_or_insert_with(|| NeighborCache::new(&self.state, &self.neighborhood));
+
+        let changes = cache
+            .take_active()
+            .into_iter()
+            .filter_map(|idx| {
+                let alive = self.state.at(idx);
+                let n_alive_neighbors = cache.counts.at(idx) as usize;
+                let next = if alive {
+                    !self.rule.dies[n_alive_neighbors]
+                } else {
+                    self.rule.born[n_alive_neighbors]
+                };
+                (next != alive).then_some((idx, next))
+            })
+            .collect::<Vec<(MatrixIndex, bool)>>();
+        for (idx, alive) in changes {
+            self.state.set(idx, alive);
+            cache.cell_changed(idx, alive, &self.neighborhood);
+        }
+
+        let (dx, dy) = self.translation;
+        if (dx, dy) != (0, 0) {
+            self.state.shift(dx, dy);
+            cache.shift(dx, dy);
+        }
+    }
+    /// Advances the [`Automaton`] one generation at a time until the fraction of alive cells is
+    /// within `tolerance` of `target`, returning the number of generations advanced, which is 0 if
+    /// the density is already close enough. Returns a [`DensityError`] if the density is still
+    /// off after `max_generations`, leaving the automaton advanced by all of them.
+    ///
+    /// Used to keep states set up from weak seeds from being nearly all dead or nearly all alive.
+    ///
+    /// # Panics
+    /// If `target` is not between 0 and 1 or `tolerance` is negative.
+    pub fn evolve_to_density(
+        &mut self,
+        target: f64,
+        tolerance: f64,
+        max_generations: u32,
+    ) -> Result<u32, DensityError> {
+        assert!(
+            (0.0..=1.0).contains(&target),
+            "target density must be between 0 and 1"
+        );
+        assert!(tolerance >= 0.0, "tolerance must not be negative");
+
+        for generation in 0..=max_generations {
+            if (self.get_density() - target).abs() <= tolerance {
+                return Ok(generation);
+            }
+            if generation < max_generations {
+                self.iter_rule(1);
+            }
+        }
+        Err(DensityError::NotReached(self.get_density()))
+    }
+    /// Returns the cycle the [`Automaton`] falls into within `max_generations` generations of
+    /// its current state, or `None` if no state recurs by then. The automaton itself is not
+    /// advanced. A state which never changes is a cycle with a period of 1.
+    pub fn find_cycle(&self, max_generations: u32) -> Option<Cycle> {
+        let mut automaton = self.clone();
+        let mut seen = HashMap::new();
+        for generation in 0..=max_generations {
+            if let Some(start) = seen.insert(automaton.state.to_bytes(), generation) {
+                return Some(Cycle {
+                    start,
+                    period: generation - start,
+                });
+            }
+            automaton.iter_rule(1);
+        }
+
+        None
+    }
+    /// Returns the fraction of cells of the [`Automaton`] which are alive.
+    pub fn get_density(&self) -> f64 {
+        self.state.popcount() as f64 / (self.state.get_rows() * self.state.get_cols()) as f64
+    }
+    /// Returns the number of generations the [`Automaton`] has advanced since it was created.
+    /// Changing the state directly does not count as a generation.
+    pub fn get_generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Returns a reference to the Automaton state, represented as a [`ToroidalBinaryMatrix`].
+    pub fn get_state(&self) -> &T {
+        &self.state
+    }
+
+    /// Returns a mutable reference to the Automaton state, allowing it to be modified between
+    /// generations. Kept neighbor counts are recounted by the next generation.
+    pub fn get_state_mut(&mut self) -> &mut T {
+        self.neighbor_cache = None;
+        &mut self.state
+    }
+
+    /// Sets the state of the cell at `idx` to `value`, returning the original value at `idx`.
+    pub fn set_state(&mut self, idx: impl Into<MatrixIndex>, value: bool) -> bool {
+        let idx = idx.into();
+        let original = self.state.set(idx, value);
+        if let Some(cache) = &mut self.neighbor_cache {
+            if original != value {
+                cache.cell_changed(idx, value, &self.neighborhood);
+            }
+        }
+
+        original
+    }
+
+    /// Counts the number of alive cells in the [`Neighborhood`] of `idx`.
+    pub fn alive_neighbors(&self, idx: impl Into<MatrixIndex>) -> u32 {
+        let idx = idx.into();
+        self.neighborhood
+            .get_offsets()
+            .iter()
+            .map(|offset| self.state[idx + *offset] as u32)
+            .sum()
+    }
+}
+
+/// A dynamical system over a binary cell-space, whose states key the scramble, shift and xor
+/// layers of a [`TalosCipher`](crate::encrypt::TalosCipher). The [`Automaton`] is the system
+/// described in RFC-0; any other system may drive the same layers in its place, such as an
+/// [`ElementaryAutomaton`] or a [`CompositeAutomaton`], see
+/// [`TalosCipher::from_sources`](crate::encrypt::TalosCipher::from_sources).
+pub trait KeystreamSource: Clone {
+    /// Matrix holding the cells of the system.
+    type State: ToroidalBinaryMatrix + Clone;
+
+    /// Advances the system `generations` generations.
+    fn evolve(&mut self, generations: u32);
+    /// Returns the number of generations the system has advanced since it was created.
+    fn get_generation(&self) -> u64;
+    /// Returns a reference to the cells of the system.
+    fn get_state(&self) -> &Self::State;
+    /// Returns a mutable reference to the cells of the system, allowing them to be modified
+    /// between generations.
+    fn get_state_mut(&mut self) -> &mut Self::State;
+}
+
+impl<T> KeystreamSource for Automaton<T>
+where
+    T: ToroidalBinaryMatrix + Clone,
+{
+    type State = T;
+
+    fn evolve(&mut self, generations: u32) {
+        self.iter_rule(generations);
+    }
+    fn get_generation(&self) -> u64 {
+        self.generation
+    }
+    fn get_state(&self) -> &T {
+        &self.state
+    }
+    fn get_state_mut(&mut self) -> &mut T {
+        Automaton::get_state_mut(self)
+    }
+}
+
+/// Represents the state of the [`Automaton`] as a rectangular array of characters.
+/// ex.
+/// an Automaton with the state
+/// ```txt
+/// TFFT
+/// TFTT
+/// TTTT
+/// ```
+/// Will be represented as
+/// ```txt
+/// #..#
+/// #.##
+/// ####
+/// ```
+impl<T> fmt::Display for Automaton<T>
+where
+    T: ToroidalBinaryMatrix,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.state.format_with(TRUE_CHAR, FALSE_CHAR, ""))
+    }
+}
@@ -1,14 +1,16 @@
 // 2025 Steven Chiacchira
-use clap::Parser;
-use rand::random;
-use std::collections::HashMap;
-use std::fs;
-use std::io::{self, Write};
-use talos::matrix::ToroidalBinaryMatrix;
-use talos::parse::explode_u8_to_bool_vec;
-use talos::{automata, encrypt, matrix, parse};
+use clap::{Parser, ValueEnum};
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use std::fs::read_to_string;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Write};
+use std::sync::{Arc, Mutex};
+use talos::matrix::MatrixStorage;
+use talos::{encrypt, parse};
 
 #[derive(Debug)]
+#[allow(clippy::enum_variant_names)]
 enum ArgParseError {
     /// An action must be specified upon invocation of `crypt`, specifically:
     /// `--encrypt`
@@ -20,6 +22,77 @@ enum ArgParseError {
 
     /// A specified filename must exist
     NoSuchFile(),
+
+    /// The input could not be decrypted with the given key.
+    DecryptFailed(#[allow(dead_code)] encrypt::DecryptError),
+
+    /// The requested cipher settings are invalid, such as rounds below [`encrypt::MIN_ROUNDS`]
+    /// or an unknown rule.
+    InvalidConfig(#[allow(dead_code)] encrypt::ConfigError),
+
+    /// The self-test run by `--self-test` found a problem.
+    SelfTestFailed(#[allow(dead_code)] talos::SelfTestError),
+
+    /// The matrix checked by `--check-matrix` has problems. Holds how many were found.
+    InvalidMatrix(#[allow(dead_code)] usize),
+
+    /// The input could not be read or the output could not be written.
+    IoFailed(#[allow(dead_code)] io::Error),
+}
+
+impl From<io::Error> for ArgParseError {
+    fn from(error: io::Error) -> Self {
+        ArgParseError::IoFailed(error)
+    }
+}
+
+impl From<encrypt::FileError> for ArgParseError {
+    fn from(error: encrypt::FileError) -> Self {
+        match error {
+            encrypt::FileError::Io(e) => ArgParseError::IoFailed(e),
+            encrypt::FileError::Decrypt(e) => ArgParseError::DecryptFailed(e),
+            encrypt::FileError::Config(e) => ArgParseError::InvalidConfig(e),
+        }
+    }
+}
+
+/// Storage used for the automaton states, see [`MatrixStorage`].
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum Storage {
+    /// One `bool` per cell
+    Bool,
+    /// Cells packed into 32 bit words
+    Bit,
+    /// Cells packed into 64 bit words
+    Bit64,
+}
+
+impl From<Storage> for MatrixStorage {
+    fn from(storage: Storage) -> Self {
+        match storage {
+            Storage::Bool => MatrixStorage::Bool,
+            Storage::Bit => MatrixStorage::Bit,
+            Storage::Bit64 => MatrixStorage::Bit64,
+        }
+    }
+}
+
+/// How the automata move from one block to the next, see [`encrypt::BlockMode`].
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum Mode {
+    /// Each block continues from the automaton states left by the previous one
+    Chained,
+    /// Each block is keyed by the nonce and its index. Reads the whole input into memory
+    Ctr,
+}
+
+impl From<Mode> for encrypt::BlockMode {
+    fn from(mode: Mode) -> Self {
+        match mode {
+            Mode::Chained => encrypt::BlockMode::Chained,
+            Mode::Ctr => encrypt::BlockMode::Ctr,
+        }
+    }
 }
 
 #[derive(Parser, Debug)]
@@ -28,7 +101,8 @@ enum ArgParseError {
 /// 2025 Steven Chiacchira
 struct Args {
     /// Name of the file to encrypt or decrypt
-    input: String,
+    #[arg(required_unless_present_any = ["self_test", "check_matrix", "keygen"])]
+    input: Option<String>,
 
     /// Output file. Defaults to stdout if nothing is specified
     #[arg(short, long)]
@@ -43,116 +117,235 @@ struct Args {
     #[arg(short, long, conflicts_with = "encrypt")]
     decrypt: bool,
 
-    /// Key to be used, specified as a decimal unsigned integer with at most 32 bits. If left
-    /// unspecified, a random key will be used.
-    #[arg(short, long)]
+    /// Legacy key to be used, specified as a decimal unsigned integer with at most 32 bits. If none
+    /// of this, --key-phrase or --key-hex is specified, a random key is drawn and printed in
+    /// hexadecimal for use with --key-hex.
+    #[arg(short, long, conflicts_with_all = ["key_phrase", "key_hex"])]
     key: Option<u32>,
+
+    /// Legacy key to be used, specified as the seed phrase printed for a 32 bit key, such as
+    /// AEBA-GBA. Mutually exclusive with --key and --key-hex
+    #[arg(long, value_parser = parse::seed_phrase::decode_u32)]
+    key_phrase: Option<u32>,
+
+    /// Key to be used, specified as 16 to 32 bytes written in hexadecimal. Mutually exclusive with
+    /// --key and --key-phrase
+    #[arg(long, conflicts_with = "key_phrase")]
+    key_hex: Option<encrypt::Key>,
+
+    /// Storage used for the automaton states. Every storage produces the same output
+    #[arg(long, value_enum, default_value = "bool")]
+    storage: Storage,
+
+    /// Number of generations the automata advance before each block. Must be at least 8, and the
+    /// same for encryption and decryption
+    #[arg(long, default_value_t = encrypt::DEFAULT_ROUNDS)]
+    rounds: u32,
+
+    /// Rule followed by both automata: `talos`, or a rulestring such as B23456/S234. Must be the
+    /// same for encryption and decryption
+    #[arg(long, default_value = encrypt::TALOS_RULE_NAME)]
+    rule: String,
+
+    /// How the automata move from one block to the next. Must be the same for encryption and
+    /// decryption
+    #[arg(long, value_enum, default_value = "chained")]
+    mode: Mode,
+
+    /// File holding the 16x16 init matrix of the shift automaton, replacing the default. Must be
+    /// given with --transpose-matrix, and the same for encryption and decryption
+    #[arg(long, requires = "transpose_matrix")]
+    shift_matrix: Option<String>,
+
+    /// File holding the 16x16 init matrix of the transpose automaton, replacing the default. Must
+    /// be given with --shift-matrix, and the same for encryption and decryption
+    #[arg(long, requires = "shift_matrix")]
+    transpose_matrix: Option<String>,
+
+    /// Append a tag authenticating the ciphertext and its header, so that a modified file is
+    /// rejected before anything is decrypted. Reads the whole input into memory when decrypting,
+    /// and must be given for both encryption and decryption
+    #[arg(long)]
+    authenticate: bool,
+
+    /// Draw the random key and nonce from a ChaCha20 generator seeded with this value instead of
+    /// the operating system, making encryption reproducible. Only for tests and research, since
+    /// anyone who knows the seed can recover the key
+    #[arg(long)]
+    rng_seed: Option<u64>,
+
+    /// Print every block, the generations of the automata at each layer and the block around each
+    /// scramble to stderr as the cipher processes them. Exposes the plaintext and the automaton
+    /// states, so only for debugging
+    #[arg(long)]
+    trace: bool,
+
+    /// Print a new random key in hexadecimal, for use with --key-hex, and exit. Weak keys are
+    /// rejected and drawn again. --rng-seed makes the key reproducible
+    #[arg(long, conflicts_with_all = ["encrypt", "decrypt", "key", "key_phrase", "key_hex"])]
+    keygen: bool,
+
+    /// Check that encryption works as intended and exit, without reading any input
+    #[arg(long, exclusive = true)]
+    self_test: bool,
+
+    /// Check that the given file is a valid 16x16 init matrix, reporting every problem found, and
+    /// exit
+    #[arg(long, exclusive = true)]
+    check_matrix: Option<String>,
+}
+
+/// Draws a random key from `rng`, drawing again while the key is weak.
+fn generate_key(rng: &mut dyn RngCore) -> encrypt::Key {
+    loop {
+        let key = encrypt::Key::generate_with(rng);
+        match encrypt::detect_weak_key(&key) {
+            Some(reason) => eprintln!("Rejected weak key: {}", reason),
+            None => break key,
+        }
+    }
 }
 
 fn main() -> Result<(), ArgParseError> {
     let args = Args::parse();
-    if args.key == None && args.decrypt {
+    if args.self_test {
+        talos::self_test().map_err(ArgParseError::SelfTestFailed)?;
+        eprintln!("Self-test passed");
+        return Ok(());
+    }
+    if let Some(filename) = args.check_matrix {
+        let issues =
+            parse::validate_init_matrix(&read_to_string(filename)?, encrypt::INIT_MATRIX_DIMS);
+        for issue in &issues {
+            eprintln!("{}", issue);
+        }
+        if !issues.is_empty() {
+            return Err(ArgParseError::InvalidMatrix(issues.len()));
+        }
+        eprintln!("Matrix is valid");
+        return Ok(());
+    }
+    let mut rng: Box<dyn RngCore> = match args.rng_seed {
+        Some(seed) => Box::new(ChaCha20Rng::seed_from_u64(seed)),
+        None => Box::new(rand::rng()),
+    };
+    if args.keygen {
+        let key = generate_key(rng.as_mut());
+        println!(
+            "{}",
+            encrypt::encode(key.get_bytes(), encrypt::CiphertextEncoding::Hex)
+        );
+        return Ok(());
+    }
+    let key = args.key.or(args.key_phrase);
+    if key.is_none() && args.key_hex.is_none() && args.decrypt {
         return Err(ArgParseError::NoKeyForDecrypt());
     }
-    let seed = match args.key {
-        Some(seed) => seed,
-        None => random::<u32>(),
+    let schedule = match (&args.key_hex, key) {
+        (Some(key), _) => key.schedule(),
+        // a given 32 bit key is only kept for ciphertexts made with one
+        (None, Some(seed)) => {
+            if args.encrypt {
+                eprintln!(
+                    "Using key {} ({})",
+                    seed,
+                    parse::seed_phrase::encode_u32(seed)
+                );
+            }
+            encrypt::KeySchedule::from(seed)
+        }
+        (None, None) => {
+            let key = generate_key(rng.as_mut());
+            if args.encrypt {
+                eprintln!(
+                    "Using key {}",
+                    encrypt::encode(key.get_bytes(), encrypt::CiphertextEncoding::Hex)
+                );
+            }
+            key.schedule()
+        }
     };
 
-    let mut char_map: HashMap<char, bool> = parse::gen_char_map(seed);
-
-    char_map.insert('#', true);
-    char_map.insert('.', false);
-
-    let t_table = parse::parse_bool_table(T_INIT_MATRIX, &char_map).unwrap();
-    let s_table = parse::parse_bool_table(S_INIT_MATRIX, &char_map).unwrap();
-
-    let t_state = matrix::ToroidalBoolMatrix::new(t_table).unwrap();
-    let s_state = matrix::ToroidalBoolMatrix::new(s_table).unwrap();
-
-    let mut transpose_automata = automata::Automaton::new(t_state, &RULE);
-    let mut shift_automata = automata::Automaton::new(s_state, &RULE);
+    if !args.encrypt && !args.decrypt {
+        return Err(ArgParseError::NoAction());
+    }
 
-    encrypt::temporal_seed_automata(
-        &mut transpose_automata,
-        seed,
-        &parse::get_temporal_seed_map(T_INIT_MATRIX),
-    );
-    encrypt::temporal_seed_automata(
-        &mut shift_automata,
-        seed,
-        &parse::get_temporal_seed_map(S_INIT_MATRIX),
-    );
+    let mut builder = encrypt::TalosCipher::builder()
+        .schedule(schedule)
+        .storage(MatrixStorage::from(args.storage))
+        .rule_name(&args.rule)
+        .rounds(args.rounds)
+        .mode(encrypt::BlockMode::from(args.mode))
+        .authenticate(args.authenticate);
+    if let (Some(shift), Some(transpose)) = (args.shift_matrix, args.transpose_matrix) {
+        builder = builder.init_matrices(&read_to_string(shift)?, &read_to_string(transpose)?);
+    }
+    if args.trace {
+        builder = builder.trace(Arc::new(Mutex::new(print_trace_event)));
+    }
+    // checked before any output is created, as the settings do not depend on the input
+    builder.build_dyn().map_err(ArgParseError::InvalidConfig)?;
 
-    let input_buffer = match fs::read(args.input) {
-        Ok(buffer) => buffer,
+    // clap requires an input unless --self-test or --check-matrix is given
+    let input = match File::open(args.input.unwrap()) {
+        Ok(file) => BufReader::new(file),
         Err(_) => {
             return Err(ArgParseError::NoSuchFile());
         }
     };
-
-    let output_bytes = if args.encrypt {
-        eprintln!("Using key {}", seed);
-        let bits = encrypt::encrypt_message_256(
-            input_buffer,
-            &mut shift_automata,
-            &mut transpose_automata,
-        );
-        parse::concat_bool_to_u8_vec(bits)
-    } else if args.decrypt {
-        let bits = explode_u8_to_bool_vec(input_buffer);
-        encrypt::decrypt_message_256(bits, &mut shift_automata, &mut transpose_automata)
-    } else {
-        return Err(ArgParseError::NoAction());
+    let output: Box<dyn Write> = match args.out {
+        Some(filename) => Box::new(File::create(filename)?),
+        None => Box::new(io::stdout().lock()),
     };
+    let output = BufWriter::new(output);
 
-    match args.out {
-        Some(filename) => {
-            let _ = fs::write(filename, output_bytes);
-        }
-        None => {
-            let _ = io::stdout().write(&output_bytes);
-        }
+    if args.encrypt {
+        encrypt::encrypt_stream_with_builder(input, output, &builder, rng.as_mut())?;
+    } else {
+        encrypt::decrypt_stream_with_builder(input, output, &builder)?;
     }
 
     Ok(())
 }
 
-const RULE: automata::AutomatonRule = automata::AutomatonRule {
-    born: [false, false, true, true, true, true, true, false, false],
-    dies: [true, true, false, false, false, true, true, true, true],
-};
-
-const T_INIT_MATRIX: &str = "P#O#N#M#L#K#J#I#
-#L#K.J#I.H.G#F.H
-Q.D#C#B#A#7#6#E#
-#M.X#W.V.U.T.5#G
-R.E.H#G.F#E.S#D.
-#N#Y.T#S.R.D#4.F
-S.F.I#3#2.Q#R#C.
-#O.Z#U.7#Z#C.3#E
-T#G#J.4.6#P.Q.B#
-#P#2.V#5.Y#B.2.D
-U.H#K.W.X#O#P.A.
-#Q.3#L.M.N.A#Z.C
-V.I.4#5.6#7.O#7.
-#R.J.K#L.M.N.Y#B
-W.S#T.U#V#W.X.6#
-#X.Y.Z.2#3.4.5.A";
-
-const S_INIT_MATRIX: &str = ".A#3.2#Z.Y#X.W#V
-7.B.4.P#O.N.M#L.
-#6#C#5#Q#3.2#Z.U
-E.5#D.6.R#4#7.K#
-#D.4#E.7.S#5.Y.T
-F.C#3.F.A#T#6#J#
-#Q#B.2.G#B.U#X.S
-G#P.A.Z#H.C#V.I#
-.R#O.7#Y.I#D.W#R
-H.E#N.6#X.J.E#H.
-#S.D#M.5#W.K#F.Q
-I#F.C#L.4#V#L.G.
-.T.A.B#K.3#U.M.P
-J#G#H#I#J#2#T#N#
-.U#V.W.X.Y.Z#S.O
-K#L.M#N#O#P.Q#R.";
+/// Prints `event` to stderr as a single line for `--trace`, with blocks in hexadecimal.
+fn print_trace_event(event: encrypt::TraceEvent<'_>) {
+    let hex = |block: &[u8]| encrypt::encode(block, encrypt::CiphertextEncoding::Hex);
+    match event {
+        encrypt::TraceEvent::BlockStart {
+            block_index,
+            direction,
+            block,
+        } => {
+            let action = match direction {
+                encrypt::TraceDirection::Encrypt => "encrypt",
+                encrypt::TraceDirection::Decrypt => "decrypt",
+            };
+            eprintln!("block {} {} {}", block_index, action, hex(block))
+        }
+        encrypt::TraceEvent::LayerKeyed {
+            block_index,
+            layer,
+            shift_generation,
+            transpose_generation,
+        } => eprintln!(
+            "block {} layer {} generations shift {} transpose {}",
+            block_index, layer, shift_generation, transpose_generation
+        ),
+        encrypt::TraceEvent::Scrambled {
+            block_index,
+            layer,
+            before,
+            after,
+        } => eprintln!(
+            "block {} layer {} scramble {} -> {}",
+            block_index,
+            layer,
+            hex(before),
+            hex(after)
+        ),
+        encrypt::TraceEvent::BlockEnd { block_index, block } => {
+            eprintln!("block {} end {}", block_index, hex(block))
+        }
+    }
+}
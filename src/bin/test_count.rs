@@ -1,7 +1,7 @@
 // 2025 Steven Chiacchira
 use clap::Parser;
-use rand::random;
-use std::collections::hash_map::HashMap;
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
 use std::fs::read_to_string;
 use talos::matrix::ToroidalBinaryMatrix;
 use talos::{automata, matrix, parse};
@@ -25,15 +25,26 @@ struct Args {
     /// File to use for initializing the [Automaton](automata::Automaton) state.
     #[arg(short, long)]
     init_file: String,
+
+    /// Seed of the ChaCha20 generator drawing non-contiguous seeds, for reproducible runs. The
+    /// operating system is used if nothing is specified.
+    #[arg(long)]
+    rng_seed: Option<u64>,
 }
 
 fn main() {
     let args = Args::parse();
 
-    let seed_gen = (0..args.seeds).map(if args.use_contiguous_seeds {
-        |i| i
-    } else {
-        |_| random::<u32>()
+    let mut rng: Box<dyn RngCore> = match args.rng_seed {
+        Some(seed) => Box::new(ChaCha20Rng::seed_from_u64(seed)),
+        None => Box::new(rand::rng()),
+    };
+    let seed_gen = (0..args.seeds).map(|i| {
+        if args.use_contiguous_seeds {
+            i
+        } else {
+            rng.next_u32()
+        }
     });
 
     println!("# Using contiguous seeds: {}", args.use_contiguous_seeds);
@@ -43,9 +54,7 @@ fn main() {
     println!("test\ttseed\tgeneration\tn_alive");
 
     for (test, seed) in seed_gen.enumerate() {
-        let mut char_map: HashMap<char, bool> = parse::gen_char_map(seed);
-        char_map.insert('#', true);
-        char_map.insert('.', false);
+        let char_map = parse::CharMap::seeded(seed);
 
         let table =
             parse::parse_bool_table(&read_to_string(&args.init_file).unwrap(), &char_map).unwrap();
@@ -1,7 +1,8 @@
 // 2025 Steven Chiacchira
 use clap::Parser;
-use rand::random;
-use std::collections::{hash_map::HashMap, HashSet};
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use std::collections::HashSet;
 use std::fs::read_to_string;
 use talos::matrix::ToroidalBinaryMatrix;
 use talos::{automata, encrypt, matrix, parse};
@@ -26,6 +27,11 @@ struct Args {
     #[arg(short, long)]
     init_file: String,
 
+    /// Seed of the ChaCha20 generator drawing non-contiguous seeds, for reproducible runs. The
+    /// operating system is used if nothing is specified.
+    #[arg(long)]
+    rng_seed: Option<u64>,
+
     #[arg(long, action)]
     no_temporal_seed: bool,
 }
@@ -33,17 +39,23 @@ struct Args {
 fn main() {
     let args = Args::parse();
 
-    let mut global_used_states: HashSet<Vec<bool>, _> = HashSet::new();
-    let mut global_duplicates: Vec<Vec<bool>> = Vec::new();
-
-    let seed_gen = (0..args.seeds).map(if args.use_contiguous_seeds {
-        |i| i
-    } else {
-        |_| random::<u32>()
+    let mut global_used_states: HashSet<matrix::ToroidalBoolMatrix> = HashSet::new();
+    let mut global_duplicates: Vec<matrix::ToroidalBoolMatrix> = Vec::new();
+
+    let mut rng: Box<dyn RngCore> = match args.rng_seed {
+        Some(seed) => Box::new(ChaCha20Rng::seed_from_u64(seed)),
+        None => Box::new(rand::rng()),
+    };
+    let seed_gen = (0..args.seeds).map(|i| {
+        if args.use_contiguous_seeds {
+            i
+        } else {
+            rng.next_u32()
+        }
     });
 
     let matrix_config = read_to_string(&args.init_file).unwrap();
-    let temporal_seed_map = parse::get_temporal_seed_map(&matrix_config);
+    let temporal_seed_map = parse::TemporalSeedMap::from_init_matrix(&matrix_config);
 
     println!("# Using contiguous seeds: {}", args.use_contiguous_seeds);
     println!("# Number of seeds: {}", args.seeds);
@@ -52,10 +64,8 @@ fn main() {
     println!("test\tn_generations\tseed\tavg_alive\tcontains_global_duplicate");
 
     for (test, seed) in seed_gen.enumerate() {
-        let mut char_map: HashMap<char, bool> = parse::gen_char_map(seed);
-        char_map.insert('#', true);
-        char_map.insert('.', false);
-        let mut local_used_states: HashSet<Vec<bool>, _> = HashSet::new();
+        let char_map = parse::CharMap::seeded(seed);
+        let mut local_used_states: HashSet<matrix::ToroidalBoolMatrix> = HashSet::new();
         let mut n_local_alive_total = 0;
 
         let table = parse::parse_bool_table(&matrix_config, &char_map).unwrap();
@@ -77,10 +87,10 @@ fn main() {
             let n_alive = automaton.get_state().popcount();
             n_local_alive_total += n_alive;
 
-            let curr_state = automaton.get_state().get_storage();
+            let curr_state = automaton.get_state();
 
             if global_used_states.contains(curr_state) {
-                global_duplicates.push(curr_state.to_vec());
+                global_duplicates.push(curr_state.clone());
                 contains_global_duplicate = true;
                 final_generation = generation;
                 break;
@@ -89,7 +99,7 @@ fn main() {
                 break;
             }
             local_used_states.insert(curr_state.clone());
-            global_used_states.insert(curr_state.to_vec());
+            global_used_states.insert(curr_state.clone());
             automaton.iter_rule(1);
         }
 
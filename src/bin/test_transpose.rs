@@ -1,7 +1,7 @@
 // 2025 Steven Chiacchira
 use clap::Parser;
-use rand::random;
-use std::collections::hash_map::HashMap;
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
 use std::fs::read_to_string;
 use talos::matrix::ToroidalBinaryMatrix;
 use talos::{automata, encrypt, matrix, parse};
@@ -29,6 +29,11 @@ struct Args {
     /// File to use for initializing the [Automaton](automata::Automaton) state.
     #[arg(long)]
     init_file: String,
+
+    /// Seed of the ChaCha20 generator drawing random seeds, for reproducible runs. The operating
+    /// system is used if nothing is specified.
+    #[arg(long)]
+    rng_seed: Option<u64>,
 }
 
 const RULE: automata::AutomatonRule = automata::AutomatonRule {
@@ -39,10 +44,16 @@ const RULE: automata::AutomatonRule = automata::AutomatonRule {
 fn main() {
     let args = Args::parse();
 
-    let seed_gen = (0..args.seeds).map(if args.use_contiguous_seeds {
-        |i| i
-    } else {
-        |_| random::<u32>()
+    let mut rng: Box<dyn RngCore> = match args.rng_seed {
+        Some(seed) => Box::new(ChaCha20Rng::seed_from_u64(seed)),
+        None => Box::new(rand::rng()),
+    };
+    let seed_gen = (0..args.seeds).map(|i| {
+        if args.use_contiguous_seeds {
+            i
+        } else {
+            rng.next_u32()
+        }
     });
 
     println!("# Using contiguous seeds: {}", args.use_contiguous_seeds);
@@ -56,9 +67,7 @@ fn main() {
 
     let seed_matrix = read_to_string(&args.init_file).unwrap();
     for (test, seed) in seed_gen.enumerate() {
-        let mut char_map: HashMap<char, bool> = parse::gen_char_map(seed);
-        char_map.insert('#', true);
-        char_map.insert('.', false);
+        let char_map = parse::CharMap::seeded(seed);
 
         let table = parse::parse_bool_table(&seed_matrix, &char_map).unwrap();
         let state = matrix::ToroidalBoolMatrix::new(table).unwrap();
@@ -78,6 +87,7 @@ fn main() {
                         (block_offset + r_offset, 4 + c_offset),
                         (block_offset + r_offset, 8 + c_offset),
                         (block_offset + r_offset, 12 + c_offset),
+                        encrypt::BIT_ORDER,
                     ) as isize;
                     println!(
                         "{}\t{}\t{}\tR{}\t{}",
@@ -100,6 +110,7 @@ fn main() {
                         (4 + r_offset, block_offset + c_offset),
                         (8 + r_offset, block_offset + c_offset),
                         (12 + r_offset, block_offset + c_offset),
+                        encrypt::BIT_ORDER,
                     ) as isize;
                     println!(
                         "{}\t{}\t{}\tC{}\t{}",
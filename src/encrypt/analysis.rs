@@ -0,0 +1,258 @@
+// 2025 Steven Chiacchira
+use crate::automata::KeystreamSource;
+use crate::encrypt::{encrypt_block_in_place, splitmix64_words, Key, TalosCipher, BLOCK_BYTES};
+use crate::matrix::{ToroidalBinaryMatrix, ToroidalBitMatrix};
+use rand::{Rng, RngCore};
+
+/// Number of bits in a message block.
+const BLOCK_BITS: usize = BLOCK_BYTES * u8::BITS as usize;
+
+/// Most rounds tried by [`rounds_to_full_diffusion`].
+pub const MAX_DIFFUSION_ROUNDS: u32 = 64;
+
+/// Summary of the fraction of ciphertext bits which changed over a number of single bit flips.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AvalancheStats {
+    /// Number of single bit flips measured.
+    pub trials: usize,
+    /// Mean fraction of ciphertext bits changed by a flip. An ideal cipher changes half of them.
+    pub mean: f64,
+    /// Smallest fraction of ciphertext bits changed by a flip.
+    pub min: f64,
+    /// Largest fraction of ciphertext bits changed by a flip.
+    pub max: f64,
+}
+
+impl AvalancheStats {
+    /// Summarizes the changed fractions of each trial. Every statistic is zero without trials.
+    fn from_fractions(fractions: &[f64]) -> Self {
+        if fractions.is_empty() {
+            return Self {
+                trials: 0,
+                mean: 0.0,
+                min: 0.0,
+                max: 0.0,
+            };
+        }
+
+        Self {
+            trials: fractions.len(),
+            mean: fractions.iter().sum::<f64>() / fractions.len() as f64,
+            min: fractions.iter().copied().fold(f64::INFINITY, f64::min),
+            max: fractions.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+        }
+    }
+}
+
+/// Result of [`avalanche`], measuring how plaintext and key changes spread through the
+/// ciphertext.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AvalancheReport {
+    /// Changes caused by flipping a single plaintext bit.
+    pub plaintext: AvalancheStats,
+    /// Changes caused by flipping a single key bit.
+    pub key: AvalancheStats,
+}
+
+/// Measures the avalanche effect of Talos on `plaintext` under `key`. For each of `trials`
+/// trials, a random bit of the plaintext and a random bit of the key are flipped in turn, and the
+/// fraction of the ciphertext bits which change is recorded. Every encryption uses a
+/// [`TalosCipher`] with nonce 0 and the default number of rounds.
+///
+/// No plaintext trials are run for an empty `plaintext`.
+pub fn avalanche(key: &Key, plaintext: &[u8], trials: usize) -> AvalancheReport {
+    avalanche_with_rng(key, plaintext, trials, &mut rand::rng())
+}
+
+/// Measures the avalanche effect as [`avalanche`] does, choosing the flipped bits with `rng` so
+/// that a seeded `rng` gives a reproducible report.
+pub fn avalanche_with_rng<R>(
+    key: &Key,
+    plaintext: &[u8],
+    trials: usize,
+    rng: &mut R,
+) -> AvalancheReport
+where
+    R: RngCore + ?Sized,
+{
+    let baseline = encrypt_with(key, plaintext);
+
+    let plaintext_fractions = if plaintext.is_empty() {
+        Vec::new()
+    } else {
+        (0..trials)
+            .map(|_| {
+                let mut flipped = plaintext.to_vec();
+                flip_random_bit(&mut flipped, rng);
+                changed_fraction(&baseline, &encrypt_with(key, &flipped))
+            })
+            .collect()
+    };
+    let key_fractions = (0..trials)
+        .map(|_| {
+            let mut flipped = key.get_bytes().to_vec();
+            flip_random_bit(&mut flipped, rng);
+            // flipping a bit does not change the length, so the key stays valid
+            let flipped = Key::new(&flipped).unwrap();
+            changed_fraction(&baseline, &encrypt_with(&flipped, plaintext))
+        })
+        .collect::<Vec<f64>>();
+
+    AvalancheReport {
+        plaintext: AvalancheStats::from_fractions(&plaintext_fractions),
+        key: AvalancheStats::from_fractions(&key_fractions),
+    }
+}
+
+/// Records which ciphertext bits of the first block depend on which plaintext bits under `key`.
+/// Element `(p, c)` of the returned 256x256 matrix is set when flipping bit `p` of an all-zero
+/// first block changes bit `c` of its ciphertext, so row `p` shows the spread of plaintext bit
+/// `p`. Bits are numbered as in [`ToroidalBinaryMatrix::from_bytes`], and every encryption uses
+/// a [`TalosCipher`] with nonce 0, the default number of rounds and the default number of layers.
+pub fn diffusion_matrix(key: &Key) -> ToroidalBitMatrix {
+    let zero_block = [0_u8; BLOCK_BYTES];
+    let baseline = encrypt_with(key, &zero_block);
+
+    let table = (0..BLOCK_BITS)
+        .map(|bit| {
+            let mut flipped = zero_block;
+            flipped[bit / u8::BITS as usize] ^= 1 << (bit % u8::BITS as usize);
+            let ciphertext = encrypt_with(key, &flipped);
+            (0..BLOCK_BITS)
+                .map(|c| {
+                    let byte = c / u8::BITS as usize;
+                    (baseline[byte] ^ ciphertext[byte]) >> (c % u8::BITS as usize) & 1 == 1
+                })
+                .collect()
+        })
+        .collect();
+
+    // the table is a non-empty square
+    ToroidalBitMatrix::new(table).unwrap()
+}
+
+/// Returns the fewest rounds after which flipping a single cell of an automaton state changes
+/// about half of the ciphertext bits, or `None` if [`MAX_DIFFUSION_ROUNDS`] rounds are not enough.
+/// A change to a cell spreads by at most one cell per generation, so this is the number of rounds
+/// the automata need before the block keys no longer reveal which cells differed.
+///
+/// The rounds are measured by [`cipher_rounds_to_full_diffusion`] on a [`TalosCipher`] under
+/// `key` with nonce 0 and the default rule, init matrices and number of layers.
+pub fn rounds_to_full_diffusion(key: &Key, threshold: f64) -> Option<u32> {
+    cipher_rounds_to_full_diffusion(
+        &TalosCipher::<ToroidalBitMatrix>::new(&key.schedule(), 0),
+        threshold,
+    )
+}
+
+/// Returns the fewest rounds after which flipping a single cell of an automaton state of
+/// `cipher` changes about half of the ciphertext bits of its next block, or `None` if
+/// [`MAX_DIFFUSION_ROUNDS`] rounds are not enough. See [`rounds_to_full_diffusion`].
+///
+/// The automata of `cipher` are copied with each cell of either state flipped in turn. For each
+/// round count, a fixed pseudorandom block is encrypted with the layers of `cipher` by the
+/// original and every copy, as the next block of `cipher` would be if it were configured with
+/// that many rounds, and the round count is accepted once the mean fraction of ciphertext bits
+/// changed is within `threshold` of one half. Copies whose states have become equal to the
+/// original, since the rule erased the flipped cell before it spread, are left out of the mean.
+/// Counts below [`MIN_ROUNDS`](crate::encrypt::MIN_ROUNDS) are measured too, though a cipher
+/// cannot be configured with them.
+pub fn cipher_rounds_to_full_diffusion<T, S>(
+    cipher: &TalosCipher<T, S>,
+    threshold: f64,
+) -> Option<u32>
+where
+    T: ToroidalBinaryMatrix + Clone,
+    S: KeystreamSource<State = T>,
+{
+    // an all-zero block would only show the mask, hiding changes to the permutations
+    let mut diffusion_block = std::array::from_fn::<u8, BLOCK_BYTES, _>(|i| i as u8);
+    splitmix64_words(&mut diffusion_block);
+    let layers = cipher.get_layers();
+    let (shift_automata, transpose_automata) = cipher.automata();
+    let mut baseline = (shift_automata.clone(), transpose_automata.clone());
+    let mut flipped = Vec::new();
+    for flip_transpose in [false, true] {
+        let state = if flip_transpose {
+            transpose_automata.get_state()
+        } else {
+            shift_automata.get_state()
+        };
+        for row in 0..state.get_rows() as isize {
+            for col in 0..state.get_cols() as isize {
+                let (mut shift_automata, mut transpose_automata) = baseline.clone();
+                let automaton = if flip_transpose {
+                    &mut transpose_automata
+                } else {
+                    &mut shift_automata
+                };
+                let state = automaton.get_state_mut();
+                let value = state.at((row, col));
+                state.set((row, col), !value);
+                flipped.push((shift_automata, transpose_automata));
+            }
+        }
+    }
+
+    // each pair of automata advances one generation at a time, so at round r it holds the states
+    // keying the first layer of a block encrypted with r rounds; copies of it key the other layers
+    let encrypt_next = |(shift_automata, transpose_automata): &mut (S, S), rounds: u32| {
+        shift_automata.evolve(1);
+        transpose_automata.evolve(1);
+        let states = (
+            shift_automata.get_state().to_bytes(),
+            transpose_automata.get_state().to_bytes(),
+        );
+        let (mut shift_automata, mut transpose_automata) =
+            (shift_automata.clone(), transpose_automata.clone());
+        let mut block = diffusion_block;
+        encrypt_block_in_place(
+            &mut block,
+            0,
+            1,
+            &mut shift_automata,
+            &mut transpose_automata,
+        );
+        encrypt_block_in_place(
+            &mut block,
+            rounds,
+            layers - 1,
+            &mut shift_automata,
+            &mut transpose_automata,
+        );
+        (block, states)
+    };
+    (1..=MAX_DIFFUSION_ROUNDS).find(|&rounds| {
+        let (baseline_block, baseline_states) = encrypt_next(&mut baseline, rounds);
+        let fractions = flipped
+            .iter_mut()
+            .filter_map(|automata| {
+                let (block, states) = encrypt_next(automata, rounds);
+                (states != baseline_states).then(|| changed_fraction(&baseline_block, &block))
+            })
+            .collect::<Vec<f64>>();
+        let mean = fractions.iter().sum::<f64>() / fractions.len() as f64;
+        !fractions.is_empty() && (mean - 0.5).abs() <= threshold
+    })
+}
+
+/// Encrypts `plaintext` under `key` with nonce 0.
+fn encrypt_with(key: &Key, plaintext: &[u8]) -> Vec<u8> {
+    let mut cipher: TalosCipher = TalosCipher::new(&key.schedule(), 0);
+    cipher.encrypt(plaintext)
+}
+
+/// Flips a bit of `bytes` chosen uniformly at random by `rng`.
+fn flip_random_bit<R>(bytes: &mut [u8], rng: &mut R)
+where
+    R: RngCore + ?Sized,
+{
+    let bit = rng.random_range(0..bytes.len() * u8::BITS as usize);
+    bytes[bit / u8::BITS as usize] ^= 1 << (bit % u8::BITS as usize);
+}
+
+/// Returns the fraction of bits which differ between the equally long `a` and `b`.
+fn changed_fraction(a: &[u8], b: &[u8]) -> f64 {
+    let n_changed: u32 = a.iter().zip(b).map(|(x, y)| (x ^ y).count_ones()).sum();
+    n_changed as f64 / (a.len() * u8::BITS as usize) as f64
+}
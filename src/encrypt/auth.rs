@@ -0,0 +1,404 @@
+// 2025 Steven Chiacchira
+use crate::automata::{Automaton, KeystreamSource};
+use crate::encrypt::{
+    decrypt_message_256, encrypt_block_in_place, encrypt_message_256, DecryptError, BLOCK_BYTES,
+    DEFAULT_LAYERS, DEFAULT_ROUNDS,
+};
+use crate::matrix::ToroidalBinaryMatrix;
+
+/// Number of bytes in an authentication tag.
+pub const TAG_BYTES: usize = 256 / 8;
+
+/// Computes a 256 bit authentication tag over `ciphertext` keyed by the state of `key_automata`.
+///
+/// The tag is a CBC-MAC built on the Talos block function: a 32 byte chaining value starts at
+/// zero, and each 32 byte block of `ciphertext` is xored into it before it is encrypted in place
+/// as [`encrypt_in_place`](crate::encrypt::encrypt_in_place) does, with [`DEFAULT_LAYERS`]
+/// layers. The MAC shift automaton is a
+/// copy of `key_automata` advanced 8 generations, and the MAC transpose automaton a copy of that
+/// advanced a further [`DEFAULT_ROUNDS`] generations. Since encrypting a block is a bijection for
+/// fixed automaton states, changing a single block always changes the tag. The number of blocks
+/// is absorbed last, so that ciphertexts which differ only by trailing zero blocks receive
+/// different tags, and the final chaining value is the tag. A partial final block is padded with
+/// zero bytes.
+pub fn mac_256<S>(ciphertext: &[u8], key_automata: &S) -> [u8; TAG_BYTES]
+where
+    S: KeystreamSource,
+{
+    mac_with_aad_256(&[], ciphertext, key_automata)
+}
+
+/// Computes a 256 bit authentication tag over `associated_data` and `ciphertext` keyed by the
+/// state of `key_automata`, as [`mac_256`] does.
+///
+/// The blocks of `associated_data` are absorbed before those of `ciphertext`, each padded
+/// separately, and the final absorbed block holds the number of ciphertext blocks followed by the
+/// length of `associated_data` in bytes, both as little-endian `u64`s. Without associated data
+/// this is the tag of [`mac_256`].
+pub fn mac_with_aad_256<S>(
+    associated_data: &[u8],
+    ciphertext: &[u8],
+    key_automata: &S,
+) -> [u8; TAG_BYTES]
+where
+    S: KeystreamSource,
+{
+    let mut mac = TalosMac::with_associated_data(key_automata, associated_data);
+    mac.update(ciphertext);
+
+    mac.finalize()
+}
+
+/// Incremental form of [`mac_with_aad_256`], absorbing the ciphertext as it becomes available
+/// rather than all at once. Feeding the ciphertext to [`TalosMac::update`] in any number of
+/// pieces gives the same tag as [`mac_with_aad_256`] on the whole ciphertext, while holding at
+/// most a single partial block.
+/// Ex:
+/// ```txt
+/// let mut mac = TalosMac::new(&key_automata);
+/// mac.update(&ciphertext[..40]);
+/// mac.update(&ciphertext[40..]);
+/// assert_eq!(mac.finalize(), mac_256(&ciphertext, &key_automata));
+/// ```
+#[derive(Debug, Clone)]
+pub struct TalosMac<S = Automaton>
+where
+    S: KeystreamSource,
+{
+    /// The MAC shift and transpose automata.
+    mac_automata: (S, S),
+    chain: [u8; TAG_BYTES],
+    /// Ciphertext bytes which do not yet fill a block.
+    partial: [u8; BLOCK_BYTES],
+    partial_len: usize,
+    /// Number of whole ciphertext blocks absorbed.
+    n_blocks: u64,
+    associated_len: u64,
+}
+
+impl<S> TalosMac<S>
+where
+    S: KeystreamSource,
+{
+    /// Creates a new MAC keyed by the state of `key_automata`, computing the tag of [`mac_256`].
+    pub fn new(key_automata: &S) -> Self {
+        Self::with_associated_data(key_automata, &[])
+    }
+    /// Creates a new MAC keyed by the state of `key_automata` which has absorbed
+    /// `associated_data`, computing the tag of [`mac_with_aad_256`].
+    pub fn with_associated_data(key_automata: &S, associated_data: &[u8]) -> Self {
+        let mut mac_shift_automata = key_automata.clone();
+        mac_shift_automata.evolve(8);
+        let mut mac_transpose_automata = mac_shift_automata.clone();
+        mac_transpose_automata.evolve(DEFAULT_ROUNDS);
+        let mut mac = Self {
+            mac_automata: (mac_shift_automata, mac_transpose_automata),
+            chain: [0_u8; TAG_BYTES],
+            partial: [0_u8; BLOCK_BYTES],
+            partial_len: 0,
+            n_blocks: 0,
+            associated_len: associated_data.len() as u64,
+        };
+        for block in associated_data.chunks(BLOCK_BYTES) {
+            absorb_block_256(&mut mac.chain, &mut mac.mac_automata, block);
+        }
+
+        mac
+    }
+    /// Absorbs the next bytes of the ciphertext. Only whole blocks are absorbed; the remaining
+    /// bytes are held until later bytes complete them or the MAC is finalized.
+    pub fn update(&mut self, ciphertext: &[u8]) {
+        let mut remaining = ciphertext;
+        if self.partial_len > 0 {
+            let n_taken = remaining.len().min(BLOCK_BYTES - self.partial_len);
+            self.partial[self.partial_len..self.partial_len + n_taken]
+                .copy_from_slice(&remaining[..n_taken]);
+            self.partial_len += n_taken;
+            remaining = &remaining[n_taken..];
+            if self.partial_len < BLOCK_BYTES {
+                return;
+            }
+            absorb_block_256(&mut self.chain, &mut self.mac_automata, &self.partial);
+            self.n_blocks += 1;
+            self.partial_len = 0;
+        }
+
+        let mut blocks = remaining.chunks_exact(BLOCK_BYTES);
+        for block in &mut blocks {
+            absorb_block_256(&mut self.chain, &mut self.mac_automata, block);
+            self.n_blocks += 1;
+        }
+        let rest = blocks.remainder();
+        self.partial[..rest.len()].copy_from_slice(rest);
+        self.partial_len = rest.len();
+    }
+    /// Absorbs any held partial block and the lengths, returning the tag.
+    pub fn finalize(mut self) -> [u8; TAG_BYTES] {
+        if self.partial_len > 0 {
+            absorb_block_256(
+                &mut self.chain,
+                &mut self.mac_automata,
+                &self.partial[..self.partial_len],
+            );
+            self.n_blocks += 1;
+        }
+        let mut lengths = [0_u8; 16];
+        lengths[..8].copy_from_slice(&self.n_blocks.to_le_bytes());
+        lengths[8..].copy_from_slice(&self.associated_len.to_le_bytes());
+        absorb_block_256(&mut self.chain, &mut self.mac_automata, &lengths);
+
+        self.chain
+    }
+}
+
+/// Xors `block`, padded with zero bytes to a whole block, into `chain` and encrypts `chain` in
+/// place with the MAC shift and transpose automata.
+fn absorb_block_256<S>(
+    chain: &mut [u8; BLOCK_BYTES],
+    (shift_automata, transpose_automata): &mut (S, S),
+    block: &[u8],
+) where
+    S: KeystreamSource,
+{
+    for (value, byte) in chain.iter_mut().zip(block) {
+        *value ^= byte;
+    }
+    encrypt_block_in_place(
+        chain,
+        DEFAULT_ROUNDS,
+        DEFAULT_LAYERS,
+        shift_automata,
+        transpose_automata,
+    );
+}
+
+/// Encrypts a byte message as [`encrypt_message_256`] does and appends a [`TAG_BYTES`] byte tag
+/// computed by [`mac_256`] over the ciphertext, keyed by the initial state of `shift_automata`.
+/// Use [`open_authenticated`] to verify and decrypt the result.
+/// Notably *DOES NOT* perform the temporal seeding as defined in RFC-1.
+pub fn seal_authenticated<T>(
+    message: &[u8],
+    shift_automata: &mut Automaton<T>,
+    transpose_automata: &mut Automaton<T>,
+) -> Vec<u8>
+where
+    T: ToroidalBinaryMatrix + Clone,
+{
+    seal_authenticated_with_aad(message, &[], shift_automata, transpose_automata)
+}
+
+/// Encrypts a byte message as [`seal_authenticated`] does, binding `associated_data` into the
+/// tag with [`mac_with_aad_256`]. The associated data is not encrypted or included in the
+/// result, and must be passed unchanged to [`open_authenticated_with_aad`].
+/// Notably *DOES NOT* perform the temporal seeding as defined in RFC-1.
+pub fn seal_authenticated_with_aad<T>(
+    message: &[u8],
+    associated_data: &[u8],
+    shift_automata: &mut Automaton<T>,
+    transpose_automata: &mut Automaton<T>,
+) -> Vec<u8>
+where
+    T: ToroidalBinaryMatrix + Clone,
+{
+    let key_automata = shift_automata.clone();
+    let mut sealed = encrypt_message_256(message, shift_automata, transpose_automata);
+    let tag = mac_with_aad_256(associated_data, &sealed, &key_automata);
+    sealed.extend_from_slice(&tag);
+
+    sealed
+}
+
+/// Verifies the tag appended by [`seal_authenticated`] and decrypts the message, returning a
+/// [`DecryptError::AuthenticationFailed`] without decrypting anything if the tag does not match.
+/// Notably *DOES NOT* perform the temporal seeding as defined in RFC-1.
+pub fn open_authenticated<T>(
+    sealed: &[u8],
+    shift_automata: &mut Automaton<T>,
+    transpose_automata: &mut Automaton<T>,
+) -> Result<Vec<u8>, DecryptError>
+where
+    T: ToroidalBinaryMatrix + Clone,
+{
+    open_authenticated_with_aad(sealed, &[], shift_automata, transpose_automata)
+}
+
+/// Verifies the tag appended by [`seal_authenticated_with_aad`] over `associated_data` and the
+/// ciphertext, then decrypts the message as [`open_authenticated`] does.
+/// Notably *DOES NOT* perform the temporal seeding as defined in RFC-1.
+pub fn open_authenticated_with_aad<T>(
+    sealed: &[u8],
+    associated_data: &[u8],
+    shift_automata: &mut Automaton<T>,
+    transpose_automata: &mut Automaton<T>,
+) -> Result<Vec<u8>, DecryptError>
+where
+    T: ToroidalBinaryMatrix + Clone,
+{
+    let ciphertext = verify_tag_256(sealed, associated_data, shift_automata)?;
+
+    decrypt_message_256(ciphertext, shift_automata, transpose_automata)
+}
+
+/// Splits the tag from the end of `sealed` and checks it against [`mac_with_aad_256`] keyed by
+/// `key_automata`, returning the ciphertext before the tag if it matches.
+pub(crate) fn verify_tag_256<'a, S>(
+    sealed: &'a [u8],
+    associated_data: &[u8],
+    key_automata: &S,
+) -> Result<&'a [u8], DecryptError>
+where
+    S: KeystreamSource,
+{
+    if sealed.len() < TAG_BYTES {
+        return Err(DecryptError::TruncatedCiphertext());
+    }
+    let (ciphertext, tag) = sealed.split_at(sealed.len() - TAG_BYTES);
+    let expected_tag = mac_with_aad_256(associated_data, ciphertext, key_automata);
+    // compare every byte rather than stopping at the first difference
+    let tags_differ = tag
+        .iter()
+        .zip(&expected_tag)
+        .fold(0, |differ, (a, b)| differ | (a ^ b));
+    if tags_differ != 0 {
+        return Err(DecryptError::AuthenticationFailed());
+    }
+
+    Ok(ciphertext)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encrypt::{Key, TalosCipher};
+    use crate::matrix::ToroidalBitMatrix;
+
+    const MESSAGE: &[u8] = b"attack the automaton at the fortieth generation";
+
+    fn automata() -> (Automaton<ToroidalBitMatrix>, Automaton<ToroidalBitMatrix>) {
+        let schedule = Key::new(&[3; 16]).unwrap().schedule();
+        let cipher = TalosCipher::<ToroidalBitMatrix>::new(&schedule, 0);
+        let (shift, transpose) = cipher.automata();
+        (shift.clone(), transpose.clone())
+    }
+
+    fn seal(message: &[u8]) -> Vec<u8> {
+        let (mut shift, mut transpose) = automata();
+        seal_authenticated(message, &mut shift, &mut transpose)
+    }
+
+    fn open(sealed: &[u8]) -> Result<Vec<u8>, DecryptError> {
+        let (mut shift, mut transpose) = automata();
+        open_authenticated(sealed, &mut shift, &mut transpose)
+    }
+
+    #[test]
+    fn sealed_round_trip() {
+        let sealed = seal(MESSAGE);
+        assert_eq!(sealed.len(), 2 * BLOCK_BYTES + TAG_BYTES);
+        assert_eq!(open(&sealed).unwrap(), MESSAGE);
+    }
+
+    #[test]
+    fn tampered_ciphertext_is_rejected() {
+        let sealed = seal(MESSAGE);
+        for i in [
+            0,
+            BLOCK_BYTES + 5,
+            sealed.len() - TAG_BYTES - 1,
+            sealed.len() - 1,
+        ] {
+            let mut tampered = sealed.clone();
+            tampered[i] ^= 0x10;
+            assert!(matches!(
+                open(&tampered),
+                Err(DecryptError::AuthenticationFailed())
+            ));
+        }
+    }
+
+    #[test]
+    fn truncated_ciphertext_is_rejected() {
+        let sealed = seal(MESSAGE);
+        assert!(matches!(
+            open(&sealed[..BLOCK_BYTES + TAG_BYTES]),
+            Err(DecryptError::AuthenticationFailed())
+        ));
+        assert!(matches!(
+            open(&sealed[..TAG_BYTES - 1]),
+            Err(DecryptError::TruncatedCiphertext())
+        ));
+    }
+
+    #[test]
+    fn associated_data_is_bound_to_the_tag() {
+        let (mut shift, mut transpose) = automata();
+        let sealed =
+            seal_authenticated_with_aad(MESSAGE, b"v1 notes.txt", &mut shift, &mut transpose);
+        for associated_data in [&b"v1 notes.txt"[..], b"v2 notes.txt", b"v1 notes.txt ", b""] {
+            let (mut shift, mut transpose) = automata();
+            let opened =
+                open_authenticated_with_aad(&sealed, associated_data, &mut shift, &mut transpose);
+            if associated_data == b"v1 notes.txt" {
+                assert_eq!(opened.unwrap(), MESSAGE);
+            } else {
+                assert!(matches!(opened, Err(DecryptError::AuthenticationFailed())));
+            }
+        }
+    }
+
+    #[test]
+    fn associated_data_is_not_confused_with_ciphertext() {
+        let (shift, _) = automata();
+        let ciphertext = [9_u8; 2 * BLOCK_BYTES];
+        assert_eq!(
+            mac_with_aad_256(&[], &ciphertext, &shift),
+            mac_256(&ciphertext, &shift)
+        );
+        assert_ne!(
+            mac_with_aad_256(
+                &ciphertext[..BLOCK_BYTES],
+                &ciphertext[BLOCK_BYTES..],
+                &shift
+            ),
+            mac_256(&ciphertext, &shift)
+        );
+    }
+
+    #[test]
+    fn incremental_tag_matches_one_shot() {
+        let (shift, _) = automata();
+        let associated_data = b"header";
+        let ciphertext = (0..3 * BLOCK_BYTES as u8 + 5).collect::<Vec<u8>>();
+        let tag = mac_with_aad_256(associated_data, &ciphertext, &shift);
+        for pieces in [
+            vec![ciphertext.len()],
+            vec![0, ciphertext.len()],
+            vec![1, 40, 41, ciphertext.len()],
+            vec![BLOCK_BYTES, 2 * BLOCK_BYTES, ciphertext.len()],
+            (1..=ciphertext.len()).collect(),
+        ] {
+            let mut mac = TalosMac::with_associated_data(&shift, associated_data);
+            let mut start = 0;
+            for end in pieces {
+                mac.update(&ciphertext[start..end]);
+                start = end;
+            }
+            assert_eq!(mac.finalize(), tag);
+        }
+    }
+
+    #[test]
+    fn sealed_stream_matches_seal() {
+        use std::io::Write;
+
+        let schedule = Key::new(&[3; 16]).unwrap().schedule();
+        let sealed = TalosCipher::<ToroidalBitMatrix>::new(&schedule, 0).seal(MESSAGE, b"header");
+
+        let mut cipher = TalosCipher::<ToroidalBitMatrix>::new(&schedule, 0);
+        let mut writer = cipher.seal_writer(Vec::new(), b"header");
+        for piece in MESSAGE.chunks(7) {
+            writer.write_all(piece).unwrap();
+        }
+        assert_eq!(writer.finish().unwrap(), sealed);
+    }
+}
@@ -0,0 +1,271 @@
+// 2025 Steven Chiacchira
+use crate::automata::AutomatonRule;
+use crate::encrypt::{
+    BlockMode, ConfigError, Key, KeySchedule, SharedTraceSink, TalosCipher, BLOCK_BITS,
+    DEFAULT_LAYERS, DEFAULT_ROUNDS, INIT_MATRIX_DIMS, SHIFT_INIT_MATRIX, TALOS_RULE,
+    TRANSPOSE_INIT_MATRIX,
+};
+use crate::matrix::{DynBinaryMatrix, MatrixConstructError, MatrixStorage, ToroidalBinaryMatrix};
+use crate::parse::validate_init_matrix;
+use std::fmt;
+
+/// Name accepted by [`TalosCipherBuilder::rule_name`] for [`TALOS_RULE`].
+pub const TALOS_RULE_NAME: &str = "talos";
+
+/// Rule given to a [`TalosCipherBuilder`], resolved when the cipher is built.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum RuleChoice {
+    Value(AutomatonRule),
+    Name(String),
+}
+
+/// Rounds given to a [`TalosCipherBuilder`], resolved when the cipher is built.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum RoundsChoice {
+    Value(u32),
+    Diffusion(f64),
+}
+
+/// Builder for a [`TalosCipher`] configured beyond the defaults of [`TalosCipher::new`]. Every
+/// setting is checked together by [`TalosCipherBuilder::build`], which returns the first problem
+/// found as a [`ConfigError`], so an invalid combination never produces a cipher.
+///
+/// Both sides of a session must build their ciphers with the same settings, except for the
+/// storage and trace sink, which never change the output.
+/// Ex:
+/// ```txt
+/// TalosCipher::builder().key(&key).nonce(nonce).rule_name("B23456/S234").rounds(13).build()
+/// ```
+#[derive(Clone)]
+pub struct TalosCipherBuilder {
+    schedule: Option<KeySchedule>,
+    nonce: u64,
+    rule: RuleChoice,
+    rounds: RoundsChoice,
+    layers: u32,
+    block_bits: usize,
+    shift_init_matrix: String,
+    transpose_init_matrix: String,
+    mode: BlockMode,
+    authenticate: bool,
+    storage: MatrixStorage,
+    trace: Option<SharedTraceSink>,
+}
+
+impl Default for TalosCipherBuilder {
+    fn default() -> Self {
+        Self {
+            schedule: None,
+            nonce: 0,
+            rule: RuleChoice::Value(TALOS_RULE),
+            rounds: RoundsChoice::Value(DEFAULT_ROUNDS),
+            layers: DEFAULT_LAYERS,
+            block_bits: BLOCK_BITS,
+            shift_init_matrix: SHIFT_INIT_MATRIX.to_string(),
+            transpose_init_matrix: TRANSPOSE_INIT_MATRIX.to_string(),
+            mode: BlockMode::default(),
+            authenticate: false,
+            storage: MatrixStorage::default(),
+            trace: None,
+        }
+    }
+}
+
+impl TalosCipherBuilder {
+    /// Sets the key of the cipher, expanding it into its [`KeySchedule`]. Required.
+    pub fn key(self, key: &Key) -> Self {
+        self.schedule(key.schedule())
+    }
+
+    /// Sets the key of the cipher as an already expanded schedule. See
+    /// [`TalosCipherBuilder::key`].
+    pub fn schedule(mut self, schedule: KeySchedule) -> Self {
+        self.schedule = Some(schedule);
+        self
+    }
+
+    /// Sets the nonce of the session. Defaults to 0.
+    pub fn nonce(mut self, nonce: u64) -> Self {
+        self.nonce = nonce;
+        self
+    }
+
+    /// Sets the rule followed by both automata. Defaults to [`TALOS_RULE`].
+    pub fn rule(mut self, rule: AutomatonRule) -> Self {
+        self.rule = RuleChoice::Value(rule);
+        self
+    }
+
+    /// Sets the rule followed by both automata by name: [`TALOS_RULE_NAME`] in any case, or a
+    /// rulestring such as `"B23456/S234"`, see [`AutomatonRule`]'s `FromStr` implementation. An
+    /// unknown name makes [`TalosCipherBuilder::build`] fail.
+    pub fn rule_name(mut self, name: &str) -> Self {
+        self.rule = RuleChoice::Name(name.to_string());
+        self
+    }
+
+    /// Sets the number of generations both automata advance before each block, see
+    /// [`TalosCipher::with_rounds`]. Defaults to [`DEFAULT_ROUNDS`].
+    pub fn rounds(mut self, rounds: u32) -> Self {
+        self.rounds = RoundsChoice::Value(rounds);
+        self
+    }
+
+    /// Sets the number of generations both automata advance before each block to the fewest
+    /// reaching diffusion within `threshold` of one half on the built cipher, see
+    /// [`TalosCipher::with_diffusion_rounds`]. The measurement follows the configured key, nonce,
+    /// rule, init matrices and layers, and makes [`TalosCipherBuilder::build`] fail if diffusion
+    /// is not reached.
+    pub fn diffusion_rounds(mut self, threshold: f64) -> Self {
+        self.rounds = RoundsChoice::Diffusion(threshold);
+        self
+    }
+
+    /// Sets the number of layers applied to each block, see [`TalosCipher::with_layers`].
+    /// Defaults to [`DEFAULT_LAYERS`].
+    pub fn layers(mut self, layers: u32) -> Self {
+        self.layers = layers;
+        self
+    }
+
+    /// Sets the number of bits in a block. Only [`BLOCK_BITS`], the default, is supported.
+    pub fn block_bits(mut self, bits: usize) -> Self {
+        self.block_bits = bits;
+        self
+    }
+
+    /// Sets the initial states of the shift and transpose automata, in the format of
+    /// [`SHIFT_INIT_MATRIX`] and [`TRANSPOSE_INIT_MATRIX`], which are the defaults. Keystream
+    /// sources start from `transpose` as well.
+    pub fn init_matrices(mut self, shift: &str, transpose: &str) -> Self {
+        self.shift_init_matrix = shift.to_string();
+        self.transpose_init_matrix = transpose.to_string();
+        self
+    }
+
+    /// Sets how the automata move from one block to the next. Defaults to
+    /// [`BlockMode::Chained`].
+    pub fn mode(mut self, mode: BlockMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Sets whether messages are to be sealed rather than encrypted alone, see
+    /// [`TalosCipher::is_authenticated`]. Defaults to `false`.
+    pub fn authenticate(mut self, authenticate: bool) -> Self {
+        self.authenticate = authenticate;
+        self
+    }
+
+    /// Sets the storage used for the automaton states by [`TalosCipherBuilder::build_dyn`].
+    /// Defaults to [`MatrixStorage::default`].
+    pub fn storage(mut self, storage: MatrixStorage) -> Self {
+        self.storage = storage;
+        self
+    }
+
+    /// Sets the sink every block is reported to, see [`TalosCipher::with_trace`]. Defaults to no
+    /// tracing.
+    pub fn trace(mut self, sink: SharedTraceSink) -> Self {
+        self.trace = Some(sink);
+        self
+    }
+
+    /// Returns whether messages are to be sealed rather than encrypted alone.
+    pub fn get_authenticate(&self) -> bool {
+        self.authenticate
+    }
+
+    /// Returns how the automata move from one block to the next.
+    pub fn get_mode(&self) -> BlockMode {
+        self.mode
+    }
+
+    /// Builds the cipher with automaton states of type `T`, returning a [`ConfigError`] if no key
+    /// was given or any setting is invalid.
+    pub fn build<T>(&self) -> Result<TalosCipher<T>, ConfigError>
+    where
+        T: ToroidalBinaryMatrix + Clone,
+    {
+        self.build_with(|table| T::new(table))
+    }
+
+    /// Builds the cipher as [`TalosCipherBuilder::build`] does, storing the automaton states in
+    /// the configured [`MatrixStorage`].
+    pub fn build_dyn(&self) -> Result<TalosCipher<Box<dyn DynBinaryMatrix>>, ConfigError> {
+        self.build_with(|table| self.storage.new_matrix(table))
+    }
+
+    fn build_with<T, F>(&self, new_state: F) -> Result<TalosCipher<T>, ConfigError>
+    where
+        T: ToroidalBinaryMatrix + Clone,
+        F: FnMut(Vec<Vec<bool>>) -> Result<T, MatrixConstructError>,
+    {
+        let schedule = self.schedule.as_ref().ok_or(ConfigError::NoKey())?;
+        if self.block_bits != BLOCK_BITS {
+            return Err(ConfigError::UnsupportedBlockSize(self.block_bits));
+        }
+        let rule = self.resolve_rule()?;
+        for init_matrix in [&self.shift_init_matrix, &self.transpose_init_matrix] {
+            if let Some(issue) = validate_init_matrix(init_matrix, INIT_MATRIX_DIMS)
+                .into_iter()
+                .next()
+            {
+                return Err(ConfigError::InvalidInitMatrix(issue));
+            }
+        }
+
+        // the init matrices were validated as 16x16 tables of known characters above
+        let cipher = TalosCipher::with_setup(
+            schedule,
+            self.nonce,
+            &rule,
+            &self.shift_init_matrix,
+            &self.transpose_init_matrix,
+            new_state,
+        )
+        .unwrap();
+        let mut cipher = match self.rounds {
+            RoundsChoice::Value(rounds) => cipher.with_rounds(rounds)?.with_layers(self.layers)?,
+            // the rounds are measured with the layers they will be used with
+            RoundsChoice::Diffusion(threshold) => cipher
+                .with_layers(self.layers)?
+                .with_diffusion_rounds(threshold)?,
+        };
+        cipher.set_mode(self.mode);
+        cipher.set_authenticated(self.authenticate);
+        cipher.set_trace(self.trace.clone());
+
+        Ok(cipher)
+    }
+
+    fn resolve_rule(&self) -> Result<AutomatonRule, ConfigError> {
+        match &self.rule {
+            RuleChoice::Value(rule) => Ok(rule.clone()),
+            RuleChoice::Name(name) if name.trim().eq_ignore_ascii_case(TALOS_RULE_NAME) => {
+                Ok(TALOS_RULE)
+            }
+            RuleChoice::Name(name) => name
+                .parse()
+                .map_err(|_| ConfigError::UnknownRule(name.clone())),
+        }
+    }
+}
+
+impl fmt::Debug for TalosCipherBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // the schedule is key material, so only its presence is shown
+        f.debug_struct("TalosCipherBuilder")
+            .field("has_key", &self.schedule.is_some())
+            .field("nonce", &self.nonce)
+            .field("rule", &self.rule)
+            .field("rounds", &self.rounds)
+            .field("layers", &self.layers)
+            .field("block_bits", &self.block_bits)
+            .field("mode", &self.mode)
+            .field("authenticate", &self.authenticate)
+            .field("storage", &self.storage)
+            .field("traced", &self.trace.is_some())
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,82 @@
+// 2025 Steven Chiacchira
+
+/// Number of bytes in a checksum appended to a message.
+pub(crate) const CHECKSUM_BYTES: usize = u32::BITS as usize / 8;
+
+/// Reversed polynomial of the CRC-32 used by zlib, PNG and Ethernet.
+const CRC32_POLYNOMIAL: u32 = 0xedb8_8320;
+
+/// Lookup table of the CRC-32 of every byte value.
+const CRC32_TABLE: [u32; 256] = crc32_table();
+
+/// Builds [`CRC32_TABLE`].
+const fn crc32_table() -> [u32; 256] {
+    let mut table = [0_u32; 256];
+    let mut i = 0;
+    while i < table.len() {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < u8::BITS {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ CRC32_POLYNOMIAL
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+
+    table
+}
+
+/// Incremental CRC-32 checksum, used to detect accidental corruption of unauthenticated
+/// ciphertexts. It offers no protection against deliberate modification.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Crc32 {
+    state: u32,
+}
+
+impl Crc32 {
+    /// Creates a checksum of no bytes.
+    pub(crate) fn new() -> Self {
+        Self { state: u32::MAX }
+    }
+
+    /// Adds `bytes` to the checksum.
+    pub(crate) fn update(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.state = CRC32_TABLE[((self.state ^ *byte as u32) & 0xff) as usize]
+                ^ (self.state >> u8::BITS);
+        }
+    }
+
+    /// Returns the checksum of every byte added so far.
+    pub(crate) fn finish(&self) -> u32 {
+        !self.state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crc32_check_value() {
+        let mut checksum = Crc32::new();
+        checksum.update(b"123456789");
+        assert_eq!(checksum.finish(), 0xcbf4_3926);
+        assert_eq!(Crc32::new().finish(), 0);
+    }
+
+    #[test]
+    fn crc32_is_incremental() {
+        let mut whole = Crc32::new();
+        whole.update(b"talos checksum");
+        let mut pieces = Crc32::new();
+        pieces.update(b"talos");
+        pieces.update(b" checksum");
+        assert_eq!(whole.finish(), pieces.finish());
+    }
+}
@@ -0,0 +1,168 @@
+// 2025 Steven Chiacchira
+use crate::automata::{Automaton, AutomatonRule};
+use crate::encrypt::{splitmix64, wipe, ConfigError, KeySchedule, BLOCK_BYTES, GOLDEN_GAMMA};
+use crate::matrix::ToroidalBinaryMatrix;
+use crate::parse::{parse_bool_table, TemporalSeedMap};
+
+/// Fraction of alive cells the keystream sources are evolved towards after seeding, see
+/// [`Automaton::evolve_to_density`].
+pub const KEYSTREAM_TARGET_DENSITY: f64 = 0.5;
+
+/// Largest distance from [`KEYSTREAM_TARGET_DENSITY`] at which a keystream source is accepted.
+pub const KEYSTREAM_DENSITY_TOLERANCE: f64 = 0.25;
+
+/// Most generations a keystream source is evolved for to reach [`KEYSTREAM_TARGET_DENSITY`].
+pub const KEYSTREAM_SETTLE_GENERATIONS: u32 = 64;
+
+/// Rule merging the states of the keystream sources of a
+/// [`TalosCipher`](crate::encrypt::TalosCipher) into the keystream xored into each block, see
+/// [`TalosCipher::with_keystream`](crate::encrypt::TalosCipher::with_keystream).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum KeystreamCombiner {
+    /// Each bit is the xor of that bit of every source.
+    #[default]
+    Xor,
+    /// Each bit is the value held by most sources, or by the first source when they are tied. An
+    /// odd number of sources never ties.
+    Majority,
+    /// Block `i` takes the state of source `i % n` of the `n` sources alone.
+    Alternating,
+}
+
+impl KeystreamCombiner {
+    /// Combines the equally long `states` of the sources into the keystream of block
+    /// `block_index`.
+    ///
+    /// # Panics
+    /// If `states` is empty.
+    pub fn combine(&self, states: &[&[u8]], block_index: u64) -> Vec<u8> {
+        assert!(!states.is_empty(), "at least one state must be combined");
+        match self {
+            KeystreamCombiner::Xor => {
+                states[1..]
+                    .iter()
+                    .fold(states[0].to_vec(), |mut acc, state| {
+                        for (byte, value) in acc.iter_mut().zip(*state) {
+                            *byte ^= value;
+                        }
+                        acc
+                    })
+            }
+            KeystreamCombiner::Majority => (0..states[0].len())
+                .map(|i| {
+                    (0..u8::BITS).fold(0_u8, |byte, bit| {
+                        let is_set = |state: &&[u8]| (state[i] >> bit) & 1 == 1;
+                        let n_set = states.iter().filter(|state| is_set(state)).count();
+                        let set = match (2 * n_set).cmp(&states.len()) {
+                            std::cmp::Ordering::Greater => true,
+                            std::cmp::Ordering::Less => false,
+                            std::cmp::Ordering::Equal => is_set(&states[0]),
+                        };
+                        byte | (set as u8) << bit
+                    })
+                })
+                .collect(),
+            KeystreamCombiner::Alternating => {
+                states[(block_index % states.len() as u64) as usize].to_vec()
+            }
+        }
+    }
+}
+
+/// Independently keyed automata whose combined states are xored into each block after its
+/// layers, as configured on a [`TalosCipher`](crate::encrypt::TalosCipher).
+#[derive(Debug, Clone)]
+pub(crate) struct KeystreamSources<T>
+where
+    T: ToroidalBinaryMatrix + Clone,
+{
+    automata: Vec<Automaton<T>>,
+    combiner: KeystreamCombiner,
+    /// Number of blocks whose keystream has been produced.
+    n_blocks: u64,
+}
+
+impl<T> KeystreamSources<T>
+where
+    T: ToroidalBinaryMatrix + Clone,
+{
+    /// Creates one source for each of `schedules`. Each source follows `rule` from
+    /// `transpose_init_matrix`, such as
+    /// [`TRANSPOSE_INIT_MATRIX`](crate::encrypt::TRANSPOSE_INIT_MATRIX), filled in by its schedule
+    /// and stored like `template`, and is seeded as the transpose automaton is, with `nonce` mixed
+    /// with the index of the source, so sources never repeat the states of the cipher's own
+    /// automata or of each other. Each source is then evolved until its density is within
+    /// [`KEYSTREAM_DENSITY_TOLERANCE`] of [`KEYSTREAM_TARGET_DENSITY`], returning a
+    /// [`ConfigError`] naming the first source which does not get there.
+    pub(crate) fn new(
+        schedules: &[KeySchedule],
+        nonce: u64,
+        combiner: KeystreamCombiner,
+        template: &T,
+        rule: &AutomatonRule,
+        transpose_init_matrix: &str,
+    ) -> Result<Self, ConfigError> {
+        let seed_map = TemporalSeedMap::from_init_matrix(transpose_init_matrix);
+        let automata = schedules
+            .iter()
+            .enumerate()
+            .map(|(i, schedule)| {
+                // the cipher's init matrices only hold base-32 digits, '#' and '.'
+                let table = parse_bool_table(transpose_init_matrix, &schedule.char_map()).unwrap();
+                let mut state = template.clone();
+                state.set_cells(table.into_iter().flatten());
+                let mut automaton = Automaton::new(state, rule);
+                let source_nonce = splitmix64(nonce ^ (i as u64 + 1).wrapping_mul(GOLDEN_GAMMA));
+                schedule.seed_transpose_automata(&mut automaton, source_nonce, &seed_map);
+                automaton
+                    .evolve_to_density(
+                        KEYSTREAM_TARGET_DENSITY,
+                        KEYSTREAM_DENSITY_TOLERANCE,
+                        KEYSTREAM_SETTLE_GENERATIONS,
+                    )
+                    .map_err(|_| ConfigError::DegenerateKeystreamSource(i))?;
+                Ok(automaton)
+            })
+            .collect::<Result<Vec<Automaton<T>>, ConfigError>>()?;
+
+        Ok(Self {
+            automata,
+            combiner,
+            n_blocks: 0,
+        })
+    }
+    /// Returns the rule merging the sources.
+    pub(crate) fn get_combiner(&self) -> KeystreamCombiner {
+        self.combiner
+    }
+    /// Returns the sources, in the order of their schedules.
+    pub(crate) fn automata_mut(&mut self) -> &mut [Automaton<T>] {
+        &mut self.automata
+    }
+    /// Advances every source `rounds` generations and xors their combined states into the 32 byte
+    /// `block`.
+    pub(crate) fn apply(&mut self, block: &mut [u8], rounds: u32) {
+        let mut states = self
+            .automata
+            .iter_mut()
+            .map(|automaton| {
+                automaton.iter_rule(rounds);
+                automaton.get_state().to_bytes()
+            })
+            .collect::<Vec<Vec<u8>>>();
+        let mut keystream = self.combiner.combine(
+            &states.iter().map(Vec::as_slice).collect::<Vec<&[u8]>>(),
+            self.n_blocks,
+        );
+        debug_assert_eq!(keystream.len(), BLOCK_BYTES);
+        for (byte, value) in block.iter_mut().zip(&keystream) {
+            *byte ^= value;
+        }
+        self.n_blocks += 1;
+
+        wipe(&mut keystream);
+        for state in &mut states {
+            wipe(state);
+        }
+    }
+}
@@ -0,0 +1,337 @@
+// 2025 Steven Chiacchira
+use crate::automata::{Automaton, KeystreamSource};
+use crate::encrypt::{
+    check_ciphertext_len, decrypt_block_in_place, encrypt_block_in_place, iter_bits,
+    pad_message_256, splitmix64, unpad_message_256, wipe, DecryptError, BLOCK_BYTES,
+    DEFAULT_LAYERS, DEFAULT_ROUNDS, GOLDEN_GAMMA,
+};
+use crate::matrix::ToroidalBinaryMatrix;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Number of rounds of the Feistel network mixing the nonce and block index of a CTR block.
+const CTR_TWEAK_ROUNDS: u64 = 4;
+
+/// Domains separating the keys hashed from the shift and transpose automata, so equal states do
+/// not receive equal tweaks.
+const SHIFT_DOMAIN: u64 = 0;
+const TRANSPOSE_DOMAIN: u64 = 1;
+
+/// Returns a key hashed from the state bytes `state` of an automaton with [`splitmix64`], separated
+/// by `domain`.
+fn state_key(state: &[u8], domain: u64) -> u64 {
+    state.chunks(8).fold(splitmix64(domain), |key, chunk| {
+        let mut word = [0_u8; 8];
+        word[..chunk.len()].copy_from_slice(chunk);
+        splitmix64(key ^ u64::from_le_bytes(word)).wrapping_add(GOLDEN_GAMMA)
+    })
+}
+
+/// Returns the 128 bit tweak of block `block_index` of a message with nonce `nonce`, keyed by
+/// `key`. The tweak is computed by a Feistel network over the nonce and block index with
+/// [`splitmix64`] as its round function, so for a fixed key no two pairs of nonce and block index
+/// share a tweak.
+fn ctr_tweak(key: u64, nonce: u64, block_index: u64) -> [u64; 2] {
+    let (mut left, mut right) = (nonce, block_index);
+    for round in 0..CTR_TWEAK_ROUNDS {
+        let round_key = splitmix64(key.wrapping_add(round.wrapping_mul(GOLDEN_GAMMA)));
+        (left, right) = (right, left ^ splitmix64(right ^ round_key));
+    }
+
+    [left, right]
+}
+
+/// Xors the tweaks of block `block_index` of a message with nonce `nonce` into the state of
+/// `automaton`, keyed by a hash of the state itself. Each 128 bit run of cells receives its own
+/// tweak, and a partial final run is left unchanged. Since the tweak of the first run differs for
+/// every pair of nonce and block index, states of at least 128 cells are keyed differently for
+/// every block of every message.
+fn tweak_state<S>(automaton: &mut S, domain: u64, nonce: u64, block_index: u64)
+where
+    S: KeystreamSource,
+{
+    let mut bytes = automaton.get_state().to_bytes();
+    let key = state_key(&bytes, domain);
+    for (run, cells) in bytes.chunks_exact_mut(16).enumerate() {
+        let tweak = ctr_tweak(splitmix64(key ^ run as u64), nonce, block_index);
+        for (word, value) in cells.chunks_exact_mut(8).zip(tweak) {
+            for (byte, tweak_byte) in word.iter_mut().zip(value.to_le_bytes()) {
+                *byte ^= tweak_byte;
+            }
+        }
+    }
+    automaton.get_state_mut().set_cells(iter_bits(&bytes));
+    wipe(&mut bytes);
+}
+
+/// Returns copies of the automata keyed for block `block_index` of a message with nonce `nonce` in
+/// CTR mode. A keyed hash of the nonce and block index is xored into the state of each copy, so
+/// the keys of a block depend on neither the other blocks nor the order they are processed in,
+/// and the rounds advanced before the first layer of the block spread the tweak across the state.
+pub(crate) fn ctr_block_automata<S>(
+    nonce: u64,
+    block_index: u64,
+    shift_automata: &S,
+    transpose_automata: &S,
+) -> (S, S)
+where
+    S: KeystreamSource,
+{
+    let (mut shift_automata, mut transpose_automata) =
+        (shift_automata.clone(), transpose_automata.clone());
+    tweak_state(&mut shift_automata, SHIFT_DOMAIN, nonce, block_index);
+    tweak_state(
+        &mut transpose_automata,
+        TRANSPOSE_DOMAIN,
+        nonce,
+        block_index,
+    );
+
+    (shift_automata, transpose_automata)
+}
+
+/// Encrypts block `block_index` of a message with nonce `nonce` in place in CTR mode, with
+/// `layers` layers keyed by copies of the automata from [`ctr_block_automata`] advanced `rounds`
+/// generations before each. The automata are left unchanged.
+pub(crate) fn encrypt_ctr_block<S>(
+    block: &mut [u8],
+    nonce: u64,
+    block_index: u64,
+    rounds: u32,
+    layers: u32,
+    shift_automata: &S,
+    transpose_automata: &S,
+) where
+    S: KeystreamSource,
+{
+    let (mut shift_automata, mut transpose_automata) =
+        ctr_block_automata(nonce, block_index, shift_automata, transpose_automata);
+    encrypt_block_in_place(
+        block,
+        rounds,
+        layers,
+        &mut shift_automata,
+        &mut transpose_automata,
+    );
+}
+
+/// Decrypts a block encrypted with [`encrypt_ctr_block`] in place.
+pub(crate) fn decrypt_ctr_block<S>(
+    block: &mut [u8],
+    nonce: u64,
+    block_index: u64,
+    rounds: u32,
+    layers: u32,
+    shift_automata: &S,
+    transpose_automata: &S,
+) where
+    S: KeystreamSource,
+{
+    let (mut shift_automata, mut transpose_automata) =
+        ctr_block_automata(nonce, block_index, shift_automata, transpose_automata);
+    decrypt_block_in_place(
+        block,
+        rounds,
+        layers,
+        &mut shift_automata,
+        &mut transpose_automata,
+    );
+}
+
+/// Encrypts a byte message with a 256 bit block using the Talos algorithm in CTR mode. Rather
+/// than advancing the automata from one block to the next, each block is encrypted with copies of
+/// the given automata into which a hash of `nonce` and the block index, keyed by their states, is
+/// mixed. No two blocks of any messages under the same automata share a keyed state, and the
+/// blocks can be encrypted in any order, as `par_encrypt_message_ctr_256` does. The message is
+/// padded with [`pad_message_256`] first.
+/// Notably *DOES NOT* perform the temporal seeding as defined in RFC-1.
+pub fn encrypt_message_ctr_256<T>(
+    message: &[u8],
+    nonce: u64,
+    shift_automata: &Automaton<T>,
+    transpose_automata: &Automaton<T>,
+) -> Vec<u8>
+where
+    T: ToroidalBinaryMatrix + Clone,
+{
+    let mut data = pad_message_256(message);
+    for (i, block) in data.chunks_exact_mut(BLOCK_BYTES).enumerate() {
+        encrypt_ctr_block(
+            block,
+            nonce,
+            i as u64,
+            DEFAULT_ROUNDS,
+            DEFAULT_LAYERS,
+            shift_automata,
+            transpose_automata,
+        );
+    }
+
+    data
+}
+
+/// Decrypts a message encrypted with [`encrypt_message_ctr_256`] and removes its padding,
+/// returning a [`DecryptError`] if the ciphertext is not a whole number of blocks or the padding
+/// is invalid.
+/// Notably *DOES NOT* perform the temporal seeding as defined in RFC-1.
+pub fn decrypt_message_ctr_256<T>(
+    ciphertext: &[u8],
+    nonce: u64,
+    shift_automata: &Automaton<T>,
+    transpose_automata: &Automaton<T>,
+) -> Result<Vec<u8>, DecryptError>
+where
+    T: ToroidalBinaryMatrix + Clone,
+{
+    check_ciphertext_len(ciphertext.len())?;
+    let mut data = ciphertext.to_vec();
+    for (i, block) in data.chunks_exact_mut(BLOCK_BYTES).enumerate() {
+        decrypt_ctr_block(
+            block,
+            nonce,
+            i as u64,
+            DEFAULT_ROUNDS,
+            DEFAULT_LAYERS,
+            shift_automata,
+            transpose_automata,
+        );
+    }
+    unpad_message_256(data)
+}
+
+/// Encrypts a byte message as [`encrypt_message_ctr_256`] does, encrypting the blocks in parallel
+/// across the rayon thread pool. The ciphertext is identical to that of the sequential version.
+#[cfg(feature = "parallel")]
+pub fn par_encrypt_message_ctr_256<T>(
+    message: &[u8],
+    nonce: u64,
+    shift_automata: &Automaton<T>,
+    transpose_automata: &Automaton<T>,
+) -> Vec<u8>
+where
+    T: ToroidalBinaryMatrix + Clone + Send + Sync,
+{
+    let mut data = pad_message_256(message);
+    data.par_chunks_exact_mut(BLOCK_BYTES)
+        .enumerate()
+        .for_each(|(i, block)| {
+            encrypt_ctr_block(
+                block,
+                nonce,
+                i as u64,
+                DEFAULT_ROUNDS,
+                DEFAULT_LAYERS,
+                shift_automata,
+                transpose_automata,
+            )
+        });
+
+    data
+}
+
+/// Decrypts a message as [`decrypt_message_ctr_256`] does, decrypting the blocks in parallel
+/// across the rayon thread pool.
+#[cfg(feature = "parallel")]
+pub fn par_decrypt_message_ctr_256<T>(
+    ciphertext: &[u8],
+    nonce: u64,
+    shift_automata: &Automaton<T>,
+    transpose_automata: &Automaton<T>,
+) -> Result<Vec<u8>, DecryptError>
+where
+    T: ToroidalBinaryMatrix + Clone + Send + Sync,
+{
+    check_ciphertext_len(ciphertext.len())?;
+    let mut data = ciphertext.to_vec();
+    data.par_chunks_exact_mut(BLOCK_BYTES)
+        .enumerate()
+        .for_each(|(i, block)| {
+            decrypt_ctr_block(
+                block,
+                nonce,
+                i as u64,
+                DEFAULT_ROUNDS,
+                DEFAULT_LAYERS,
+                shift_automata,
+                transpose_automata,
+            )
+        });
+    unpad_message_256(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encrypt::{Key, TalosCipher};
+    use crate::matrix::ToroidalBitMatrix;
+    use std::collections::HashSet;
+
+    const N_NONCES: u64 = 8;
+    const N_BLOCKS: usize = 8;
+
+    fn automata() -> (Automaton<ToroidalBitMatrix>, Automaton<ToroidalBitMatrix>) {
+        let schedule = Key::new(&[7; 16]).unwrap().schedule();
+        let cipher = TalosCipher::<ToroidalBitMatrix>::new(&schedule, 0);
+        let (shift, transpose) = cipher.automata();
+        (shift.clone(), transpose.clone())
+    }
+
+    #[test]
+    fn ctr_round_trip() {
+        let (shift, transpose) = automata();
+        let message = b"a message spanning a few blocks of the CTR mode of Talos".repeat(3);
+        let ciphertext = encrypt_message_ctr_256(&message, 5, &shift, &transpose);
+        assert_ne!(ciphertext[..BLOCK_BYTES], message[..BLOCK_BYTES]);
+        assert_eq!(
+            decrypt_message_ctr_256(&ciphertext, 5, &shift, &transpose).unwrap(),
+            message
+        );
+        assert_ne!(
+            decrypt_message_ctr_256(&ciphertext, 6, &shift, &transpose).ok(),
+            Some(message)
+        );
+    }
+
+    #[test]
+    fn ctr_block_states_are_distinct() {
+        let (shift, transpose) = automata();
+        let mut states = HashSet::new();
+        for nonce in 0..N_NONCES {
+            for block_index in 0..N_BLOCKS as u64 {
+                let (shift, transpose) = ctr_block_automata(nonce, block_index, &shift, &transpose);
+                assert!(states.insert(shift.get_state().to_bytes()));
+                assert!(states.insert(transpose.get_state().to_bytes()));
+            }
+        }
+    }
+
+    #[test]
+    fn ctr_block_keys_are_distinct_across_nonces() {
+        // a zero block is only masked, so its ciphertext is the mask of its key
+        let (shift, transpose) = automata();
+        let zeros = [0_u8; N_BLOCKS * BLOCK_BYTES];
+        let mut masks = HashSet::new();
+        for nonce in 0..N_NONCES {
+            let ciphertext = encrypt_message_ctr_256(&zeros, nonce, &shift, &transpose);
+            for block in ciphertext[..zeros.len()].chunks_exact(BLOCK_BYTES) {
+                assert!(masks.insert(block.to_vec()));
+            }
+        }
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn par_ctr_matches_sequential() {
+        let (shift, transpose) = automata();
+        let message = [0x5a_u8; 5 * BLOCK_BYTES + 3];
+        let ciphertext = encrypt_message_ctr_256(&message, 9, &shift, &transpose);
+        assert_eq!(
+            par_encrypt_message_ctr_256(&message, 9, &shift, &transpose),
+            ciphertext
+        );
+        assert_eq!(
+            par_decrypt_message_ctr_256(&ciphertext, 9, &shift, &transpose).unwrap(),
+            message
+        );
+    }
+}
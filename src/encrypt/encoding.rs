@@ -0,0 +1,127 @@
+// 2025 Steven Chiacchira
+use std::{error, fmt};
+
+/// Alphabet of the standard Base64 encoding in
+/// [RFC 4648](https://datatracker.ietf.org/doc/html/rfc4648#section-4).
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+/// Character padding Base64 text to a multiple of four characters.
+const BASE64_PAD: char = '=';
+
+/// Text encoding for embedding ciphertext in text formats such as JSON or HTTP headers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CiphertextEncoding {
+    /// Two lowercase hexadecimal digits per byte. Uppercase digits are accepted when decoding.
+    Hex,
+    /// The standard padded Base64 encoding of RFC 4648.
+    Base64,
+}
+
+/// Error arising from decoding text produced by [`encode`].
+#[derive(Debug)]
+pub enum DecodeError {
+    /// Encoded text must have an even number of hexadecimal digits, or a multiple of four Base64
+    /// characters. Holds the length which was given.
+    InvalidLength(usize),
+    /// Encoded text may only hold characters of its encoding. Holds the first invalid character.
+    InvalidCharacter(char),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::InvalidLength(len) => {
+                write!(f, "encoded text has invalid length {}", len)
+            }
+            DecodeError::InvalidCharacter(c) => {
+                write!(f, "encoded text has invalid character {:?}", c)
+            }
+        }
+    }
+}
+
+impl error::Error for DecodeError {}
+
+/// Encodes `ciphertext` as text with `encoding`.
+pub fn encode(ciphertext: &[u8], encoding: CiphertextEncoding) -> String {
+    match encoding {
+        CiphertextEncoding::Hex => ciphertext
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect(),
+        CiphertextEncoding::Base64 => encode_base64(ciphertext),
+    }
+}
+
+/// Decodes `text` produced by [`encode`] with `encoding`, returning a [`DecodeError`] if it is not
+/// valid text of that encoding.
+pub fn decode(text: &str, encoding: CiphertextEncoding) -> Result<Vec<u8>, DecodeError> {
+    match encoding {
+        CiphertextEncoding::Hex => decode_hex(text),
+        CiphertextEncoding::Base64 => decode_base64(text),
+    }
+}
+
+/// Decodes hexadecimal text with two digits per byte.
+pub(crate) fn decode_hex(text: &str) -> Result<Vec<u8>, DecodeError> {
+    if !text.len().is_multiple_of(2) {
+        return Err(DecodeError::InvalidLength(text.len()));
+    }
+    let digits = text
+        .chars()
+        .map(|c| c.to_digit(16).ok_or(DecodeError::InvalidCharacter(c)))
+        .collect::<Result<Vec<u32>, _>>()?;
+
+    Ok(digits
+        .chunks_exact(2)
+        .map(|pair| (pair[0] * 16 + pair[1]) as u8)
+        .collect())
+}
+
+/// Encodes `bytes` as padded Base64, each group of three bytes becoming four characters.
+fn encode_base64(bytes: &[u8]) -> String {
+    let mut result = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for group in bytes.chunks(3) {
+        let mut padded = [0_u8; 3];
+        padded[..group.len()].copy_from_slice(group);
+        let bits = u32::from_be_bytes([0, padded[0], padded[1], padded[2]]);
+        for i in 0..4 {
+            if i <= group.len() {
+                let sextet = (bits >> (18 - 6 * i)) & 0x3f;
+                result.push(BASE64_ALPHABET[sextet as usize] as char);
+            } else {
+                result.push(BASE64_PAD);
+            }
+        }
+    }
+
+    result
+}
+
+/// Decodes padded Base64 produced by [`encode_base64`].
+fn decode_base64(text: &str) -> Result<Vec<u8>, DecodeError> {
+    if !text.len().is_multiple_of(4) {
+        return Err(DecodeError::InvalidLength(text.len()));
+    }
+    let chars = text.chars().collect::<Vec<char>>();
+    let n_padding = chars.iter().rev().take_while(|c| **c == BASE64_PAD).count();
+    if n_padding > 2 {
+        return Err(DecodeError::InvalidCharacter(BASE64_PAD));
+    }
+
+    let mut result = Vec::with_capacity(chars.len() / 4 * 3);
+    for group in chars[..chars.len() - n_padding].chunks(4) {
+        let mut bits: u32 = 0;
+        for (i, c) in group.iter().enumerate() {
+            let sextet = BASE64_ALPHABET
+                .iter()
+                .position(|a| *a as char == *c)
+                .ok_or(DecodeError::InvalidCharacter(*c))?;
+            bits |= (sextet as u32) << (18 - 6 * i);
+        }
+        // a group of n characters holds n - 1 whole bytes
+        result.extend_from_slice(&bits.to_be_bytes()[1..group.len()]);
+    }
+
+    Ok(result)
+}
@@ -0,0 +1,499 @@
+// 2025 Steven Chiacchira
+use crate::encrypt::{
+    generate_nonce_with, wipe, BlockMode, ConfigError, Crc32, DecryptError, Key, KeySchedule,
+    TalosCipher, TalosCipherBuilder, BIT_ORDER, BLOCK_BYTES, CHECKSUM_BYTES, DEFAULT_LAYERS,
+    DEFAULT_ROUNDS, NONCE_BYTES, TAG_BYTES,
+};
+use crate::matrix::{BitOrder, MatrixStorage};
+use rand::RngCore;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::{error, fmt};
+
+/// Number of bytes moved between the input and the cipher at a time.
+const COPY_BUFFER_BYTES: usize = 8 * 1024;
+
+/// Number of bytes in the header written by [`encrypt_stream`]: a byte naming the [`BIT_ORDER`]
+/// of the cipher, `0` for [`BitOrder::LsbFirst`] and `1` for [`BitOrder::MsbFirst`], followed by
+/// the [`NONCE_BYTES`] byte little-endian nonce.
+pub const STREAM_HEADER_BYTES: usize = 1 + NONCE_BYTES;
+
+/// Options for [`encrypt_file`] and [`decrypt_file`]. Decryption must use the options the
+/// ciphertext was encrypted with, except for `storage`, which never changes the output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FileOptions {
+    /// Storage used for the automaton states.
+    pub storage: MatrixStorage,
+    /// Number of generations the automata advance before each block, see
+    /// [`TalosCipher::set_rounds`].
+    pub rounds: u32,
+    /// Number of layers applied to each block, see [`TalosCipher::set_layers`].
+    pub layers: u32,
+    /// Whether to append a tag authenticating the ciphertext and its header, see
+    /// [`TalosCipher::seal`]. Authenticated ciphertext is read whole into memory when decrypted,
+    /// since nothing is written before its tag is checked.
+    /// Without authentication, a CRC-32 of the message is encrypted after it instead, which
+    /// detects accidental corruption but not deliberate modification.
+    pub authenticate: bool,
+}
+
+impl Default for FileOptions {
+    fn default() -> Self {
+        Self {
+            storage: MatrixStorage::default(),
+            rounds: DEFAULT_ROUNDS,
+            layers: DEFAULT_LAYERS,
+            authenticate: false,
+        }
+    }
+}
+
+impl FileOptions {
+    /// Returns a builder for the cipher described by these options under `schedule`, which
+    /// [`encrypt_stream_with_builder`] and [`decrypt_stream_with_builder`] accept after further
+    /// configuration.
+    pub fn builder(&self, schedule: &KeySchedule) -> TalosCipherBuilder {
+        TalosCipher::builder()
+            .schedule(schedule.clone())
+            .storage(self.storage)
+            .rounds(self.rounds)
+            .layers(self.layers)
+            .authenticate(self.authenticate)
+    }
+}
+
+/// Returns the number of bytes [`encrypt_stream`] writes for a message of `plaintext_len` bytes
+/// under `options`: the header, the message padded to a whole number of blocks, and either the
+/// tag or the checksum. The block mode does not change the length, so this also holds for
+/// [`encrypt_stream_with_builder`] with the same authentication.
+/// Ex:
+/// ```txt
+/// ciphertext_len(0, &FileOptions::default()) == 9 + 32
+/// ciphertext_len(28, &FileOptions::default()) == 9 + 64
+/// ```
+pub fn ciphertext_len(plaintext_len: u64, options: &FileOptions) -> u64 {
+    let (encrypted_len, tag_len) = if options.authenticate {
+        (plaintext_len, TAG_BYTES)
+    } else {
+        (plaintext_len + CHECKSUM_BYTES as u64, 0)
+    };
+    let block_bytes = BLOCK_BYTES as u64;
+    // padding always adds at least one byte
+    let padded_len = (encrypted_len / block_bytes + 1) * block_bytes;
+
+    STREAM_HEADER_BYTES as u64 + padded_len + tag_len as u64
+}
+
+/// Returns the largest message [`encrypt_stream`] could have written as `ciphertext_len` bytes
+/// under any options, so that a buffer of this size holds the decrypted message. The bound is
+/// reached by an unauthenticated message whose final block holds a single byte of padding.
+pub fn plaintext_len_upper_bound(ciphertext_len: u64) -> u64 {
+    ciphertext_len.saturating_sub((STREAM_HEADER_BYTES + CHECKSUM_BYTES + 1) as u64)
+}
+
+/// Error arising from encrypting or decrypting a file or stream.
+#[derive(Debug)]
+pub enum FileError {
+    /// The input could not be read or the output could not be written.
+    Io(io::Error),
+    /// The input could not be decrypted.
+    Decrypt(DecryptError),
+    /// The [`FileOptions`] or [`TalosCipherBuilder`] do not make a valid cipher.
+    Config(ConfigError),
+}
+
+impl fmt::Display for FileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FileError::Io(e) => write!(f, "I/O failed: {}", e),
+            FileError::Decrypt(e) => write!(f, "decryption failed: {}", e),
+            FileError::Config(e) => write!(f, "invalid options: {}", e),
+        }
+    }
+}
+
+impl error::Error for FileError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            FileError::Io(e) => Some(e),
+            FileError::Decrypt(e) => Some(e),
+            FileError::Config(e) => Some(e),
+        }
+    }
+}
+
+impl From<io::Error> for FileError {
+    /// Recovers the [`DecryptError`] carried by errors from
+    /// [`DecryptReader`](crate::encrypt::DecryptReader).
+    fn from(error: io::Error) -> Self {
+        if error
+            .get_ref()
+            .is_some_and(|inner| inner.is::<DecryptError>())
+        {
+            // checked above
+            let inner = error.into_inner().unwrap().downcast().unwrap();
+            return FileError::Decrypt(*inner);
+        }
+        FileError::Io(error)
+    }
+}
+
+impl From<DecryptError> for FileError {
+    fn from(error: DecryptError) -> Self {
+        FileError::Decrypt(error)
+    }
+}
+
+impl From<ConfigError> for FileError {
+    fn from(error: ConfigError) -> Self {
+        FileError::Config(error)
+    }
+}
+
+/// Encrypts the file at `path_in` under `key` into a new file at `path_out`, as
+/// [`encrypt_stream`] does.
+pub fn encrypt_file<P, Q>(
+    path_in: P,
+    path_out: Q,
+    key: &Key,
+    options: &FileOptions,
+) -> Result<(), FileError>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    let input = BufReader::new(File::open(path_in)?);
+    let output = BufWriter::new(File::create(path_out)?);
+
+    encrypt_stream(input, output, &key.schedule(), options)
+}
+
+/// Decrypts the file at `path_in` written by [`encrypt_file`] under `key` into a new file at
+/// `path_out`, as [`decrypt_stream`] does.
+pub fn decrypt_file<P, Q>(
+    path_in: P,
+    path_out: Q,
+    key: &Key,
+    options: &FileOptions,
+) -> Result<(), FileError>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    let input = BufReader::new(File::open(path_in)?);
+    let output = BufWriter::new(File::create(path_out)?);
+
+    decrypt_stream(input, output, &key.schedule(), options)
+}
+
+/// Encrypts everything read from `input` under `schedule` and writes it to `output`, preceded by
+/// a [`STREAM_HEADER_BYTES`] byte header holding the bit order and a freshly generated nonce.
+/// The result can be decrypted by [`decrypt_stream`] with the same `options`.
+pub fn encrypt_stream<R, W>(
+    input: R,
+    output: W,
+    schedule: &KeySchedule,
+    options: &FileOptions,
+) -> Result<(), FileError>
+where
+    R: Read,
+    W: Write,
+{
+    encrypt_stream_with_rng(input, output, schedule, options, &mut rand::rng())
+}
+
+/// Encrypts as [`encrypt_stream`] does, drawing the nonce from `rng` with
+/// [`generate_nonce_with`].
+pub fn encrypt_stream_with_rng<R, W, G>(
+    input: R,
+    output: W,
+    schedule: &KeySchedule,
+    options: &FileOptions,
+    rng: &mut G,
+) -> Result<(), FileError>
+where
+    R: Read,
+    W: Write,
+    G: RngCore + ?Sized,
+{
+    encrypt_stream_with_builder(input, output, &options.builder(schedule), rng)
+}
+
+/// Encrypts as [`encrypt_stream_with_rng`] does, with the cipher built by `builder` for the
+/// generated nonce, which replaces any nonce set on it. The message is sealed as it is streamed
+/// when the builder authenticates, see [`TalosCipherBuilder::authenticate`], and is read whole
+/// into memory rather than streamed in [`BlockMode::Ctr`].
+pub fn encrypt_stream_with_builder<R, W, G>(
+    mut input: R,
+    mut output: W,
+    builder: &TalosCipherBuilder,
+    rng: &mut G,
+) -> Result<(), FileError>
+where
+    R: Read,
+    W: Write,
+    G: RngCore + ?Sized,
+{
+    let nonce = generate_nonce_with(rng);
+    let header = stream_header(nonce);
+    let mut cipher = builder.clone().nonce(nonce).build_dyn()?;
+    output.write_all(&header)?;
+
+    if cipher.get_mode() == BlockMode::Ctr {
+        let mut data = Vec::new();
+        input.read_to_end(&mut data)?;
+        if cipher.is_authenticated() {
+            // the header is bound into the tag, so a swapped nonce is detected as well
+            output.write_all(&cipher.seal(&data, &header))?;
+        } else {
+            let mut checksum = Crc32::new();
+            checksum.update(&data);
+            data.extend_from_slice(&checksum.finish().to_le_bytes());
+            output.write_all(&cipher.encrypt(&data))?;
+        }
+        wipe(&mut data);
+        output.flush()?;
+    } else if cipher.is_authenticated() {
+        let mut writer = cipher.seal_writer(output, &header);
+        copy_plaintext(&mut input, &mut writer, |_| {})?;
+        writer.finish()?;
+    } else {
+        let mut writer = cipher.encrypt_writer(output);
+        let mut checksum = Crc32::new();
+        copy_plaintext(&mut input, &mut writer, |piece| checksum.update(piece))?;
+        writer.write_all(&checksum.finish().to_le_bytes())?;
+        writer.finish()?;
+    }
+
+    Ok(())
+}
+
+/// Decrypts a ciphertext written by [`encrypt_stream`] from `input` under `schedule` and writes
+/// the message to `output`. Without authentication the message is streamed, so `output` may hold
+/// part of it when a [`FileError::Decrypt`] is returned, and a message which does not match its
+/// checksum gives a [`DecryptError::CorruptCiphertext`]. With authentication nothing is written
+/// unless the tag matches.
+pub fn decrypt_stream<R, W>(
+    input: R,
+    output: W,
+    schedule: &KeySchedule,
+    options: &FileOptions,
+) -> Result<(), FileError>
+where
+    R: Read,
+    W: Write,
+{
+    decrypt_stream_with_builder(input, output, &options.builder(schedule))
+}
+
+/// Decrypts a ciphertext written by [`encrypt_stream_with_builder`] as [`decrypt_stream`] does,
+/// with the cipher built by `builder` for the nonce in the header. In [`BlockMode::Ctr`] the
+/// ciphertext is read whole and nothing is written unless its checksum matches.
+pub fn decrypt_stream_with_builder<R, W>(
+    mut input: R,
+    mut output: W,
+    builder: &TalosCipherBuilder,
+) -> Result<(), FileError>
+where
+    R: Read,
+    W: Write,
+{
+    let (header, nonce) = read_stream_header(&mut input)?;
+    let mut cipher = builder.clone().nonce(nonce).build_dyn()?;
+
+    if cipher.is_authenticated() {
+        let mut data = Vec::new();
+        input.read_to_end(&mut data)?;
+        output.write_all(&cipher.open(&data, &header)?)?;
+    } else if cipher.get_mode() == BlockMode::Ctr {
+        let mut data = Vec::new();
+        input.read_to_end(&mut data)?;
+        let mut message = cipher.decrypt(&data)?;
+        let split = message
+            .len()
+            .checked_sub(CHECKSUM_BYTES)
+            .ok_or(DecryptError::CorruptCiphertext())?;
+        let (body, stored) = message.split_at(split);
+        let mut checksum = Crc32::new();
+        checksum.update(body);
+        // the slice holds the checksum bytes
+        let matches = u32::from_le_bytes(stored.try_into().unwrap()) == checksum.finish();
+        if matches {
+            output.write_all(body)?;
+        }
+        wipe(&mut message);
+        if !matches {
+            return Err(DecryptError::CorruptCiphertext().into());
+        }
+    } else {
+        let mut reader = cipher.decrypt_reader(input);
+        let mut checksum = Crc32::new();
+        let mut buffer = [0_u8; COPY_BUFFER_BYTES];
+        // the last bytes read may be the checksum, so they are held back until more arrive
+        let mut held = Vec::with_capacity(COPY_BUFFER_BYTES + CHECKSUM_BYTES);
+        let result = loop {
+            let n_read = match reader.read(&mut buffer) {
+                Ok(0) => break Ok(()),
+                Ok(n_read) => n_read,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => break Err(e),
+            };
+            held.extend_from_slice(&buffer[..n_read]);
+            let n_released = held.len().saturating_sub(CHECKSUM_BYTES);
+            checksum.update(&held[..n_released]);
+            if let Err(e) = output.write_all(&held[..n_released]) {
+                break Err(e);
+            }
+            held.drain(..n_released);
+        };
+        let stored = <[u8; CHECKSUM_BYTES]>::try_from(held.as_slice()).ok();
+        wipe(&mut buffer);
+        wipe(&mut held);
+        result?;
+        if stored.map(u32::from_le_bytes) != Some(checksum.finish()) {
+            return Err(DecryptError::CorruptCiphertext().into());
+        }
+    }
+    output.flush()?;
+
+    Ok(())
+}
+
+/// Copies everything read from `input` into `writer`, passing each piece to `inspect` before it is
+/// written, and wipes the copy buffer afterwards.
+fn copy_plaintext<R, W, F>(input: &mut R, writer: &mut W, mut inspect: F) -> io::Result<()>
+where
+    R: Read,
+    W: Write,
+    F: FnMut(&[u8]),
+{
+    let mut buffer = [0_u8; COPY_BUFFER_BYTES];
+    let result = loop {
+        let n_read = match input.read(&mut buffer) {
+            Ok(0) => break Ok(()),
+            Ok(n_read) => n_read,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => break Err(e),
+        };
+        inspect(&buffer[..n_read]);
+        if let Err(e) = writer.write_all(&buffer[..n_read]) {
+            break Err(e);
+        }
+    };
+    wipe(&mut buffer);
+
+    result
+}
+
+/// Returns the header written by [`encrypt_stream`] before a ciphertext with `nonce`.
+fn stream_header(nonce: u64) -> [u8; STREAM_HEADER_BYTES] {
+    let mut header = [0_u8; STREAM_HEADER_BYTES];
+    header[0] = match BIT_ORDER {
+        BitOrder::LsbFirst => 0,
+        BitOrder::MsbFirst => 1,
+    };
+    header[1..].copy_from_slice(&nonce.to_le_bytes());
+    header
+}
+
+/// Reads a header written by [`stream_header`] from `input`, returning it with its nonce. Gives a
+/// [`DecryptError::TruncatedCiphertext`] if `input` ends before the header does, or a
+/// [`DecryptError::UnsupportedBitOrder`] if it names a bit order other than [`BIT_ORDER`].
+fn read_stream_header<R>(input: &mut R) -> Result<([u8; STREAM_HEADER_BYTES], u64), FileError>
+where
+    R: Read,
+{
+    let mut header = [0_u8; STREAM_HEADER_BYTES];
+    input.read_exact(&mut header).map_err(|e| match e.kind() {
+        io::ErrorKind::UnexpectedEof => FileError::from(DecryptError::TruncatedCiphertext()),
+        _ => FileError::from(e),
+    })?;
+    if header[0] != stream_header(0)[0] {
+        return Err(DecryptError::UnsupportedBitOrder(header[0]).into());
+    }
+
+    // the slice holds the nonce bytes
+    let nonce = u64::from_le_bytes(header[1..].try_into().unwrap());
+    Ok((header, nonce))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_header_is_truncated_ciphertext() {
+        let schedule = Key::new(&[7; 16]).unwrap().schedule();
+        let header = stream_header(42);
+        for len in 0..STREAM_HEADER_BYTES {
+            let result = decrypt_stream(
+                &header[..len],
+                io::sink(),
+                &schedule,
+                &FileOptions::default(),
+            );
+            assert!(matches!(
+                result,
+                Err(FileError::Decrypt(DecryptError::TruncatedCiphertext()))
+            ));
+        }
+    }
+
+    fn encrypt_with(mode: BlockMode, message: &[u8]) -> (TalosCipherBuilder, Vec<u8>) {
+        let schedule = Key::new(&[7; 16]).unwrap().schedule();
+        let builder = FileOptions::default().builder(&schedule).mode(mode);
+        let mut ciphertext = Vec::new();
+        encrypt_stream_with_builder(message, &mut ciphertext, &builder, &mut rand::rng()).unwrap();
+        (builder, ciphertext)
+    }
+
+    #[test]
+    fn corrupted_message_fails_checksum() {
+        let message = [0x42_u8; 3 * BLOCK_BYTES + 7];
+        for mode in [BlockMode::Chained, BlockMode::Ctr] {
+            let (builder, ciphertext) = encrypt_with(mode, &message);
+            let mut decrypted = Vec::new();
+            decrypt_stream_with_builder(&ciphertext[..], &mut decrypted, &builder).unwrap();
+            assert_eq!(decrypted, message);
+
+            let mut corrupted = ciphertext.clone();
+            corrupted[STREAM_HEADER_BYTES + BLOCK_BYTES + 3] ^= 0x01;
+            let result = decrypt_stream_with_builder(&corrupted[..], io::sink(), &builder);
+            assert!(matches!(
+                result,
+                Err(FileError::Decrypt(DecryptError::CorruptCiphertext()))
+            ));
+        }
+    }
+
+    #[test]
+    fn truncated_message_is_rejected() {
+        let message = [0x42_u8; 3 * BLOCK_BYTES + 7];
+        for mode in [BlockMode::Chained, BlockMode::Ctr] {
+            let (builder, ciphertext) = encrypt_with(mode, &message);
+            let result = decrypt_stream_with_builder(
+                &ciphertext[..ciphertext.len() - 5],
+                io::sink(),
+                &builder,
+            );
+            assert!(matches!(
+                result,
+                Err(FileError::Decrypt(DecryptError::TruncatedCiphertext()))
+            ));
+
+            // the remaining blocks rarely end in valid padding, and then their checksum differs
+            let result = decrypt_stream_with_builder(
+                &ciphertext[..ciphertext.len() - BLOCK_BYTES],
+                io::sink(),
+                &builder,
+            );
+            assert!(matches!(
+                result,
+                Err(FileError::Decrypt(
+                    DecryptError::InvalidPadding() | DecryptError::CorruptCiphertext()
+                ))
+            ));
+        }
+    }
+}
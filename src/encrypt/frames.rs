@@ -0,0 +1,474 @@
+// 2025 Steven Chiacchira
+//! Framing of ciphertext for transport over pipes or message queues which may reorder, duplicate
+//! or drop pieces of it. Each [`Frame`] carries a sequence number, a flag marking the final frame
+//! and a length-prefixed payload, so that [`FrameAssembler`] can put the ciphertext back together
+//! and report anything missing.
+//!
+//! With the `parallel` feature, `encrypt_framed_ctr_parallel` and `decrypt_framed_ctr_parallel`
+//! encrypt a message in CTR mode straight into frames and decrypt it back from frames received in
+//! any order, processing the blocks across the rayon thread pool.
+//!
+//! Frames are not authenticated. Corruption within a payload is left to the ciphertext itself,
+//! for example through [`TalosCipher::seal`](crate::encrypt::TalosCipher::seal).
+#[cfg(feature = "parallel")]
+use crate::automata::Automaton;
+#[cfg(feature = "parallel")]
+use crate::encrypt::{par_decrypt_message_ctr_256, par_encrypt_message_ctr_256};
+#[cfg(feature = "parallel")]
+use crate::matrix::ToroidalBinaryMatrix;
+use std::collections::BTreeMap;
+use std::io::{self, Read, Write};
+use std::{error, fmt};
+
+/// Number of bytes in the header of an encoded frame: the sequence number as a little-endian
+/// `u64`, the payload length as a little-endian `u32` and a flags byte.
+pub const FRAME_HEADER_BYTES: usize = 8 + 4 + 1;
+
+/// Flag set on the final frame of a ciphertext.
+const LAST_FRAME_FLAG: u8 = 1;
+
+/// Error arising from decoding or reassembling frames.
+#[derive(Debug)]
+pub enum FrameError {
+    /// An encoded frame must hold its whole header and payload.
+    TruncatedFrame(),
+    /// An encoded frame may only set known flags. Holds the flags byte which was given.
+    InvalidFlags(u8),
+    /// Each sequence number may only be received once. Holds the repeated sequence number.
+    DuplicateFrame(u64),
+    /// No frame may follow the final frame. Holds the sequence number past the end.
+    FrameAfterLast(u64),
+    /// Only one frame may be marked as the final frame. Holds the sequence number of the second
+    /// frame marked final.
+    ConflictingLastFrame(u64),
+    /// Every frame up to the final frame must be received. Holds the first sequence number which
+    /// was not received.
+    MissingFrame(u64),
+}
+
+impl fmt::Display for FrameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FrameError::TruncatedFrame() => write!(f, "frame is shorter than its header states"),
+            FrameError::InvalidFlags(flags) => write!(f, "frame has unknown flags {:#04x}", flags),
+            FrameError::DuplicateFrame(sequence) => {
+                write!(f, "frame {} was received more than once", sequence)
+            }
+            FrameError::FrameAfterLast(sequence) => {
+                write!(f, "frame {} follows the final frame", sequence)
+            }
+            FrameError::ConflictingLastFrame(sequence) => write!(
+                f,
+                "frame {} is marked final after another frame was",
+                sequence
+            ),
+            FrameError::MissingFrame(sequence) => write!(f, "frame {} is missing", sequence),
+        }
+    }
+}
+
+impl error::Error for FrameError {}
+
+/// A piece of ciphertext numbered by its position.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Frame {
+    /// Position of the frame, counting from 0.
+    pub sequence: u64,
+    /// Whether this is the final frame of the ciphertext.
+    pub last: bool,
+    /// Bytes of the ciphertext carried by the frame.
+    pub payload: Vec<u8>,
+}
+
+impl Frame {
+    /// Encodes the frame as its [`FRAME_HEADER_BYTES`] byte header followed by its payload.
+    ///
+    /// # Panics
+    /// If the payload is longer than `u32::MAX` bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut result = Vec::with_capacity(FRAME_HEADER_BYTES + self.payload.len());
+        result.extend_from_slice(&self.header());
+        result.extend_from_slice(&self.payload);
+
+        result
+    }
+
+    /// Decodes the frame at the start of `bytes` written by [`Frame::to_bytes`], returning it
+    /// with the number of bytes it took up.
+    pub fn from_bytes(bytes: &[u8]) -> Result<(Self, usize), FrameError> {
+        if bytes.len() < FRAME_HEADER_BYTES {
+            return Err(FrameError::TruncatedFrame());
+        }
+        // the slice has the length of a header
+        let (sequence, len, last) = parse_header(bytes[..FRAME_HEADER_BYTES].try_into().unwrap())?;
+        let end = FRAME_HEADER_BYTES + len;
+        if bytes.len() < end {
+            return Err(FrameError::TruncatedFrame());
+        }
+
+        let frame = Self {
+            sequence,
+            last,
+            payload: bytes[FRAME_HEADER_BYTES..end].to_vec(),
+        };
+        Ok((frame, end))
+    }
+
+    /// Returns the encoded header of the frame.
+    fn header(&self) -> [u8; FRAME_HEADER_BYTES] {
+        let len = u32::try_from(self.payload.len()).expect("frame payload exceeds u32::MAX bytes");
+        let mut header = [0_u8; FRAME_HEADER_BYTES];
+        header[..8].copy_from_slice(&self.sequence.to_le_bytes());
+        header[8..12].copy_from_slice(&len.to_le_bytes());
+        header[12] = if self.last { LAST_FRAME_FLAG } else { 0 };
+
+        header
+    }
+}
+
+/// Splits `header` into the sequence number, payload length and final frame flag.
+fn parse_header(header: &[u8; FRAME_HEADER_BYTES]) -> Result<(u64, usize, bool), FrameError> {
+    // the slices have the lengths of the integers they hold
+    let sequence = u64::from_le_bytes(header[..8].try_into().unwrap());
+    let len = u32::from_le_bytes(header[8..12].try_into().unwrap()) as usize;
+    let flags = header[12];
+    if flags & !LAST_FRAME_FLAG != 0 {
+        return Err(FrameError::InvalidFlags(flags));
+    }
+
+    Ok((sequence, len, flags == LAST_FRAME_FLAG))
+}
+
+/// Splits `ciphertext` into frames with payloads of at most `max_payload` bytes, numbered from 0.
+/// An empty `ciphertext` gives a single empty final frame.
+///
+/// # Panics
+/// If `max_payload` is 0.
+pub fn split_frames(ciphertext: &[u8], max_payload: usize) -> Vec<Frame> {
+    assert!(max_payload > 0, "frames must be able to carry a payload");
+    if ciphertext.is_empty() {
+        return vec![Frame {
+            sequence: 0,
+            last: true,
+            payload: Vec::new(),
+        }];
+    }
+
+    let n_frames = ciphertext.len().div_ceil(max_payload);
+    ciphertext
+        .chunks(max_payload)
+        .enumerate()
+        .map(|(i, chunk)| Frame {
+            sequence: i as u64,
+            last: i + 1 == n_frames,
+            payload: chunk.to_vec(),
+        })
+        .collect()
+}
+
+/// Reassembles the ciphertext split by [`split_frames`] from `frames` given in any order.
+pub fn reassemble_frames<I>(frames: I) -> Result<Vec<u8>, FrameError>
+where
+    I: IntoIterator<Item = Frame>,
+{
+    let mut assembler = FrameAssembler::new();
+    for frame in frames {
+        assembler.push(frame)?;
+    }
+
+    assembler.finish()
+}
+
+/// Returns the number of bytes written by [`write_frame`] for every frame [`split_frames`] makes
+/// of a ciphertext of `ciphertext_len` bytes: the ciphertext itself and a [`FRAME_HEADER_BYTES`]
+/// byte header per frame.
+///
+/// # Panics
+/// If `max_payload` is 0.
+pub fn framed_len(ciphertext_len: usize, max_payload: usize) -> usize {
+    assert!(max_payload > 0, "frames must be able to carry a payload");
+    // an empty ciphertext still takes a single empty frame
+    let n_frames = ciphertext_len.div_ceil(max_payload).max(1);
+
+    ciphertext_len + n_frames * FRAME_HEADER_BYTES
+}
+
+/// Writes `frame` to `writer` as [`Frame::to_bytes`] encodes it.
+pub fn write_frame<W>(writer: &mut W, frame: &Frame) -> io::Result<()>
+where
+    W: Write,
+{
+    writer.write_all(&frame.header())?;
+    writer.write_all(&frame.payload)
+}
+
+/// Reads the next frame written by [`write_frame`] from `reader`, returning `None` if `reader`
+/// ends before the frame starts. A frame cut short gives an [`io::ErrorKind::UnexpectedEof`]
+/// error, and a frame with unknown flags an [`io::ErrorKind::InvalidData`] error carrying a
+/// [`FrameError`].
+pub fn read_frame<R>(reader: &mut R) -> io::Result<Option<Frame>>
+where
+    R: Read,
+{
+    let mut header = [0_u8; FRAME_HEADER_BYTES];
+    let mut n_read = 0;
+    while n_read < FRAME_HEADER_BYTES {
+        match reader.read(&mut header[n_read..]) {
+            Ok(0) if n_read == 0 => return Ok(None),
+            Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+            Ok(n) => n_read += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    let (sequence, len, last) =
+        parse_header(&header).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+    // read incrementally, so a corrupted length cannot allocate more than is actually sent
+    let mut payload = Vec::new();
+    reader.take(len as u64).read_to_end(&mut payload)?;
+    if payload.len() < len {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+
+    Ok(Some(Frame {
+        sequence,
+        last,
+        payload,
+    }))
+}
+
+/// Puts frames received in any order back together into a ciphertext. Frames are held until
+/// every frame before them has arrived.
+#[derive(Debug, Clone, Default)]
+pub struct FrameAssembler {
+    /// Ciphertext of every frame before `next_sequence`.
+    assembled: Vec<u8>,
+    /// Sequence number of the next frame to be appended to `assembled`.
+    next_sequence: u64,
+    /// Frames received ahead of `next_sequence`.
+    pending: BTreeMap<u64, Frame>,
+    /// Sequence number of the final frame, once it has been received.
+    last_sequence: Option<u64>,
+}
+
+impl FrameAssembler {
+    /// Creates a new assembler which has received no frames.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `frame`, returning a [`FrameError::DuplicateFrame`] if its sequence number was already
+    /// received, a [`FrameError::FrameAfterLast`] if it lies past the final frame and a
+    /// [`FrameError::ConflictingLastFrame`] if it is marked final after another frame was.
+    pub fn push(&mut self, frame: Frame) -> Result<(), FrameError> {
+        let sequence = frame.sequence;
+        if sequence < self.next_sequence || self.pending.contains_key(&sequence) {
+            return Err(FrameError::DuplicateFrame(sequence));
+        }
+        let highest = self.pending.keys().next_back().copied();
+        match (self.last_sequence, frame.last) {
+            (Some(last), _) if sequence > last => return Err(FrameError::FrameAfterLast(sequence)),
+            (Some(_), true) => return Err(FrameError::ConflictingLastFrame(sequence)),
+            (None, true) if highest.is_some_and(|highest| highest > sequence) => {
+                // checked to be some above
+                return Err(FrameError::FrameAfterLast(highest.unwrap()));
+            }
+            (_, true) => self.last_sequence = Some(sequence),
+            _ => {}
+        }
+
+        self.pending.insert(sequence, frame);
+        while let Some(next) = self.pending.remove(&self.next_sequence) {
+            self.assembled.extend_from_slice(&next.payload);
+            self.next_sequence += 1;
+        }
+
+        Ok(())
+    }
+
+    /// Returns whether every frame up to and including the final frame has been received.
+    pub fn is_complete(&self) -> bool {
+        self.last_sequence
+            .is_some_and(|last| self.next_sequence == last + 1)
+    }
+
+    /// Returns the reassembled ciphertext, or a [`FrameError::MissingFrame`] naming the first
+    /// frame which has not been received.
+    pub fn finish(self) -> Result<Vec<u8>, FrameError> {
+        if !self.is_complete() {
+            return Err(FrameError::MissingFrame(self.next_sequence));
+        }
+
+        Ok(self.assembled)
+    }
+}
+
+/// Encrypts `message` with [`par_encrypt_message_ctr_256`] and splits the ciphertext into frames
+/// with payloads of at most `max_payload` bytes, as [`split_frames`] does. The frames can be
+/// decrypted by [`decrypt_framed_ctr_parallel`].
+///
+/// # Panics
+/// If `max_payload` is 0.
+#[cfg(feature = "parallel")]
+pub fn encrypt_framed_ctr_parallel<T>(
+    message: &[u8],
+    nonce: u64,
+    max_payload: usize,
+    shift_automata: &Automaton<T>,
+    transpose_automata: &Automaton<T>,
+) -> Vec<Frame>
+where
+    T: ToroidalBinaryMatrix + Clone + Send + Sync,
+{
+    let ciphertext =
+        par_encrypt_message_ctr_256(message, nonce, shift_automata, transpose_automata);
+    split_frames(&ciphertext, max_payload)
+}
+
+/// Reassembles the frames written by [`encrypt_framed_ctr_parallel`] from `frames` given in any
+/// order and decrypts the ciphertext with [`par_decrypt_message_ctr_256`]. Every sequence number
+/// is checked before any block is decrypted, so a duplicate, missing or extra frame gives an
+/// [`Error::Frame`](crate::Error::Frame) and nothing is decrypted. A ciphertext which cannot be
+/// decrypted gives an [`Error::Decrypt`](crate::Error::Decrypt).
+#[cfg(feature = "parallel")]
+pub fn decrypt_framed_ctr_parallel<T, I>(
+    frames: I,
+    nonce: u64,
+    shift_automata: &Automaton<T>,
+    transpose_automata: &Automaton<T>,
+) -> Result<Vec<u8>, crate::Error>
+where
+    T: ToroidalBinaryMatrix + Clone + Send + Sync,
+    I: IntoIterator<Item = Frame>,
+{
+    let ciphertext = reassemble_frames(frames)?;
+    Ok(par_decrypt_message_ctr_256(
+        &ciphertext,
+        nonce,
+        shift_automata,
+        transpose_automata,
+    )?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CIPHERTEXT: &[u8] = b"sixty-four bytes of ciphertext, give or take a couple of bytes..";
+
+    #[test]
+    fn frames_reassemble_in_any_order() {
+        let mut frames = split_frames(CIPHERTEXT, 10);
+        assert_eq!(frames.len(), 7);
+        assert!(frames
+            .iter()
+            .all(|frame| frame.last == (frame.sequence == 6)));
+        frames.reverse();
+        frames.swap(2, 5);
+        assert_eq!(reassemble_frames(frames).unwrap(), CIPHERTEXT);
+    }
+
+    #[test]
+    fn frames_round_trip_through_bytes() {
+        let mut encoded = Vec::new();
+        for frame in split_frames(CIPHERTEXT, 16) {
+            write_frame(&mut encoded, &frame).unwrap();
+        }
+        assert_eq!(encoded.len(), framed_len(CIPHERTEXT.len(), 16));
+
+        let mut reader = &encoded[..];
+        let mut frames = Vec::new();
+        while let Some(frame) = read_frame(&mut reader).unwrap() {
+            let (decoded, len) = Frame::from_bytes(&frame.to_bytes()).unwrap();
+            assert_eq!(len, FRAME_HEADER_BYTES + frame.payload.len());
+            assert_eq!(decoded, frame);
+            frames.push(frame);
+        }
+        assert_eq!(reassemble_frames(frames).unwrap(), CIPHERTEXT);
+    }
+
+    #[test]
+    fn missing_frames_are_reported() {
+        let mut frames = split_frames(CIPHERTEXT, 10);
+        frames.remove(3);
+        assert!(matches!(
+            reassemble_frames(frames.clone()),
+            Err(FrameError::MissingFrame(3))
+        ));
+        // without the final frame the end of the ciphertext is unknown
+        frames.pop();
+        let mut assembler = FrameAssembler::new();
+        for frame in frames {
+            assembler.push(frame).unwrap();
+        }
+        assert!(!assembler.is_complete());
+        assert!(matches!(
+            assembler.finish(),
+            Err(FrameError::MissingFrame(3))
+        ));
+    }
+
+    #[test]
+    fn duplicate_and_extra_frames_are_rejected() {
+        let frames = split_frames(CIPHERTEXT, 10);
+        let mut assembler = FrameAssembler::new();
+        assembler.push(frames[0].clone()).unwrap();
+        assembler.push(frames[2].clone()).unwrap();
+        assert!(matches!(
+            assembler.push(frames[0].clone()),
+            Err(FrameError::DuplicateFrame(0))
+        ));
+        assert!(matches!(
+            assembler.push(frames[2].clone()),
+            Err(FrameError::DuplicateFrame(2))
+        ));
+
+        assembler.push(frames[6].clone()).unwrap();
+        let extra = Frame {
+            sequence: 7,
+            last: false,
+            payload: vec![0],
+        };
+        assert!(matches!(
+            assembler.push(extra),
+            Err(FrameError::FrameAfterLast(7))
+        ));
+    }
+
+    #[test]
+    fn truncated_encodings_are_rejected() {
+        let encoded = split_frames(CIPHERTEXT, 16)[0].to_bytes();
+        for len in [FRAME_HEADER_BYTES - 1, encoded.len() - 1] {
+            assert!(matches!(
+                Frame::from_bytes(&encoded[..len]),
+                Err(FrameError::TruncatedFrame())
+            ));
+            let error = read_frame(&mut &encoded[..len]).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+        }
+        assert!(read_frame(&mut &encoded[..0]).unwrap().is_none());
+    }
+
+    #[test]
+    fn second_last_frame_is_rejected() {
+        let frames = split_frames(CIPHERTEXT, 10);
+        let mut assembler = FrameAssembler::new();
+        assembler.push(frames[4].clone()).unwrap();
+        assembler.push(frames[6].clone()).unwrap();
+        let early_last = Frame {
+            last: true,
+            ..frames[2].clone()
+        };
+        assert!(matches!(
+            assembler.push(early_last),
+            Err(FrameError::ConflictingLastFrame(2))
+        ));
+        // the rejected frame leaves the assembler as it was
+        for i in [0, 1, 2, 3, 5] {
+            assert!(!assembler.is_complete());
+            assembler.push(frames[i].clone()).unwrap();
+        }
+        assert_eq!(assembler.finish().unwrap(), CIPHERTEXT);
+    }
+}
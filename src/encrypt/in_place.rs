@@ -0,0 +1,597 @@
+// 2025 Steven Chiacchira
+use crate::automata::{Automaton, KeystreamSource};
+use crate::encrypt::{
+    scramble_permutations_256, shift_amounts_256, wipe, BlockTracer, BLOCK_BYTES, DEFAULT_LAYERS,
+    DEFAULT_ROUNDS,
+};
+use crate::matrix::{ToroidalBinaryMatrix, ToroidalBoolMatrix};
+#[cfg(feature = "parallel")]
+use {
+    crate::encrypt::{check_ciphertext_len, pad_message_256, unpad_message_256, DecryptError},
+    rayon::prelude::*,
+};
+
+/// Number of rows and columns in a message block.
+const BLOCK_SIDE: usize = 16;
+
+/// Number of blocks whose layer keys are derived ahead of encryption by
+/// [`encrypt_message_parallel`], bounding the memory held by derived keys.
+#[cfg(feature = "parallel")]
+const PARALLEL_BATCH_BLOCKS: usize = 1024;
+
+/// The pair of automata driving the Talos algorithm, as used by [`encrypt_in_place`] and
+/// [`decrypt_in_place`].
+#[derive(Debug, Clone)]
+pub struct TalosState<T = ToroidalBoolMatrix>
+where
+    T: ToroidalBinaryMatrix + Clone,
+{
+    pub shift_automata: Automaton<T>,
+    pub transpose_automata: Automaton<T>,
+}
+
+impl<T> TalosState<T>
+where
+    T: ToroidalBinaryMatrix + Clone,
+{
+    /// Creates a new state from already seeded automata.
+    pub fn new(shift_automata: Automaton<T>, transpose_automata: Automaton<T>) -> Self {
+        Self {
+            shift_automata,
+            transpose_automata,
+        }
+    }
+}
+
+/// Encrypts `data` in place with the Talos algorithm. When `data` is a message padded with
+/// [`pad_message_256`](crate::encrypt::pad_message_256), this produces the same bytes as
+/// [`encrypt_message_256`](crate::encrypt::encrypt_message_256) on the unpadded message.
+/// The blocks are permuted and xored as rows of 16 bit words, so no per-bit buffers are
+/// allocated.
+/// Notably *DOES NOT* pad `data` or perform the temporal seeding as defined in RFC-1.
+///
+/// # Panics
+/// Panics if the length of `data` is not a multiple of the 32 byte block size.
+pub fn encrypt_in_place<T>(data: &mut [u8], state: &mut TalosState<T>)
+where
+    T: ToroidalBinaryMatrix + Clone,
+{
+    assert!(
+        data.len().is_multiple_of(BLOCK_BYTES),
+        "data must be a whole number of blocks"
+    );
+    for block in data.chunks_exact_mut(BLOCK_BYTES) {
+        encrypt_block_in_place(
+            block,
+            DEFAULT_ROUNDS,
+            DEFAULT_LAYERS,
+            &mut state.shift_automata,
+            &mut state.transpose_automata,
+        );
+    }
+}
+
+/// Decrypts `data` encrypted with [`encrypt_in_place`] in place. The padding is left in place.
+/// Notably *DOES NOT* perform the temporal seeding as defined in RFC-1.
+///
+/// # Panics
+/// Panics if the length of `data` is not a multiple of the 32 byte block size.
+pub fn decrypt_in_place<T>(data: &mut [u8], state: &mut TalosState<T>)
+where
+    T: ToroidalBinaryMatrix + Clone,
+{
+    assert!(
+        data.len().is_multiple_of(BLOCK_BYTES),
+        "data must be a whole number of blocks"
+    );
+    for block in data.chunks_exact_mut(BLOCK_BYTES) {
+        decrypt_block_in_place(
+            block,
+            DEFAULT_ROUNDS,
+            DEFAULT_LAYERS,
+            &mut state.shift_automata,
+            &mut state.transpose_automata,
+        );
+    }
+}
+
+/// Encrypts a byte message as [`encrypt_message_256`](crate::encrypt::encrypt_message_256) does,
+/// producing the same ciphertext. The generations of each automaton form a serial chain, so the
+/// layer keys of a batch of blocks are derived ahead of time, with the shift and transpose chains
+/// advanced side by side, while the blocks of the previous batch are scrambled, shifted and masked
+/// in parallel across the rayon thread pool. Advancing the automata dominates the cost, so at most
+/// about twice the sequential throughput can be expected.
+/// Notably *DOES NOT* perform the temporal seeding as defined in RFC-1.
+#[cfg(feature = "parallel")]
+pub fn encrypt_message_parallel<T>(
+    message: &[u8],
+    shift_automata: &mut Automaton<T>,
+    transpose_automata: &mut Automaton<T>,
+) -> Vec<u8>
+where
+    T: ToroidalBinaryMatrix + Clone + Send,
+{
+    let mut data = pad_message_256(message);
+    par_encrypt_blocks_in_place(
+        &mut data,
+        DEFAULT_ROUNDS,
+        DEFAULT_LAYERS,
+        shift_automata,
+        transpose_automata,
+    );
+
+    data
+}
+
+/// Decrypts a message encrypted with [`encrypt_message_parallel`] or
+/// [`encrypt_message_256`](crate::encrypt::encrypt_message_256) and removes its padding as
+/// [`decrypt_message_256`](crate::encrypt::decrypt_message_256) does, returning the same result.
+/// The layer keys of a batch of blocks are derived ahead of time while the blocks of the previous
+/// batch are unmasked, unshifted and unscrambled in parallel, as in [`encrypt_message_parallel`].
+/// Notably *DOES NOT* perform the temporal seeding as defined in RFC-1.
+#[cfg(feature = "parallel")]
+pub fn decrypt_message_parallel<T>(
+    ciphertext: &[u8],
+    shift_automata: &mut Automaton<T>,
+    transpose_automata: &mut Automaton<T>,
+) -> Result<Vec<u8>, DecryptError>
+where
+    T: ToroidalBinaryMatrix + Clone + Send,
+{
+    check_ciphertext_len(ciphertext.len())?;
+    let mut data = ciphertext.to_vec();
+    par_decrypt_blocks_in_place(
+        &mut data,
+        DEFAULT_ROUNDS,
+        DEFAULT_LAYERS,
+        shift_automata,
+        transpose_automata,
+    );
+    unpad_message_256(data)
+}
+
+/// Encrypts the whole blocks of `data` in place as [`encrypt_block_in_place`] does, deriving the
+/// layer keys of one batch of blocks while the previous batch is encrypted in parallel.
+#[cfg(feature = "parallel")]
+pub(crate) fn par_encrypt_blocks_in_place<T>(
+    data: &mut [u8],
+    rounds: u32,
+    layers: u32,
+    shift_automata: &mut Automaton<T>,
+    transpose_automata: &mut Automaton<T>,
+) where
+    T: ToroidalBinaryMatrix + Clone + Send,
+{
+    par_blocks_in_place(
+        data,
+        rounds,
+        layers,
+        shift_automata,
+        transpose_automata,
+        |block_keys, rows| {
+            for layer_key in block_keys {
+                layer_key.encrypt(rows);
+            }
+        },
+    );
+}
+
+/// Decrypts the whole blocks of `data` in place as [`decrypt_block_in_place`] does, deriving the
+/// layer keys of one batch of blocks while the previous batch is decrypted in parallel.
+#[cfg(feature = "parallel")]
+pub(crate) fn par_decrypt_blocks_in_place<T>(
+    data: &mut [u8],
+    rounds: u32,
+    layers: u32,
+    shift_automata: &mut Automaton<T>,
+    transpose_automata: &mut Automaton<T>,
+) where
+    T: ToroidalBinaryMatrix + Clone + Send,
+{
+    par_blocks_in_place(
+        data,
+        rounds,
+        layers,
+        shift_automata,
+        transpose_automata,
+        |block_keys, rows| {
+            for layer_key in block_keys.iter().rev() {
+                layer_key.decrypt(rows);
+            }
+        },
+    );
+}
+
+/// Applies `apply` to the rows of each whole block of `data` with the layer keys of that block,
+/// deriving the keys of one batch of blocks while the previous batch is processed in parallel.
+#[cfg(feature = "parallel")]
+fn par_blocks_in_place<T, F>(
+    data: &mut [u8],
+    rounds: u32,
+    layers: u32,
+    shift_automata: &mut Automaton<T>,
+    transpose_automata: &mut Automaton<T>,
+    apply: F,
+) where
+    T: ToroidalBinaryMatrix + Clone + Send,
+    F: Fn(&[LayerKey], &mut [u16; BLOCK_SIDE]) + Sync,
+{
+    let layers = layers as usize;
+    // the two automata do not depend on each other, so their chains are advanced side by side
+    let mut derive_keys = |n_blocks: usize| {
+        let (shift_parts, transpose_parts) = rayon::join(
+            || {
+                (0..n_blocks * layers)
+                    .map(|_| {
+                        shift_automata.iter_rule(rounds);
+                        shift_amounts_256(shift_automata.get_state())
+                    })
+                    .collect::<Vec<_>>()
+            },
+            || {
+                (0..n_blocks * layers)
+                    .map(|_| {
+                        transpose_automata.iter_rule(rounds);
+                        let key = transpose_automata.get_state();
+                        (scramble_permutations_256(key), key_rows(key))
+                    })
+                    .collect::<Vec<_>>()
+            },
+        );
+        shift_parts
+            .into_iter()
+            .zip(transpose_parts)
+            .map(
+                |((row_shifts, col_shifts), ((row_perm, col_perm), mask))| LayerKey {
+                    row_perm,
+                    col_perm,
+                    row_shifts,
+                    col_shifts,
+                    mask,
+                },
+            )
+            .collect::<Vec<LayerKey>>()
+    };
+
+    let mut batches = data.chunks_mut(PARALLEL_BATCH_BLOCKS * BLOCK_BYTES);
+    let mut current = batches.next().map(|batch| {
+        let keys = derive_keys(batch.len() / BLOCK_BYTES);
+        (batch, keys)
+    });
+    while let Some((batch, keys)) = current {
+        let next_batch = batches.next();
+        let next_n_blocks = next_batch.as_ref().map(|batch| batch.len() / BLOCK_BYTES);
+        let ((), next_keys) = rayon::join(
+            || {
+                batch
+                    .par_chunks_exact_mut(BLOCK_BYTES)
+                    .zip(keys.par_chunks(layers))
+                    .for_each(|(block, block_keys)| {
+                        let mut rows = read_rows(block);
+                        apply(block_keys, &mut rows);
+                        write_rows(block, &rows);
+                        wipe(&mut rows);
+                    })
+            },
+            || next_n_blocks.map(&mut derive_keys),
+        );
+        current = next_batch.zip(next_keys);
+    }
+}
+
+/// The permutations, rotations and xor mask applied by one layer of block encryption, derived
+/// from the automaton states of that layer. Each key is derived once, then used to scramble, shift
+/// and mask its block. Keys are not cached across blocks, since neither chained nor CTR blocks ever
+/// key two layers with the same states.
+struct LayerKey {
+    row_perm: Vec<usize>,
+    col_perm: Vec<usize>,
+    row_shifts: Vec<usize>,
+    col_shifts: Vec<usize>,
+    mask: [u16; BLOCK_SIDE],
+}
+
+impl LayerKey {
+    /// Advances the automata `rounds` generations and derives the key of a layer from their new
+    /// states.
+    fn next<S>(rounds: u32, shift_automata: &mut S, transpose_automata: &mut S) -> Self
+    where
+        S: KeystreamSource,
+    {
+        shift_automata.evolve(rounds);
+        transpose_automata.evolve(rounds);
+        let key = transpose_automata.get_state();
+        let (row_perm, col_perm) = scramble_permutations_256(key);
+        let (row_shifts, col_shifts) = shift_amounts_256(shift_automata.get_state());
+
+        Self {
+            row_perm,
+            col_perm,
+            row_shifts,
+            col_shifts,
+            mask: key_rows(key),
+        }
+    }
+    /// Scrambles, shifts and masks `rows`.
+    fn encrypt(&self, rows: &mut [u16; BLOCK_SIDE]) {
+        self.scramble(rows);
+        self.shift_and_mask(rows);
+    }
+    /// Permutes the rows and columns of `rows`.
+    fn scramble(&self, rows: &mut [u16; BLOCK_SIDE]) {
+        let mut scrambled = [0_u16; BLOCK_SIDE];
+        for (new_row, old_row) in scrambled.iter_mut().zip(&self.row_perm) {
+            let row = rows[*old_row];
+            for (new_col, old_col) in self.col_perm.iter().enumerate() {
+                *new_row |= ((row >> old_col) & 1) << new_col;
+            }
+        }
+        *rows = scrambled;
+        wipe(&mut scrambled);
+    }
+    /// Rotates the rows and columns of `rows`, then masks them.
+    fn shift_and_mask(&self, rows: &mut [u16; BLOCK_SIDE]) {
+        for (row, k) in rows.iter_mut().zip(&self.row_shifts) {
+            *row = row.rotate_left(*k as u32);
+        }
+        rotate_cols(rows, &self.col_shifts);
+        for (row, mask_row) in rows.iter_mut().zip(self.mask) {
+            *row ^= mask_row;
+        }
+    }
+    /// Undoes [`LayerKey::encrypt`] on `rows`.
+    fn decrypt(&self, rows: &mut [u16; BLOCK_SIDE]) {
+        self.unmask_and_unshift(rows);
+        self.unscramble(rows);
+    }
+    /// Undoes [`LayerKey::shift_and_mask`] on `rows`.
+    fn unmask_and_unshift(&self, rows: &mut [u16; BLOCK_SIDE]) {
+        for (row, mask_row) in rows.iter_mut().zip(self.mask) {
+            *row ^= mask_row;
+        }
+        let mut inverse_col_shifts = self
+            .col_shifts
+            .iter()
+            .map(|k| (BLOCK_SIDE - k) % BLOCK_SIDE)
+            .collect::<Vec<usize>>();
+        rotate_cols(rows, &inverse_col_shifts);
+        wipe(&mut inverse_col_shifts);
+        for (row, k) in rows.iter_mut().zip(&self.row_shifts) {
+            *row = row.rotate_right(*k as u32);
+        }
+    }
+    /// Undoes [`LayerKey::scramble`] on `rows`.
+    fn unscramble(&self, rows: &mut [u16; BLOCK_SIDE]) {
+        let mut unscrambled = [0_u16; BLOCK_SIDE];
+        for (row, old_row) in rows.iter().zip(&self.row_perm) {
+            let mut original = 0;
+            for (new_col, old_col) in self.col_perm.iter().enumerate() {
+                original |= ((row >> new_col) & 1) << old_col;
+            }
+            unscrambled[*old_row] = original;
+        }
+        *rows = unscrambled;
+        wipe(&mut unscrambled);
+    }
+}
+
+/// Clears the permutations, rotations and mask, so layer keys do not linger in memory.
+#[cfg(feature = "zeroize")]
+impl Drop for LayerKey {
+    fn drop(&mut self) {
+        wipe(&mut self.row_perm);
+        wipe(&mut self.col_perm);
+        wipe(&mut self.row_shifts);
+        wipe(&mut self.col_shifts);
+        wipe(&mut self.mask);
+    }
+}
+
+/// Encrypts a single 32 byte block in place with `layers` layers, advancing the automata `rounds`
+/// generations before each layer.
+pub(crate) fn encrypt_block_in_place<S>(
+    block: &mut [u8],
+    rounds: u32,
+    layers: u32,
+    shift_automata: &mut S,
+    transpose_automata: &mut S,
+) where
+    S: KeystreamSource,
+{
+    let mut rows = read_rows(block);
+    for _ in 0..layers {
+        LayerKey::next(rounds, shift_automata, transpose_automata).encrypt(&mut rows);
+    }
+    write_rows(block, &rows);
+    wipe(&mut rows);
+}
+
+/// Decrypts a single 32 byte block in place with `layers` layers, advancing the automata `rounds`
+/// generations before each layer.
+pub(crate) fn decrypt_block_in_place<S>(
+    block: &mut [u8],
+    rounds: u32,
+    layers: u32,
+    shift_automata: &mut S,
+    transpose_automata: &mut S,
+) where
+    S: KeystreamSource,
+{
+    // the layers are undone last to first, so every layer key is derived up front
+    let layer_keys = (0..layers)
+        .map(|_| LayerKey::next(rounds, shift_automata, transpose_automata))
+        .collect::<Vec<LayerKey>>();
+    let mut rows = read_rows(block);
+    for layer_key in layer_keys.iter().rev() {
+        layer_key.decrypt(&mut rows);
+    }
+    write_rows(block, &rows);
+    wipe(&mut rows);
+}
+
+/// Encrypts a single 32 byte block in place as [`encrypt_block_in_place`] does, reporting the
+/// generations reached by the automata and the block around each scramble to `tracer`.
+pub(crate) fn encrypt_block_in_place_traced<S>(
+    block: &mut [u8],
+    rounds: u32,
+    layers: u32,
+    shift_automata: &mut S,
+    transpose_automata: &mut S,
+    tracer: &mut BlockTracer,
+) where
+    S: KeystreamSource,
+{
+    let mut rows = read_rows(block);
+    let (mut before, mut after) = ([0_u8; BLOCK_BYTES], [0_u8; BLOCK_BYTES]);
+    for layer in 0..layers {
+        let layer_key = LayerKey::next(rounds, shift_automata, transpose_automata);
+        tracer.layer_keyed(
+            layer,
+            shift_automata.get_generation(),
+            transpose_automata.get_generation(),
+        );
+        write_rows(&mut before, &rows);
+        layer_key.scramble(&mut rows);
+        write_rows(&mut after, &rows);
+        tracer.scrambled(layer, &before, &after);
+        layer_key.shift_and_mask(&mut rows);
+    }
+    write_rows(block, &rows);
+    wipe(&mut rows);
+    wipe(&mut before);
+    wipe(&mut after);
+}
+
+/// Decrypts a single 32 byte block in place as [`decrypt_block_in_place`] does, reporting to
+/// `tracer` as [`encrypt_block_in_place_traced`] does.
+pub(crate) fn decrypt_block_in_place_traced<S>(
+    block: &mut [u8],
+    rounds: u32,
+    layers: u32,
+    shift_automata: &mut S,
+    transpose_automata: &mut S,
+    tracer: &mut BlockTracer,
+) where
+    S: KeystreamSource,
+{
+    let layer_keys = (0..layers)
+        .map(|layer| {
+            let layer_key = LayerKey::next(rounds, shift_automata, transpose_automata);
+            tracer.layer_keyed(
+                layer,
+                shift_automata.get_generation(),
+                transpose_automata.get_generation(),
+            );
+            layer_key
+        })
+        .collect::<Vec<LayerKey>>();
+    let mut rows = read_rows(block);
+    let (mut before, mut after) = ([0_u8; BLOCK_BYTES], [0_u8; BLOCK_BYTES]);
+    for (layer, layer_key) in layer_keys.iter().enumerate().rev() {
+        layer_key.unmask_and_unshift(&mut rows);
+        write_rows(&mut after, &rows);
+        layer_key.unscramble(&mut rows);
+        write_rows(&mut before, &rows);
+        tracer.scrambled(layer as u32, &before, &after);
+    }
+    write_rows(block, &rows);
+    wipe(&mut rows);
+    wipe(&mut before);
+    wipe(&mut after);
+}
+
+/// Reads a 32 byte block as 16 rows, where bit `c` of row `r` is bit `16 * r + c` of the block in
+/// the least significant bit first [`BIT_ORDER`](crate::encrypt::BIT_ORDER).
+fn read_rows(block: &[u8]) -> [u16; BLOCK_SIDE] {
+    std::array::from_fn(|r| u16::from_le_bytes([block[2 * r], block[2 * r + 1]]))
+}
+
+/// Rotates column `c` of the rows read by [`read_rows`] `shifts[c]` places towards the last row,
+/// as [`ToroidalBinaryMatrix::rotate_col`] does.
+fn rotate_cols(rows: &mut [u16; BLOCK_SIDE], shifts: &[usize]) {
+    let mut original = *rows;
+    for (col, k) in shifts.iter().enumerate() {
+        let mask = 1 << col;
+        for (row, value) in original.iter().enumerate() {
+            let dest = &mut rows[(row + k) % BLOCK_SIDE];
+            *dest = (*dest & !mask) | (value & mask);
+        }
+    }
+    wipe(&mut original);
+}
+
+/// Writes 16 rows read by [`read_rows`] back into a 32 byte block.
+fn write_rows(block: &mut [u8], rows: &[u16; BLOCK_SIDE]) {
+    for (bytes, row) in block.chunks_exact_mut(2).zip(rows) {
+        bytes.copy_from_slice(&row.to_le_bytes());
+    }
+}
+
+/// Returns the rows of a 16x16 automaton state in the layout of [`read_rows`].
+fn key_rows<T>(key: &T) -> [u16; BLOCK_SIDE]
+where
+    T: ToroidalBinaryMatrix,
+{
+    let mut rows = [0_u16; BLOCK_SIDE];
+    for (i, cell) in key.iter_cells().enumerate() {
+        rows[i / BLOCK_SIDE] |= (cell as u16) << (i % BLOCK_SIDE);
+    }
+
+    rows
+}
+
+#[cfg(all(test, feature = "parallel"))]
+mod tests {
+    use super::*;
+    use crate::encrypt::{decrypt_message_256, encrypt_message_256, Key, TalosCipher};
+    use crate::matrix::ToroidalBitMatrix;
+
+    fn state() -> TalosState<ToroidalBitMatrix> {
+        let schedule = Key::new(&[4; 16]).unwrap().schedule();
+        let cipher = TalosCipher::<ToroidalBitMatrix>::new(&schedule, 0);
+        let (shift, transpose) = cipher.automata();
+        TalosState::new(shift.clone(), transpose.clone())
+    }
+
+    #[test]
+    fn parallel_chain_matches_sequential() {
+        // spans more than one batch of derived keys
+        let message = (0..(PARALLEL_BATCH_BLOCKS + 3) * BLOCK_BYTES)
+            .map(|i| (i * 7 % 251) as u8)
+            .collect::<Vec<u8>>();
+        let (mut sequential, mut parallel) = (state(), state());
+        let ciphertext = encrypt_message_256(
+            &message,
+            &mut sequential.shift_automata,
+            &mut sequential.transpose_automata,
+        );
+        assert_eq!(
+            encrypt_message_parallel(
+                &message,
+                &mut parallel.shift_automata,
+                &mut parallel.transpose_automata
+            ),
+            ciphertext
+        );
+
+        let (mut sequential, mut parallel) = (state(), state());
+        assert_eq!(
+            decrypt_message_parallel(
+                &ciphertext,
+                &mut parallel.shift_automata,
+                &mut parallel.transpose_automata
+            )
+            .unwrap(),
+            decrypt_message_256(
+                &ciphertext,
+                &mut sequential.shift_automata,
+                &mut sequential.transpose_automata
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            parallel.shift_automata.get_generation(),
+            sequential.shift_automata.get_generation()
+        );
+    }
+}
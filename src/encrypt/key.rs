@@ -0,0 +1,283 @@
+// 2025 Steven Chiacchira
+use crate::automata::Automaton;
+use crate::encrypt::{
+    decode_hex, detect_weak_key, splitmix64, temporal_seed_automata, temporal_seed_nonce, wipe,
+    GOLDEN_GAMMA,
+};
+use crate::matrix::{MatrixIndex, ToroidalBinaryMatrix};
+use crate::parse::CharMap;
+use rand::RngCore;
+use std::str::FromStr;
+use std::{error, fmt};
+
+/// Minimum number of bytes in a [`Key`].
+pub const MIN_KEY_BYTES: usize = 16;
+/// Maximum number of bytes in a [`Key`].
+pub const MAX_KEY_BYTES: usize = 32;
+
+/// Number of distinct initial generation offsets an automaton can be assigned by a key schedule.
+const KEY_GENERATION_SPREAD: u64 = 16;
+
+/// Error arising from constructing a [`Key`].
+#[derive(Debug)]
+pub enum KeyError {
+    /// A key must have between [`MIN_KEY_BYTES`] and [`MAX_KEY_BYTES`] bytes. Holds the length
+    /// which was given.
+    InvalidLength(usize),
+    /// A key written in hexadecimal must consist of an even number of hexadecimal digits.
+    InvalidHex(),
+}
+
+impl fmt::Display for KeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyError::InvalidLength(len) => write!(
+                f,
+                "key has {} bytes, expected between {} and {}",
+                len, MIN_KEY_BYTES, MAX_KEY_BYTES
+            ),
+            KeyError::InvalidHex() => write!(f, "key is not a valid hexadecimal string"),
+        }
+    }
+}
+
+impl error::Error for KeyError {}
+
+/// A secret key of between 16 and 32 bytes, expanded into a [`KeySchedule`] for seeding the
+/// automata.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Key {
+    bytes: Vec<u8>,
+}
+
+impl Key {
+    /// Creates a new key from `bytes`, returning a [`KeyError::InvalidLength`] unless it has
+    /// between [`MIN_KEY_BYTES`] and [`MAX_KEY_BYTES`] bytes.
+    pub fn new(bytes: &[u8]) -> Result<Self, KeyError> {
+        if !(MIN_KEY_BYTES..=MAX_KEY_BYTES).contains(&bytes.len()) {
+            return Err(KeyError::InvalidLength(bytes.len()));
+        }
+        Ok(Self {
+            bytes: bytes.to_vec(),
+        })
+    }
+    /// Creates a new [`MAX_KEY_BYTES`] byte key drawn from the thread-local generator, which is
+    /// seeded by the operating system.
+    pub fn generate() -> Self {
+        Self::generate_with(&mut rand::rng())
+    }
+    /// Creates a new [`MAX_KEY_BYTES`] byte key drawn from `rng`. A seeded `rng` makes the key
+    /// reproducible, which is useful for tests and research runs but must not be used for real
+    /// messages.
+    pub fn generate_with<R>(rng: &mut R) -> Self
+    where
+        R: RngCore + ?Sized,
+    {
+        let mut bytes = vec![0_u8; MAX_KEY_BYTES];
+        rng.fill_bytes(&mut bytes);
+
+        Self { bytes }
+    }
+    /// Returns whether the key sets up degenerate automata, see [`detect_weak_key`].
+    pub fn is_weak(&self) -> bool {
+        detect_weak_key(self).is_some()
+    }
+    /// Returns the bytes of the key.
+    pub fn get_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+    /// Expands the key into a [`KeySchedule`] as follows:
+    /// 1. The key is absorbed into a 64 bit state `s`, starting from the key length, by replacing
+    ///    `s` with `splitmix64(s ^ c)` for each 8 byte little-endian chunk `c` of the key. The final
+    ///    chunk is padded with zero bytes.
+    /// 2. Schedule words are drawn as `w[i] = splitmix64(s + (i + 1) * GOLDEN_GAMMA)`.
+    /// 3. The char map seed is the low 32 bits of `w[0]`, and the initial generation offsets of
+    ///    the transpose and shift automata are the low and high 32 bits of `w[1]` modulo 16.
+    /// 4. For each 4 byte little-endian word `k[j]` of the key, the transpose automaton is seeded
+    ///    with `k[j]` xored with the low 32 bits of `w[2 + j]`, and the shift automaton with `k[j]`
+    ///    xored with the high 32 bits. The final key word is padded with zero bytes.
+    ///
+    /// `splitmix64` is the finalizer of the [splitmix64](https://prng.di.unimi.it/splitmix64.c)
+    /// generator and `GOLDEN_GAMMA` its increment `0x9e3779b97f4a7c15`.
+    pub fn schedule(&self) -> KeySchedule {
+        let state = self
+            .bytes
+            .chunks(8)
+            .fold(self.bytes.len() as u64, |state, chunk| {
+                let mut word = [0_u8; 8];
+                word[..chunk.len()].copy_from_slice(chunk);
+                splitmix64(state ^ u64::from_le_bytes(word))
+            });
+        let schedule_word =
+            |i: u64| splitmix64(state.wrapping_add((i + 1).wrapping_mul(GOLDEN_GAMMA)));
+
+        let offsets = schedule_word(1);
+        let (transpose_seeds, shift_seeds) = self
+            .bytes
+            .chunks(4)
+            .enumerate()
+            .map(|(j, chunk)| {
+                let mut word = [0_u8; 4];
+                word[..chunk.len()].copy_from_slice(chunk);
+                let key_word = u32::from_le_bytes(word);
+                let mask = schedule_word(2 + j as u64);
+                (
+                    key_word ^ mask as u32,
+                    key_word ^ (mask >> u32::BITS) as u32,
+                )
+            })
+            .unzip();
+
+        KeySchedule {
+            char_map_seed: schedule_word(0) as u32,
+            transpose_seeds,
+            shift_seeds,
+            transpose_offset: (offsets as u32 as u64 % KEY_GENERATION_SPREAD) as u32,
+            shift_offset: ((offsets >> u32::BITS) % KEY_GENERATION_SPREAD) as u32,
+        }
+    }
+}
+
+/// Parses a key written as a string of hexadecimal digits, two per byte.
+impl FromStr for Key {
+    type Err = KeyError;
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        let mut bytes = decode_hex(string).map_err(|_| KeyError::InvalidHex())?;
+        let key = Self::new(&bytes);
+        wipe(&mut bytes);
+
+        key
+    }
+}
+
+/// Clears the key bytes, so the key does not linger in memory.
+#[cfg(feature = "zeroize")]
+impl Drop for Key {
+    fn drop(&mut self) {
+        wipe(&mut self.bytes);
+    }
+}
+
+/// Only the length of the key is shown, so keys do not end up in logs.
+impl fmt::Debug for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Key({} bytes)", self.bytes.len())
+    }
+}
+
+/// The values derived from a key which drive the setup of both automata.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeySchedule {
+    /// Seed passed to [`CharMap::seeded`] to fill in the initial automaton states.
+    pub char_map_seed: u32,
+    /// 32 bit values seeded into the transpose automaton, one temporal seeding pass each.
+    pub transpose_seeds: Vec<u32>,
+    /// 32 bit values seeded into the shift automaton, one temporal seeding pass each.
+    pub shift_seeds: Vec<u32>,
+    /// Generations the transpose automaton is advanced by before seeding.
+    pub transpose_offset: u32,
+    /// Generations the shift automaton is advanced by before seeding.
+    pub shift_offset: u32,
+}
+
+impl KeySchedule {
+    /// Returns the map from characters to `bool` values used to fill in the initial automaton
+    /// states, see [`CharMap::seeded`].
+    pub fn char_map(&self) -> CharMap {
+        CharMap::seeded(self.char_map_seed)
+    }
+    /// Seeds the transpose automaton: advances it by the transpose offset, performs one pass of
+    /// [`temporal_seed_automata`] for each transpose seed, then seeds `nonce` with
+    /// [`temporal_seed_nonce`].
+    pub fn seed_transpose_automata<T>(
+        &self,
+        automaton: &mut Automaton<T>,
+        nonce: u64,
+        seed_positions: &[Vec<MatrixIndex>],
+    ) where
+        T: ToroidalBinaryMatrix + Clone,
+    {
+        seed_scheduled(
+            automaton,
+            self.transpose_offset,
+            &self.transpose_seeds,
+            nonce,
+            seed_positions,
+        );
+    }
+    /// Seeds the shift automaton in the same way as
+    /// [`KeySchedule::seed_transpose_automata`], using the shift offset and seeds.
+    pub fn seed_shift_automata<T>(
+        &self,
+        automaton: &mut Automaton<T>,
+        nonce: u64,
+        seed_positions: &[Vec<MatrixIndex>],
+    ) where
+        T: ToroidalBinaryMatrix + Clone,
+    {
+        seed_scheduled(
+            automaton,
+            self.shift_offset,
+            &self.shift_seeds,
+            nonce,
+            seed_positions,
+        );
+    }
+}
+
+/// Builds the schedule of a legacy 32 bit key, which seeds both automata with the key itself and
+/// no generation offset, matching [`temporal_seed_automata`] followed by
+/// [`temporal_seed_nonce`].
+impl From<u32> for KeySchedule {
+    fn from(key: u32) -> Self {
+        Self {
+            char_map_seed: key,
+            transpose_seeds: vec![key],
+            shift_seeds: vec![key],
+            transpose_offset: 0,
+            shift_offset: 0,
+        }
+    }
+}
+
+/// Clears every value derived from the key, so the schedule does not linger in memory.
+#[cfg(feature = "zeroize")]
+impl Drop for KeySchedule {
+    fn drop(&mut self) {
+        wipe(&mut self.char_map_seed);
+        wipe(&mut self.transpose_seeds);
+        wipe(&mut self.shift_seeds);
+        wipe(&mut self.transpose_offset);
+        wipe(&mut self.shift_offset);
+    }
+}
+
+/// Advances `automaton` by `offset` generations, seeds each of `seeds` and then `nonce`.
+fn seed_scheduled<T>(
+    automaton: &mut Automaton<T>,
+    offset: u32,
+    seeds: &[u32],
+    nonce: u64,
+    seed_positions: &[Vec<MatrixIndex>],
+) where
+    T: ToroidalBinaryMatrix + Clone,
+{
+    seed_key(automaton, offset, seeds, seed_positions);
+    temporal_seed_nonce(automaton, nonce, seed_positions);
+}
+
+/// Advances `automaton` by `offset` generations and seeds each of `seeds`, leaving out the nonce.
+pub(crate) fn seed_key<T>(
+    automaton: &mut Automaton<T>,
+    offset: u32,
+    seeds: &[u32],
+    seed_positions: &[Vec<MatrixIndex>],
+) where
+    T: ToroidalBinaryMatrix + Clone,
+{
+    automaton.iter_rule(offset);
+    for seed in seeds {
+        temporal_seed_automata(automaton, *seed, seed_positions);
+    }
+}
@@ -0,0 +1,632 @@
+// 2025 Steven Chiacchira
+use crate::automata::Automaton;
+use crate::matrix::{BitOrder, MatrixIndex, ToroidalBinaryMatrix};
+use crate::parse::{concat_bits, BitPacker};
+use std::string::{self};
+use std::{error, fmt};
+
+pub mod analysis;
+mod auth;
+mod builder;
+mod checksum;
+mod combiner;
+mod ctr;
+mod encoding;
+mod file;
+pub mod frames;
+mod in_place;
+mod key;
+mod nonce;
+mod rng;
+#[cfg(feature = "rustcrypto")]
+mod rustcrypto;
+mod scrambler;
+mod session;
+mod stream;
+mod trace;
+mod weak_key;
+
+pub use auth::*;
+pub use builder::*;
+pub(crate) use checksum::*;
+pub use combiner::*;
+pub use ctr::*;
+pub use encoding::*;
+pub use file::*;
+pub use in_place::*;
+pub use key::*;
+pub use nonce::*;
+pub use rng::*;
+#[cfg(feature = "rustcrypto")]
+pub use rustcrypto::*;
+pub use scrambler::*;
+pub use session::*;
+pub use stream::*;
+pub use trace::*;
+pub use weak_key::*;
+
+/// Number of bytes in a message block.
+const BLOCK_BYTES: usize = 256 / 8;
+
+/// Order of the bits within each byte as the cipher reads them. Message blocks are loaded into
+/// 16x16 matrices with [`ToroidalBinaryMatrix::from_bytes`], so element `i` of a block in
+/// row-major order is bit `i % 8` of byte `i / 8`, counting from the least significant bit, and
+/// the 4 bit values read from automaton states by [`read_4_bits`] hold their first element in
+/// their least significant bit. Recorded in the header written by [`encrypt_stream`]. Integers
+/// written alongside ciphertext, such as nonces and lengths, are always little-endian, so neither
+/// depends on the byte order of the platform.
+pub const BIT_ORDER: BitOrder = BitOrder::LsbFirst;
+
+/// Number of generations both automata are advanced by before each block is encrypted.
+pub const DEFAULT_ROUNDS: u32 = 11;
+
+/// Number of scramble, shift and xor layers applied to each block. Each layer is keyed by
+/// automaton states advanced a further [`DEFAULT_ROUNDS`] generations.
+pub const DEFAULT_LAYERS: u32 = 1;
+
+/// Increment of the [splitmix64](https://prng.di.unimi.it/splitmix64.c) generator, used to spread
+/// consecutive counters across the 64 bit range.
+pub(crate) const GOLDEN_GAMMA: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// Applies the finalizer of the [splitmix64](https://prng.di.unimi.it/splitmix64.c) generator to
+/// `z`, mixing every input bit into every output bit.
+pub(crate) fn splitmix64(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Applies [`splitmix64`] to each 8 byte little-endian word of `bytes`. Neighboring cells of an
+/// automaton state are strongly correlated, so states are passed through this bijection before
+/// being output as random-looking bytes. A partial final word is left unchanged.
+pub(crate) fn splitmix64_words(bytes: &mut [u8]) {
+    for word in bytes.chunks_exact_mut(8) {
+        // chunks have the length of a u64
+        let mixed = splitmix64(u64::from_le_bytes((&*word).try_into().unwrap()));
+        word.copy_from_slice(&mixed.to_le_bytes());
+    }
+}
+
+/// Iterates over the bits of `bytes` in the order of [`ToroidalBinaryMatrix::from_bytes`].
+pub(crate) fn iter_bits(bytes: &[u8]) -> impl Iterator<Item = bool> + '_ {
+    bytes
+        .iter()
+        .flat_map(|byte| (0..u8::BITS).map(move |i| (byte >> i) & 1 == 1))
+}
+
+/// Clears `secret` in a way which is not optimized away when the `zeroize` feature is enabled,
+/// and does nothing otherwise.
+#[cfg(feature = "zeroize")]
+pub(crate) fn wipe<Z>(secret: &mut Z)
+where
+    Z: zeroize::Zeroize + ?Sized,
+{
+    secret.zeroize();
+}
+
+/// Clears `secret` in a way which is not optimized away when the `zeroize` feature is enabled,
+/// and does nothing otherwise.
+#[cfg(not(feature = "zeroize"))]
+pub(crate) fn wipe<Z>(_secret: &mut Z)
+where
+    Z: ?Sized,
+{
+}
+
+/// Error arising from decrypting a message
+#[derive(Debug)]
+pub enum DecryptError {
+    /// A ciphertext must hold at least one whole 32 byte block, and nothing past its last block.
+    /// A truncated ciphertext usually means it was cut short in transit or storage.
+    TruncatedCiphertext(),
+    /// A decrypted message must end with valid PKCS#7 padding for a 32 byte block. Invalid
+    /// padding usually means the wrong key was used or the ciphertext was modified.
+    InvalidPadding(),
+    /// An authenticated ciphertext must carry a tag matching its contents. A mismatched tag means
+    /// the wrong key was used or the ciphertext was modified.
+    AuthenticationFailed(),
+    /// A decrypted message must match the checksum stored with it. A mismatched checksum means the
+    /// ciphertext was corrupted or cut short at a block boundary.
+    CorruptCiphertext(),
+    /// The header of a stream must name the [`BIT_ORDER`] of the cipher. Holds the bit order byte
+    /// of the header, which is usually not a bit order at all when the input is not a ciphertext.
+    UnsupportedBitOrder(u8),
+    /// A message decrypted as a string must be valid UTF-8. Since the padding was valid, the key
+    /// was most likely correct and the message was not text.
+    InvalidUtf8(string::FromUtf8Error),
+}
+
+impl fmt::Display for DecryptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecryptError::TruncatedCiphertext() => {
+                write!(f, "ciphertext is not a whole number of blocks")
+            }
+            DecryptError::InvalidPadding() => write!(f, "decrypted message has invalid padding"),
+            DecryptError::AuthenticationFailed() => write!(f, "ciphertext failed authentication"),
+            DecryptError::CorruptCiphertext() => {
+                write!(f, "decrypted message does not match its checksum")
+            }
+            DecryptError::UnsupportedBitOrder(byte) => {
+                write!(f, "ciphertext header names unsupported bit order {}", byte)
+            }
+            DecryptError::InvalidUtf8(e) => write!(f, "decrypted message is not UTF-8: {}", e),
+        }
+    }
+}
+
+/// Allows a [`DecryptError`] to be carried by an [`std::io::Error`], as done by
+/// [`DecryptReader`].
+impl error::Error for DecryptError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            DecryptError::InvalidUtf8(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Returns a [`DecryptError::TruncatedCiphertext`] unless a ciphertext of `len` bytes is a
+/// non-zero whole number of blocks.
+pub(crate) fn check_ciphertext_len(len: usize) -> Result<(), DecryptError> {
+    if len == 0 || !len.is_multiple_of(BLOCK_BYTES) {
+        return Err(DecryptError::TruncatedCiphertext());
+    }
+
+    Ok(())
+}
+
+/// Pads `message` to a multiple of the 32 byte block size with
+/// [PKCS#7](https://datatracker.ietf.org/doc/html/rfc5652#section-6.3) padding: `n` bytes of
+/// value `n` are appended, where `n` is between 1 and 32. A message which is already a multiple of
+/// the block size gains a full block of padding, so the padding can always be removed
+/// unambiguously.
+pub fn pad_message_256(message: &[u8]) -> Vec<u8> {
+    let n_padding = BLOCK_BYTES - message.len() % BLOCK_BYTES;
+    let mut result = Vec::with_capacity(message.len() + n_padding);
+    result.extend_from_slice(message);
+    result.resize(message.len() + n_padding, n_padding as u8);
+
+    result
+}
+
+/// Removes the padding added by [`pad_message_256`] from `message`, returning a
+/// [`DecryptError::InvalidPadding`] if `message` does not end with valid padding.
+pub fn unpad_message_256(mut message: Vec<u8>) -> Result<Vec<u8>, DecryptError> {
+    let n_padding = *message.last().ok_or(DecryptError::InvalidPadding())? as usize;
+    if n_padding == 0
+        || n_padding > BLOCK_BYTES
+        || n_padding > message.len()
+        || message[message.len() - n_padding..]
+            .iter()
+            .any(|byte| *byte as usize != n_padding)
+    {
+        return Err(DecryptError::InvalidPadding());
+    }
+    message.truncate(message.len() - n_padding);
+
+    Ok(message)
+}
+
+/// Reads 4 bit values at `idx0`, `idx`, `idx2`, `idx3`, in `matrix`, then concatenates them into a
+/// `u8` in `order`. The scrambling of RFC-0 reads them in [`BIT_ORDER`], least significant bit
+/// first. The indices are not wrapped, and an index outside `matrix` panics. This is
+/// [`read_bits`] with 4 indices.
+pub fn read_4_bits<T>(
+    matrix: &T,
+    idx0: impl Into<MatrixIndex>,
+    idx1: impl Into<MatrixIndex>,
+    idx2: impl Into<MatrixIndex>,
+    idx3: impl Into<MatrixIndex>,
+    order: BitOrder,
+) -> u8
+where
+    T: ToroidalBinaryMatrix,
+{
+    read_bits(
+        matrix,
+        [idx0.into(), idx1.into(), idx2.into(), idx3.into()],
+        order,
+    ) as u8
+}
+
+/// Reads the `N` bit values at `indices` in `matrix` and concatenates them into the low `N` bits
+/// of a `u32`. With [`BitOrder::LsbFirst`] bit `i` of the result is the element at `indices[i]`,
+/// and with [`BitOrder::MsbFirst`] the element at `indices[0]` is bit `N - 1`, so the elements
+/// read as a binary number from left to right. `N` may be at most 32, which is checked at compile
+/// time. The indices are not wrapped, and an index outside `matrix` panics.
+/// ex.
+/// reading the elements `[true, false, true, true]` gives `0b1101` least significant bit first
+/// and `0b1011` most significant bit first.
+pub fn read_bits<T, I, const N: usize>(matrix: &T, indices: [I; N], order: BitOrder) -> u32
+where
+    T: ToroidalBinaryMatrix,
+    I: Into<MatrixIndex>,
+{
+    const { assert!(N <= u32::BITS as usize, "at most 32 bits fit in a u32") };
+
+    let bits = indices.map(|idx| matrix.get_unwrapped(idx));
+    match order {
+        BitOrder::LsbFirst => concat_bits(&bits, order),
+        // an empty read would shift by the full width
+        BitOrder::MsbFirst if N == 0 => 0,
+        BitOrder::MsbFirst => concat_bits::<u32>(&bits, order) >> (u32::BITS as usize - N),
+    }
+}
+
+/// Computes the row and column permutations applied by the matrix scrambling algorithm $V$
+/// explained in RFC-0 with key `key`. Row `i` of a scrambled block holds row `rows[i]` of the
+/// original block, and likewise for columns; see [`ToroidalBinaryMatrix::permute_rows`].
+pub fn scramble_permutations_256<T>(key: &T) -> (Vec<usize>, Vec<usize>)
+where
+    T: ToroidalBinaryMatrix,
+{
+    let mut rows = (0..16).collect::<Vec<usize>>();
+    let mut cols = (0..16).collect::<Vec<usize>>();
+    for row_block in 0..4 {
+        // iterate over each row in the 'row block' and swap
+        let block_offset: isize = 4 * row_block;
+        for (row_offset, col_offset) in [0, 2, 1, 3].iter().enumerate() {
+            let (r_offset, c_offset) = (row_offset as isize, *col_offset as isize);
+            let base = MatrixIndex::new(block_offset + r_offset, c_offset);
+            let row_swap_idx = read_4_bits(
+                key,
+                base,
+                base.offset_cols(4),
+                base.offset_cols(8),
+                base.offset_cols(12),
+                BIT_ORDER,
+            ) as usize;
+            rows.swap((block_offset + r_offset) as usize, row_swap_idx);
+        }
+    }
+    for col_block in 0..4 {
+        // iterate over each col in the 'col block' and swap
+        let block_offset: isize = 4 * col_block;
+        for (col_offset, row_offset) in [3, 0, 2, 1].iter().enumerate() {
+            let (r_offset, c_offset) = (*row_offset as isize, col_offset as isize);
+            let base = MatrixIndex::new(r_offset, block_offset + c_offset);
+            let col_swap_idx = read_4_bits(
+                key,
+                base,
+                base.offset_rows(4),
+                base.offset_rows(8),
+                base.offset_rows(12),
+                BIT_ORDER,
+            ) as usize;
+            cols.swap((block_offset + c_offset) as usize, col_swap_idx);
+        }
+    }
+
+    (rows, cols)
+}
+
+/// Computes the row and column rotations applied by the shift stage explained in RFC-0 with key
+/// `key`. Row `r` of a block is rotated `rows[r]` places towards the last column, then column `c`
+/// is rotated `cols[c]` places towards the last row; see [`ToroidalBinaryMatrix::rotate_row`].
+/// Each amount is the 4 bit value read by [`read_4_bits`] from `(r, 1)`, `(r, 5)`, `(r, 9)`,
+/// `(r, 13)` for rows and from `(2, c)`, `(6, c)`, `(10, c)`, `(14, c)` for columns.
+pub fn shift_amounts_256<T>(key: &T) -> (Vec<usize>, Vec<usize>)
+where
+    T: ToroidalBinaryMatrix,
+{
+    let rows = (0..16)
+        .map(|row| {
+            let base = MatrixIndex::new(row, 1);
+            read_4_bits(
+                key,
+                base,
+                base.offset_cols(4),
+                base.offset_cols(8),
+                base.offset_cols(12),
+                BIT_ORDER,
+            ) as usize
+        })
+        .collect();
+    let cols = (0..16)
+        .map(|col| {
+            let base = MatrixIndex::new(2, col);
+            read_4_bits(
+                key,
+                base,
+                base.offset_rows(4),
+                base.offset_rows(8),
+                base.offset_rows(12),
+                BIT_ORDER,
+            ) as usize
+        })
+        .collect();
+
+    (rows, cols)
+}
+
+/// Applies the shift stage explained in RFC-0.
+fn shift_matrix_256<T>(message_matrix: &mut T, key: &T)
+where
+    T: ToroidalBinaryMatrix,
+{
+    let (rows, cols) = shift_amounts_256(key);
+    for (row, k) in rows.into_iter().enumerate() {
+        message_matrix.rotate_row(row as isize, k as isize);
+    }
+    for (col, k) in cols.into_iter().enumerate() {
+        message_matrix.rotate_col(col as isize, k as isize);
+    }
+}
+
+/// Applies the inverse of the shift stage explained in RFC-0.
+fn unshift_matrix_256<T>(message_matrix: &mut T, key: &T)
+where
+    T: ToroidalBinaryMatrix,
+{
+    let (rows, cols) = shift_amounts_256(key);
+    for (col, k) in cols.into_iter().enumerate() {
+        message_matrix.rotate_col(col as isize, -(k as isize));
+    }
+    for (row, k) in rows.into_iter().enumerate() {
+        message_matrix.rotate_row(row as isize, -(k as isize));
+    }
+}
+
+/// Reconstructs a UTF-8 string from the bitstring `bits`, represented as a `Vec<bool>`,
+/// returning a [`DecryptError::InvalidUtf8`] if the bytes are not UTF-8.
+pub fn reconstruct_message(bits: Vec<bool>) -> Result<String, DecryptError> {
+    let bytes: Vec<u8> = BitPacker::new(bits, BIT_ORDER).collect();
+    String::from_utf8(bytes).map_err(DecryptError::InvalidUtf8)
+}
+
+/// Encrypts a 256 bit message block with the Talos algorithm, permuting it with `scrambler`.
+/// Each of the `layers` layers advances the automata [`DEFAULT_ROUNDS`] generations, then
+/// scrambles, shifts and xors the block with the new states.
+fn encrypt_block_256<T, S>(
+    message_block: &[u8],
+    scrambler: &S,
+    layers: u32,
+    shift_automata: &mut Automaton<T>,
+    transpose_automata: &mut Automaton<T>,
+) -> Vec<u8>
+where
+    T: ToroidalBinaryMatrix + Clone,
+    S: Scrambler,
+{
+    let mut message_matrix = T::from_bytes(16, 16, message_block).unwrap();
+    for _ in 0..layers {
+        shift_automata.iter_rule(DEFAULT_ROUNDS);
+        transpose_automata.iter_rule(DEFAULT_ROUNDS);
+
+        scrambler.scramble(&mut message_matrix, transpose_automata.get_state());
+        shift_matrix_256(&mut message_matrix, shift_automata.get_state());
+        message_matrix
+            .bitwise_xor(transpose_automata.get_state())
+            .expect("automaton states must be 16x16");
+    }
+
+    message_matrix.to_bytes()
+}
+
+/// Decrypts a 256 bit message block encrypted by [`encrypt_block_256`] with the same `scrambler`
+/// and number of `layers`.
+fn decrypt_block_256<T, S>(
+    encrypted_block: &[u8],
+    scrambler: &S,
+    layers: u32,
+    shift_automata: &mut Automaton<T>,
+    transpose_automata: &mut Automaton<T>,
+) -> Vec<u8>
+where
+    T: ToroidalBinaryMatrix + Clone,
+    S: Scrambler,
+{
+    let mut message_matrix =
+        T::from_bytes(16, 16, encrypted_block).expect("ciphertext blocks must be 32 bytes");
+    // the layers are undone last to first, so the states of every layer are kept
+    let layer_states = (0..layers)
+        .map(|_| {
+            shift_automata.iter_rule(DEFAULT_ROUNDS);
+            transpose_automata.iter_rule(DEFAULT_ROUNDS);
+            (
+                shift_automata.get_state().clone(),
+                transpose_automata.get_state().clone(),
+            )
+        })
+        .collect::<Vec<(T, T)>>();
+
+    for (shift_state, transpose_state) in layer_states.iter().rev() {
+        message_matrix
+            .bitwise_xor(transpose_state)
+            .expect("automaton states must be 16x16");
+        unshift_matrix_256(&mut message_matrix, shift_state);
+        scrambler.unscramble(&mut message_matrix, transpose_state);
+    }
+
+    message_matrix.to_bytes()
+}
+
+/// Encrypts a byte message with a 256 bit block using the Talos algorithm. The message is padded
+/// with [`pad_message_256`] first, so the ciphertext is always at least one block longer than the
+/// message rounded down to a whole block. Bits are packed into the ciphertext bytes in
+/// [`BIT_ORDER`].
+/// Notably *DOES NOT* perform the temporal seeding as defined in RFC-1.
+pub fn encrypt_message_256<T>(
+    message: &[u8],
+    shift_automata: &mut Automaton<T>,
+    transpose_automata: &mut Automaton<T>,
+) -> Vec<u8>
+where
+    T: ToroidalBinaryMatrix + Clone,
+{
+    encrypt_message_with_scrambler_256(message, &Rfc0Scrambler, shift_automata, transpose_automata)
+}
+
+/// Encrypts a message as [`encrypt_message_256`] does, permuting each block with `scrambler`
+/// rather than the RFC-0 [`Rfc0Scrambler`].
+/// Notably *DOES NOT* perform the temporal seeding as defined in RFC-1.
+pub fn encrypt_message_with_scrambler_256<T, S>(
+    message: &[u8],
+    scrambler: &S,
+    shift_automata: &mut Automaton<T>,
+    transpose_automata: &mut Automaton<T>,
+) -> Vec<u8>
+where
+    T: ToroidalBinaryMatrix + Clone,
+    S: Scrambler,
+{
+    pad_message_256(message)
+        .chunks(BLOCK_BYTES) // read each byte into a chunk of 256 bits (32 bytes)
+        .flat_map(|block| {
+            encrypt_block_256(
+                block,
+                scrambler,
+                DEFAULT_LAYERS,
+                shift_automata,
+                transpose_automata,
+            )
+        })
+        .collect()
+}
+
+/// Encrypts the UTF-8 bytes of `message` as [`encrypt_message_256`] does.
+/// Notably *DOES NOT* perform the temporal seeding as defined in RFC-1.
+pub fn encrypt_str_256<T>(
+    message: &str,
+    shift_automata: &mut Automaton<T>,
+    transpose_automata: &mut Automaton<T>,
+) -> Vec<u8>
+where
+    T: ToroidalBinaryMatrix + Clone,
+{
+    encrypt_message_256(message.as_bytes(), shift_automata, transpose_automata)
+}
+
+/// Decrypts a message with a 256 bit block using the Talos algorithm and removes the padding added
+/// by [`encrypt_message_256`], returning a [`DecryptError`] if the ciphertext is not a whole
+/// number of blocks or the padding is invalid.
+/// Notably *DOES NOT* perform the temporal seeding as defined in RFC-1.
+pub fn decrypt_message_256<T>(
+    ciphertext: &[u8],
+    shift_automata: &mut Automaton<T>,
+    transpose_automata: &mut Automaton<T>,
+) -> Result<Vec<u8>, DecryptError>
+where
+    T: ToroidalBinaryMatrix + Clone,
+{
+    decrypt_message_with_scrambler_256(
+        ciphertext,
+        &Rfc0Scrambler,
+        shift_automata,
+        transpose_automata,
+    )
+}
+
+/// Decrypts a message encrypted with [`encrypt_message_with_scrambler_256`] as
+/// [`decrypt_message_256`] does, permuting each block with `scrambler`.
+/// Notably *DOES NOT* perform the temporal seeding as defined in RFC-1.
+pub fn decrypt_message_with_scrambler_256<T, S>(
+    ciphertext: &[u8],
+    scrambler: &S,
+    shift_automata: &mut Automaton<T>,
+    transpose_automata: &mut Automaton<T>,
+) -> Result<Vec<u8>, DecryptError>
+where
+    T: ToroidalBinaryMatrix + Clone,
+    S: Scrambler,
+{
+    check_ciphertext_len(ciphertext.len())?;
+    let message = ciphertext
+        .chunks(BLOCK_BYTES)
+        .flat_map(|block| {
+            decrypt_block_256(
+                block,
+                scrambler,
+                DEFAULT_LAYERS,
+                shift_automata,
+                transpose_automata,
+            )
+        })
+        .collect();
+    unpad_message_256(message)
+}
+
+/// Decrypts `ciphertext` as [`decrypt_message_256`] does, returning a
+/// [`DecryptError::InvalidUtf8`] if the message is not UTF-8.
+/// Notably *DOES NOT* perform the temporal seeding as defined in RFC-1.
+pub fn decrypt_str_256<T>(
+    ciphertext: &[u8],
+    shift_automata: &mut Automaton<T>,
+    transpose_automata: &mut Automaton<T>,
+) -> Result<String, DecryptError>
+where
+    T: ToroidalBinaryMatrix + Clone,
+{
+    let message = decrypt_message_256(ciphertext, shift_automata, transpose_automata)?;
+    String::from_utf8(message).map_err(DecryptError::InvalidUtf8)
+}
+
+/// Performs temporal seeding across `automata` using the method described in RFC-1. `key` is the
+/// 32-bit key used for seeding, and `seed_position` maps bit indices in `seed` to (potentially
+/// multiple) `MatrixIndices`.
+pub fn temporal_seed_automata<T>(
+    automaton: &mut Automaton<T>,
+    key: u32,
+    seed_positions: &[Vec<MatrixIndex>],
+) where
+    T: ToroidalBinaryMatrix + Clone,
+{
+    automaton.iter_rule(8);
+    for (bit_pos, positions) in seed_positions.iter().enumerate().take(u32::BITS as usize) {
+        let overwritten_value: bool = (key >> bit_pos & 1) > 0;
+        for matrix_idx in positions {
+            automaton.set_state(matrix_idx, overwritten_value);
+        }
+        automaton.iter_rule(8);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pad_empty_message() {
+        let padded = pad_message_256(&[]);
+        assert_eq!(padded, vec![BLOCK_BYTES as u8; BLOCK_BYTES]);
+        assert_eq!(unpad_message_256(padded).unwrap(), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn pad_whole_block_message() {
+        let message: Vec<u8> = (0..BLOCK_BYTES as u8).collect();
+        let padded = pad_message_256(&message);
+        assert_eq!(padded.len(), 2 * BLOCK_BYTES);
+        assert_eq!(padded[..BLOCK_BYTES], message[..]);
+        assert!(padded[BLOCK_BYTES..]
+            .iter()
+            .all(|byte| *byte == BLOCK_BYTES as u8));
+        assert_eq!(unpad_message_256(padded).unwrap(), message);
+    }
+
+    #[test]
+    fn pad_partial_block_message() {
+        let message = b"talos".to_vec();
+        let padded = pad_message_256(&message);
+        assert_eq!(padded.len(), BLOCK_BYTES);
+        assert_eq!(unpad_message_256(padded).unwrap(), message);
+    }
+
+    #[test]
+    fn unpad_rejects_invalid_padding() {
+        let invalid = [
+            vec![],
+            vec![0; BLOCK_BYTES],
+            vec![BLOCK_BYTES as u8 + 1; BLOCK_BYTES],
+            vec![4; 3],
+            [vec![0; BLOCK_BYTES - 3], vec![2, 3, 3]].concat(),
+        ];
+        for message in invalid {
+            assert!(matches!(
+                unpad_message_256(message),
+                Err(DecryptError::InvalidPadding())
+            ));
+        }
+    }
+}
@@ -0,0 +1,73 @@
+// 2025 Steven Chiacchira
+use crate::automata::Automaton;
+use crate::encrypt::temporal_seed_automata;
+use crate::matrix::{MatrixIndex, ToroidalBinaryMatrix};
+use rand::RngCore;
+use std::io::{self, Read, Write};
+
+/// Number of bytes in the nonce header at the start of a ciphertext.
+pub const NONCE_BYTES: usize = u64::BITS as usize / 8;
+
+/// Returns a new random nonce drawn from the thread-local generator, which is seeded by the
+/// operating system. A fresh nonce should be used for every message encrypted with the same key.
+pub fn generate_nonce() -> u64 {
+    generate_nonce_with(&mut rand::rng())
+}
+
+/// Returns a new nonce drawn from `rng`. A seeded `rng` makes the nonce reproducible, which is
+/// useful for tests and research runs but must not be used for real messages.
+pub fn generate_nonce_with<R>(rng: &mut R) -> u64
+where
+    R: RngCore + ?Sized,
+{
+    rng.next_u64()
+}
+
+/// Performs temporal seeding across `automaton` as [`temporal_seed_automata`] does, then seeds
+/// `nonce` with [`temporal_seed_nonce`], so that the same key produces different automaton states
+/// for different nonces.
+pub fn temporal_seed_automata_with_nonce<T>(
+    automaton: &mut Automaton<T>,
+    key: u32,
+    nonce: u64,
+    seed_positions: &[Vec<MatrixIndex>],
+) where
+    T: ToroidalBinaryMatrix + Clone,
+{
+    temporal_seed_automata(automaton, key, seed_positions);
+    temporal_seed_nonce(automaton, nonce, seed_positions);
+}
+
+/// Seeds the low and then the high 32 bits of `nonce` into `automaton` in the same way
+/// [`temporal_seed_automata`] seeds a key.
+pub fn temporal_seed_nonce<T>(
+    automaton: &mut Automaton<T>,
+    nonce: u64,
+    seed_positions: &[Vec<MatrixIndex>],
+) where
+    T: ToroidalBinaryMatrix + Clone,
+{
+    temporal_seed_automata(automaton, nonce as u32, seed_positions);
+    temporal_seed_automata(automaton, (nonce >> u32::BITS) as u32, seed_positions);
+}
+
+/// Writes `nonce` to `writer` as the [`NONCE_BYTES`] byte little-endian header preceding a
+/// ciphertext.
+pub fn write_nonce_header<W>(writer: &mut W, nonce: u64) -> io::Result<()>
+where
+    W: Write,
+{
+    writer.write_all(&nonce.to_le_bytes())
+}
+
+/// Reads the nonce header written by [`write_nonce_header`] from `reader`, returning an
+/// [`io::ErrorKind::UnexpectedEof`] error if `reader` ends before the header does.
+pub fn read_nonce_header<R>(reader: &mut R) -> io::Result<u64>
+where
+    R: Read,
+{
+    let mut header = [0_u8; NONCE_BYTES];
+    reader.read_exact(&mut header)?;
+
+    Ok(u64::from_le_bytes(header))
+}
@@ -0,0 +1,155 @@
+// 2025 Steven Chiacchira
+use crate::encrypt::{splitmix64_words, wipe, Key, KeySchedule, TalosCipher, BLOCK_BYTES};
+use crate::matrix::{ToroidalBinaryMatrix, ToroidalBitMatrix};
+use rand::{RngCore, SeedableRng};
+
+/// A pseudo-random number generator driven by the two Talos automata.
+///
+/// Each block of output advances both automata by the cipher's number of rounds, as encrypting a
+/// block would, and is the xor of the shift and transpose states with the splitmix64 finalizer
+/// applied to each 8 byte word. Neither state is output on its own, since the next generation of
+/// a known state can be computed by anyone, and the finalizer breaks up the correlation between
+/// neighboring cells. The generator has not been analysed and is meant for experiments and
+/// statistical testing, not for keys.
+#[derive(Debug, Clone)]
+pub struct TalosRng<T = ToroidalBitMatrix>
+where
+    T: ToroidalBinaryMatrix + Clone,
+{
+    cipher: TalosCipher<T>,
+    block: [u8; BLOCK_BYTES],
+    /// Number of bytes of `block` already returned.
+    n_used: usize,
+}
+
+impl<T> TalosRng<T>
+where
+    T: ToroidalBinaryMatrix + Clone,
+{
+    /// Creates a new generator with the automata of a [`TalosCipher`] for `schedule` and `nonce`.
+    pub fn new(schedule: &KeySchedule, nonce: u64) -> Self {
+        Self::with_cipher(TalosCipher::new(schedule, nonce))
+    }
+    /// Creates a new generator continuing from the automaton states and rounds of `cipher`.
+    pub fn with_cipher(cipher: TalosCipher<T>) -> Self {
+        Self {
+            cipher,
+            block: [0; BLOCK_BYTES],
+            n_used: BLOCK_BYTES,
+        }
+    }
+    /// Advances the automata and fills `block` with the next block of output.
+    fn refill(&mut self) {
+        let rounds = self.cipher.get_rounds();
+        let (shift_automata, transpose_automata) = self.cipher.automata_mut();
+        shift_automata.iter_rule(rounds);
+        transpose_automata.iter_rule(rounds);
+        let shift_state = shift_automata.get_state().iter_cells();
+        let transpose_state = transpose_automata.get_state().iter_cells();
+
+        self.block = [0; BLOCK_BYTES];
+        for (i, (s, t)) in shift_state.zip(transpose_state).enumerate() {
+            self.block[i / u8::BITS as usize] |= ((s ^ t) as u8) << (i % u8::BITS as usize);
+        }
+        splitmix64_words(&mut self.block);
+        self.n_used = 0;
+    }
+}
+
+impl<T> RngCore for TalosRng<T>
+where
+    T: ToroidalBinaryMatrix + Clone,
+{
+    fn next_u32(&mut self) -> u32 {
+        let mut bytes = [0_u8; 4];
+        self.fill_bytes(&mut bytes);
+        u32::from_le_bytes(bytes)
+    }
+    fn next_u64(&mut self) -> u64 {
+        let mut bytes = [0_u8; 8];
+        self.fill_bytes(&mut bytes);
+        u64::from_le_bytes(bytes)
+    }
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        let mut n_filled = 0;
+        while n_filled < dst.len() {
+            if self.n_used == BLOCK_BYTES {
+                self.refill();
+            }
+            let n_copied = (dst.len() - n_filled).min(BLOCK_BYTES - self.n_used);
+            dst[n_filled..n_filled + n_copied]
+                .copy_from_slice(&self.block[self.n_used..self.n_used + n_copied]);
+            n_filled += n_copied;
+            self.n_used += n_copied;
+        }
+    }
+}
+
+/// Seeds are used as 32 byte [`Key`]s with nonce 0.
+impl<T> SeedableRng for TalosRng<T>
+where
+    T: ToroidalBinaryMatrix + Clone,
+{
+    type Seed = [u8; BLOCK_BYTES];
+
+    fn from_seed(mut seed: Self::Seed) -> Self {
+        // a seed has the maximum key length
+        let key = Key::new(&seed).unwrap();
+        wipe(&mut seed);
+
+        Self::new(&key.schedule(), 0)
+    }
+}
+
+/// Clears the unused output, so it does not linger in memory.
+#[cfg(feature = "zeroize")]
+impl<T> Drop for TalosRng<T>
+where
+    T: ToroidalBinaryMatrix + Clone,
+{
+    fn drop(&mut self) {
+        wipe(&mut self.block);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn output(rng: &mut TalosRng, len: usize) -> Vec<u8> {
+        let mut bytes = vec![0; len];
+        rng.fill_bytes(&mut bytes);
+        bytes
+    }
+
+    #[test]
+    fn same_seed_gives_same_output() {
+        let mut a = TalosRng::from_seed([9; BLOCK_BYTES]);
+        let mut b = TalosRng::from_seed([9; BLOCK_BYTES]);
+        assert_eq!(output(&mut a, 100), output(&mut b, 100));
+        assert_eq!(a.next_u64(), b.next_u64());
+
+        let mut c = TalosRng::from_seed([10; BLOCK_BYTES]);
+        let mut a = TalosRng::from_seed([9; BLOCK_BYTES]);
+        assert_ne!(output(&mut a, 64), output(&mut c, 64));
+    }
+
+    #[test]
+    fn output_does_not_depend_on_request_sizes() {
+        let whole = output(&mut TalosRng::from_seed([9; BLOCK_BYTES]), 3 * BLOCK_BYTES);
+        let mut rng = TalosRng::from_seed([9; BLOCK_BYTES]);
+        let mut pieces = output(&mut rng, 5);
+        pieces.extend_from_slice(&rng.next_u32().to_le_bytes());
+        pieces.extend_from_slice(&rng.next_u64().to_le_bytes());
+        pieces.extend_from_slice(&output(&mut rng, 3 * BLOCK_BYTES - pieces.len()));
+        assert_eq!(pieces, whole);
+    }
+
+    #[test]
+    fn nonce_changes_output() {
+        let schedule = Key::new(&[9; 16]).unwrap().schedule();
+        let mut a = TalosRng::<ToroidalBitMatrix>::new(&schedule, 1);
+        let mut b = TalosRng::<ToroidalBitMatrix>::new(&schedule, 2);
+        assert_ne!(output(&mut a, 64), output(&mut b, 64));
+    }
+}
@@ -0,0 +1,133 @@
+// 2025 Steven Chiacchira
+use crate::automata::Automaton;
+use crate::encrypt::{
+    decrypt_block_in_place, encrypt_block_in_place, DEFAULT_LAYERS, DEFAULT_ROUNDS,
+};
+use crate::matrix::{ToroidalBinaryMatrix, ToroidalBoolMatrix};
+use cipher::consts::{U1, U32};
+use cipher::inout::InOut;
+use cipher::{
+    Block, BlockBackend, BlockCipher, BlockClosure, BlockDecryptMut, BlockEncryptMut,
+    BlockSizeUser, ParBlocksSizeUser,
+};
+
+/// The Talos algorithm with a 256 bit block, implementing the
+/// [RustCrypto](https://github.com/RustCrypto/traits) [`BlockEncryptMut`] and
+/// [`BlockDecryptMut`] traits.
+///
+/// Each processed block advances the automata, so blocks must be decrypted in the order they were
+/// encrypted, starting from automata in the same state; this is why only the `Mut` traits are
+/// implemented. No padding is applied to the blocks.
+/// Notably *DOES NOT* perform the temporal seeding as defined in RFC-1.
+#[derive(Debug, Clone)]
+pub struct Talos256<T = ToroidalBoolMatrix>
+where
+    T: ToroidalBinaryMatrix + Clone,
+{
+    shift_automata: Automaton<T>,
+    transpose_automata: Automaton<T>,
+}
+
+impl<T> Talos256<T>
+where
+    T: ToroidalBinaryMatrix + Clone,
+{
+    /// Creates a new cipher from already seeded automata.
+    pub fn new(shift_automata: Automaton<T>, transpose_automata: Automaton<T>) -> Self {
+        Self {
+            shift_automata,
+            transpose_automata,
+        }
+    }
+    /// Returns the shift and transpose automata, in their current states.
+    pub fn into_automata(self) -> (Automaton<T>, Automaton<T>) {
+        (self.shift_automata, self.transpose_automata)
+    }
+}
+
+impl<T> BlockSizeUser for Talos256<T>
+where
+    T: ToroidalBinaryMatrix + Clone,
+{
+    type BlockSize = U32;
+}
+
+impl<T> BlockCipher for Talos256<T> where T: ToroidalBinaryMatrix + Clone {}
+
+impl<T> BlockEncryptMut for Talos256<T>
+where
+    T: ToroidalBinaryMatrix + Clone,
+{
+    fn encrypt_with_backend_mut(&mut self, f: impl BlockClosure<BlockSize = Self::BlockSize>) {
+        f.call(&mut TalosBackend {
+            cipher: self,
+            decrypt: false,
+        });
+    }
+}
+
+impl<T> BlockDecryptMut for Talos256<T>
+where
+    T: ToroidalBinaryMatrix + Clone,
+{
+    fn decrypt_with_backend_mut(&mut self, f: impl BlockClosure<BlockSize = Self::BlockSize>) {
+        f.call(&mut TalosBackend {
+            cipher: self,
+            decrypt: true,
+        });
+    }
+}
+
+/// Backend processing the blocks handed to [`Talos256`] one at a time.
+struct TalosBackend<'a, T>
+where
+    T: ToroidalBinaryMatrix + Clone,
+{
+    cipher: &'a mut Talos256<T>,
+    decrypt: bool,
+}
+
+impl<T> BlockSizeUser for TalosBackend<'_, T>
+where
+    T: ToroidalBinaryMatrix + Clone,
+{
+    type BlockSize = U32;
+}
+
+impl<T> ParBlocksSizeUser for TalosBackend<'_, T>
+where
+    T: ToroidalBinaryMatrix + Clone,
+{
+    type ParBlocksSize = U1;
+}
+
+impl<T> BlockBackend for TalosBackend<'_, T>
+where
+    T: ToroidalBinaryMatrix + Clone,
+{
+    fn proc_block(&mut self, mut block: InOut<'_, '_, Block<Self>>) {
+        let Talos256 {
+            shift_automata,
+            transpose_automata,
+        } = &mut *self.cipher;
+        let mut bytes = block.clone_in();
+        if self.decrypt {
+            decrypt_block_in_place(
+                &mut bytes,
+                DEFAULT_ROUNDS,
+                DEFAULT_LAYERS,
+                shift_automata,
+                transpose_automata,
+            );
+        } else {
+            encrypt_block_in_place(
+                &mut bytes,
+                DEFAULT_ROUNDS,
+                DEFAULT_LAYERS,
+                shift_automata,
+                transpose_automata,
+            );
+        }
+        *block.get_out() = bytes;
+    }
+}
@@ -0,0 +1,49 @@
+// 2025 Steven Chiacchira
+use crate::encrypt::scramble_permutations_256;
+use crate::matrix::{invert_permutation, ToroidalBinaryMatrix};
+
+/// A permutation layer applied to each 16x16 message block, keyed by the state of the transpose
+/// automaton. Block encryption only relies on [`Scrambler::unscramble`] undoing
+/// [`Scrambler::scramble`] under the same key, so alternative layers can be used with
+/// [`encrypt_message_with_scrambler_256`](crate::encrypt::encrypt_message_with_scrambler_256).
+/// The word-based paths such as [`encrypt_in_place`](crate::encrypt::encrypt_in_place) and
+/// [`TalosCipher`](crate::encrypt::TalosCipher) always use [`Rfc0Scrambler`].
+pub trait Scrambler {
+    /// Permutes the elements of `message_matrix` as determined by `key`.
+    fn scramble<T>(&self, message_matrix: &mut T, key: &T)
+    where
+        T: ToroidalBinaryMatrix;
+    /// Undoes [`Scrambler::scramble`] with the same `key`.
+    fn unscramble<T>(&self, message_matrix: &mut T, key: &T)
+    where
+        T: ToroidalBinaryMatrix;
+}
+
+/// The matrix scrambling algorithm $V$ explained in RFC-0, which permutes the rows and then the
+/// columns of a block by the permutations of [`scramble_permutations_256`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Rfc0Scrambler;
+
+impl Scrambler for Rfc0Scrambler {
+    fn scramble<T>(&self, message_matrix: &mut T, key: &T)
+    where
+        T: ToroidalBinaryMatrix,
+    {
+        let (rows, cols) = scramble_permutations_256(key);
+        // the permutations are always valid for a 16x16 block
+        message_matrix.permute_rows(&rows).unwrap();
+        message_matrix.permute_cols(&cols).unwrap();
+    }
+    fn unscramble<T>(&self, message_matrix: &mut T, key: &T)
+    where
+        T: ToroidalBinaryMatrix,
+    {
+        let (rows, cols) = scramble_permutations_256(key);
+        message_matrix
+            .permute_cols(&invert_permutation(&cols))
+            .unwrap();
+        message_matrix
+            .permute_rows(&invert_permutation(&rows))
+            .unwrap();
+    }
+}
@@ -0,0 +1,867 @@
+// 2025 Steven Chiacchira
+use crate::automata::{Automaton, AutomatonRule, KeystreamSource};
+use crate::encrypt::analysis::{cipher_rounds_to_full_diffusion, MAX_DIFFUSION_ROUNDS};
+use crate::encrypt::{
+    check_ciphertext_len, decrypt_block_in_place, decrypt_block_in_place_traced, decrypt_ctr_block,
+    encrypt_block_in_place, encrypt_block_in_place_traced, encrypt_blocks, encrypt_ctr_block,
+    iter_bits, mac_with_aad_256, pad_message_256, unpad_message_256, verify_tag_256, wipe,
+    BlockTracer, DecryptError, DecryptReader, EncryptBlocks, EncryptWriter, KeySchedule,
+    KeystreamCombiner, KeystreamSources, SharedTraceSink, TalosCipherBuilder, TalosMac,
+    TraceDirection, BLOCK_BYTES, DEFAULT_LAYERS, DEFAULT_ROUNDS,
+};
+use crate::matrix::{
+    DynBinaryMatrix, MatrixConstructError, MatrixStorage, ToroidalBinaryMatrix, ToroidalBoolMatrix,
+};
+use crate::parse::{parse_bool_table, InitMatrixIssue, TemporalSeedMap};
+use std::io::{Read, Write};
+use std::{error, fmt};
+
+/// Fewest generations the automata may advance per block. Changes to a cell take a generation to
+/// reach each neighbor, so fewer generations leave parts of a 16x16 state unaffected by the rest.
+pub const MIN_ROUNDS: u32 = 8;
+
+/// Error arising from configuring a [`TalosCipher`].
+#[derive(Debug)]
+pub enum ConfigError {
+    /// The automata must advance at least [`MIN_ROUNDS`] generations per block. Holds the count
+    /// which was given.
+    TooFewRounds(u32),
+    /// At least one layer must be applied to each block.
+    NoLayers(),
+    /// A combined keystream must have at least one source.
+    NoKeystreamSources(),
+    /// Rounds chosen by measurement must reach full diffusion within
+    /// [`MAX_DIFFUSION_ROUNDS`](crate::encrypt::analysis::MAX_DIFFUSION_ROUNDS). Holds the
+    /// threshold which was given.
+    DiffusionNotReached(f64),
+    /// Every keystream source must reach a balanced density after seeding, see
+    /// [`KEYSTREAM_TARGET_DENSITY`](crate::encrypt::KEYSTREAM_TARGET_DENSITY). Holds the index of
+    /// the first source which did not.
+    DegenerateKeystreamSource(usize),
+    /// A cipher must be given a key, see [`TalosCipherBuilder::key`].
+    NoKey(),
+    /// Only blocks of [`BLOCK_BITS`] bits are supported. Holds the size which was given.
+    UnsupportedBlockSize(usize),
+    /// A rule given by name must be `talos` or a rulestring, see
+    /// [`TalosCipherBuilder::rule_name`]. Holds the name which was given.
+    UnknownRule(String),
+    /// Init matrices must be valid 16x16 init matrices, see
+    /// [`validate_init_matrix`](crate::parse::validate_init_matrix). Holds the first problem
+    /// found.
+    InvalidInitMatrix(InitMatrixIssue),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::TooFewRounds(rounds) => write!(
+                f,
+                "{} rounds is too few, at least {} are required",
+                rounds, MIN_ROUNDS
+            ),
+            ConfigError::NoLayers() => write!(f, "at least one layer is required"),
+            ConfigError::NoKeystreamSources() => {
+                write!(f, "at least one keystream source is required")
+            }
+            ConfigError::DiffusionNotReached(threshold) => write!(
+                f,
+                "diffusion within {} of one half is not reached in {} rounds",
+                threshold, MAX_DIFFUSION_ROUNDS
+            ),
+            ConfigError::DegenerateKeystreamSource(i) => write!(
+                f,
+                "keystream source {} stays nearly all dead or all alive",
+                i
+            ),
+            ConfigError::NoKey() => write!(f, "a key is required"),
+            ConfigError::UnsupportedBlockSize(bits) => write!(
+                f,
+                "{} bit blocks are not supported, only {} bit blocks are",
+                bits, BLOCK_BITS
+            ),
+            ConfigError::UnknownRule(name) => write!(f, "rule {:?} is unknown", name),
+            ConfigError::InvalidInitMatrix(issue) => write!(f, "init matrix is invalid: {}", issue),
+        }
+    }
+}
+
+impl error::Error for ConfigError {}
+
+/// Number of rows and columns of an init matrix.
+pub const INIT_MATRIX_DIMS: (usize, usize) = (16, 16);
+
+/// Number of bits in a block, the only block size supported.
+pub const BLOCK_BITS: usize = 8 * BLOCK_BYTES;
+
+/// How a [`TalosCipher`] moves its automata from one block to the next.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum BlockMode {
+    /// Each block advances the automata from the states left by the previous block, as described
+    /// in RFC-0.
+    #[default]
+    Chained,
+    /// Each block is encrypted with copies of the automata keyed by a hash of the nonce and the
+    /// index of the block in the session, see
+    /// [`encrypt_message_ctr_256`](crate::encrypt::encrypt_message_ctr_256). Streaming is not
+    /// supported.
+    Ctr,
+}
+
+/// The rule shared by both Talos automata, as described in RFC-0.
+pub const TALOS_RULE: AutomatonRule = AutomatonRule {
+    born: [false, false, true, true, true, true, true, false, false],
+    dies: [true, true, false, false, false, true, true, true, true],
+};
+
+/// Initial state of the transpose automaton. `#` and `.` are fixed alive and dead cells, while
+/// each base-32 digit is filled in from the key and marks the cells seeded with the matching key
+/// bit, see [`TemporalSeedMap::from_init_matrix`].
+pub const TRANSPOSE_INIT_MATRIX: &str = "P#O#N#M#L#K#J#I#
+#L#K.J#I.H.G#F.H
+Q.D#C#B#A#7#6#E#
+#M.X#W.V.U.T.5#G
+R.E.H#G.F#E.S#D.
+#N#Y.T#S.R.D#4.F
+S.F.I#3#2.Q#R#C.
+#O.Z#U.7#Z#C.3#E
+T#G#J.4.6#P.Q.B#
+#P#2.V#5.Y#B.2.D
+U.H#K.W.X#O#P.A.
+#Q.3#L.M.N.A#Z.C
+V.I.4#5.6#7.O#7.
+#R.J.K#L.M.N.Y#B
+W.S#T.U#V#W.X.6#
+#X.Y.Z.2#3.4.5.A";
+
+/// Initial state of the shift automaton, in the same format as [`TRANSPOSE_INIT_MATRIX`].
+pub const SHIFT_INIT_MATRIX: &str = ".A#3.2#Z.Y#X.W#V
+7.B.4.P#O.N.M#L.
+#6#C#5#Q#3.2#Z.U
+E.5#D.6.R#4#7.K#
+#D.4#E.7.S#5.Y.T
+F.C#3.F.A#T#6#J#
+#Q#B.2.G#B.U#X.S
+G#P.A.Z#H.C#V.I#
+.R#O.7#Y.I#D.W#R
+H.E#N.6#X.J.E#H.
+#S.D#M.5#W.K#F.Q
+I#F.C#L.4#V#L.G.
+.T.A.B#K.3#U.M.P
+J#G#H#I#J#2#T#N#
+.U#V.W.X.Y.Z#S.O
+K#L.M#N#O#P.Q#R.";
+
+/// An encryption session owning both Talos automata, the rule they follow, and the number of
+/// generations they advance per block.
+///
+/// The automata are set up from a [`KeySchedule`] and a nonce as described in RFC-1, so callers
+/// never handle them directly. A session encrypts or decrypts a single stream of blocks: each call
+/// continues from the automaton states left by the previous one, so a message split across
+/// several calls must be decrypted by the same sequence of calls on a fresh session.
+///
+/// The shift and transpose automata may be replaced by any [`KeystreamSource`] `S` with
+/// [`TalosCipher::from_sources`], which drives the same layers with another dynamical system.
+#[derive(Debug, Clone)]
+pub struct TalosCipher<T = ToroidalBoolMatrix, S = Automaton<T>>
+where
+    T: ToroidalBinaryMatrix + Clone,
+    S: KeystreamSource<State = T>,
+{
+    shift_automata: S,
+    transpose_automata: S,
+    rule: AutomatonRule,
+    rounds: u32,
+    layers: u32,
+    nonce: u64,
+    mode: BlockMode,
+    /// Index of the next block processed in [`BlockMode::Ctr`].
+    ctr_block: u64,
+    authenticated: bool,
+    /// Init matrix of the transpose automaton, from which keystream sources also start.
+    transpose_init_matrix: String,
+    keystream: Option<KeystreamSources<T>>,
+    trace: Option<BlockTracer>,
+}
+
+impl<T> TalosCipher<T>
+where
+    T: ToroidalBinaryMatrix + Clone,
+{
+    /// Creates a new session for the key expanded into `schedule` and `nonce`.
+    pub fn new(schedule: &KeySchedule, nonce: u64) -> Self {
+        // the initial matrices are valid 16x16 tables
+        Self::with_states(schedule, nonce, |table| T::new(table)).unwrap()
+    }
+    /// Creates a new session as [`TalosCipher::new`] does, building the initial automaton states
+    /// from their tables with `new_state`.
+    pub fn with_states<F>(
+        schedule: &KeySchedule,
+        nonce: u64,
+        new_state: F,
+    ) -> Result<Self, MatrixConstructError>
+    where
+        F: FnMut(Vec<Vec<bool>>) -> Result<T, MatrixConstructError>,
+    {
+        Self::with_setup(
+            schedule,
+            nonce,
+            &TALOS_RULE,
+            SHIFT_INIT_MATRIX,
+            TRANSPOSE_INIT_MATRIX,
+            new_state,
+        )
+    }
+    /// Creates a new session whose automata follow `rule` from the given init matrices, which
+    /// must hold only base-32 digits, [`TRUE_CHAR`](crate::matrix::TRUE_CHAR) and
+    /// [`FALSE_CHAR`](crate::matrix::FALSE_CHAR).
+    ///
+    /// # Panics
+    /// If an init matrix holds any other character.
+    pub(crate) fn with_setup<F>(
+        schedule: &KeySchedule,
+        nonce: u64,
+        rule: &AutomatonRule,
+        shift_init_matrix: &str,
+        transpose_init_matrix: &str,
+        mut new_state: F,
+    ) -> Result<Self, MatrixConstructError>
+    where
+        F: FnMut(Vec<Vec<bool>>) -> Result<T, MatrixConstructError>,
+    {
+        let char_map = schedule.char_map();
+        let t_table = parse_bool_table(transpose_init_matrix, &char_map)
+            .expect("init matrices must hold only base-32 digits, '#' and '.'");
+        let s_table = parse_bool_table(shift_init_matrix, &char_map)
+            .expect("init matrices must hold only base-32 digits, '#' and '.'");
+
+        let mut transpose_automata = Automaton::new(new_state(t_table)?, rule);
+        let mut shift_automata = Automaton::new(new_state(s_table)?, rule);
+        schedule.seed_transpose_automata(
+            &mut transpose_automata,
+            nonce,
+            &TemporalSeedMap::from_init_matrix(transpose_init_matrix),
+        );
+        schedule.seed_shift_automata(
+            &mut shift_automata,
+            nonce,
+            &TemporalSeedMap::from_init_matrix(shift_init_matrix),
+        );
+
+        Ok(Self {
+            shift_automata,
+            transpose_automata,
+            rule: rule.clone(),
+            rounds: DEFAULT_ROUNDS,
+            layers: DEFAULT_LAYERS,
+            nonce,
+            mode: BlockMode::default(),
+            ctr_block: 0,
+            authenticated: false,
+            transpose_init_matrix: transpose_init_matrix.to_string(),
+            keystream: None,
+            trace: None,
+        })
+    }
+}
+
+impl<T, S> TalosCipher<T, S>
+where
+    T: ToroidalBinaryMatrix + Clone,
+    S: KeystreamSource<State = T>,
+{
+    /// Creates a new session driven by the already set up `shift` and `transpose` sources in
+    /// place of the Talos automata. No seeding is done, so `nonce` only keys the blocks of
+    /// [`BlockMode::Ctr`] and the keystream sources of
+    /// [`TalosCipher::with_keystream`], which remain automata following [`TALOS_RULE`] from
+    /// [`TRANSPOSE_INIT_MATRIX`].
+    pub fn from_sources(shift: S, transpose: S, nonce: u64) -> Self {
+        Self {
+            shift_automata: shift,
+            transpose_automata: transpose,
+            rule: TALOS_RULE,
+            rounds: DEFAULT_ROUNDS,
+            layers: DEFAULT_LAYERS,
+            nonce,
+            mode: BlockMode::default(),
+            ctr_block: 0,
+            authenticated: false,
+            transpose_init_matrix: TRANSPOSE_INIT_MATRIX.to_string(),
+            keystream: None,
+            trace: None,
+        }
+    }
+    /// Sets the number of generations both automata advance before each block, returning the
+    /// modified [`TalosCipher`], or a [`ConfigError`] if `rounds` is below [`MIN_ROUNDS`]. Both
+    /// sides of a session must use the same number of rounds.
+    pub fn with_rounds(mut self, rounds: u32) -> Result<Self, ConfigError> {
+        self.set_rounds(rounds)?;
+        Ok(self)
+    }
+    /// Sets the number of generations both automata advance before each block. See
+    /// [`TalosCipher::with_rounds`].
+    pub fn set_rounds(&mut self, rounds: u32) -> Result<(), ConfigError> {
+        if rounds < MIN_ROUNDS {
+            return Err(ConfigError::TooFewRounds(rounds));
+        }
+        self.rounds = rounds;
+        Ok(())
+    }
+    /// Sets the number of generations both automata advance before each block to the fewest
+    /// after which a change to a single cell of the current automaton states spreads to about
+    /// half of the next block, as measured by [`cipher_rounds_to_full_diffusion`] with
+    /// `threshold`, but never fewer than [`MIN_ROUNDS`]. The measurement uses the rule and number
+    /// of layers of this session, so the layers should be set first. Returns the modified
+    /// [`TalosCipher`], or a [`ConfigError`] if diffusion is not reached. Both sides of a session
+    /// choose the same rounds, as long as they measure at the same point of the session.
+    pub fn with_diffusion_rounds(mut self, threshold: f64) -> Result<Self, ConfigError> {
+        let rounds = cipher_rounds_to_full_diffusion(&self, threshold)
+            .ok_or(ConfigError::DiffusionNotReached(threshold))?;
+        self.set_rounds(rounds.max(MIN_ROUNDS))?;
+        Ok(self)
+    }
+    /// Sets the number of scramble, shift and xor layers applied to each block, returning the
+    /// modified [`TalosCipher`], or a [`ConfigError`] if `layers` is zero. Each layer advances the
+    /// automata the configured number of rounds first, so the permutation and mask applied to a
+    /// block depend on more generations at a proportional cost. Every layer only moves and flips
+    /// bits, so a single changed plaintext bit still changes a single ciphertext bit. Both sides
+    /// of a session must use the same number of layers.
+    pub fn with_layers(mut self, layers: u32) -> Result<Self, ConfigError> {
+        self.set_layers(layers)?;
+        Ok(self)
+    }
+    /// Sets the number of layers applied to each block. See [`TalosCipher::with_layers`].
+    pub fn set_layers(&mut self, layers: u32) -> Result<(), ConfigError> {
+        if layers == 0 {
+            return Err(ConfigError::NoLayers());
+        }
+        self.layers = layers;
+        Ok(())
+    }
+    /// Adds a keystream xored into each block after its layers, returning the modified
+    /// [`TalosCipher`], or a [`ConfigError`] if `sources` is empty or a source is left nearly all
+    /// dead or all alive by its schedule. Each schedule in `sources`
+    /// keys its own automaton, set up as the transpose automaton is but with the nonce mixed with
+    /// the position of the source, which advances the configured number of rounds per block. The
+    /// states of the sources are merged by `combiner`, so recovering the keystream of a block
+    /// does not reveal the state of any single source. Both sides of a session must configure the
+    /// same sources and combiner before the first block.
+    pub fn with_keystream(
+        mut self,
+        sources: &[KeySchedule],
+        combiner: KeystreamCombiner,
+    ) -> Result<Self, ConfigError> {
+        self.set_keystream(sources, combiner)?;
+        Ok(self)
+    }
+    /// Sets the combined keystream of the session, restarting its sources. See
+    /// [`TalosCipher::with_keystream`].
+    pub fn set_keystream(
+        &mut self,
+        sources: &[KeySchedule],
+        combiner: KeystreamCombiner,
+    ) -> Result<(), ConfigError> {
+        if sources.is_empty() {
+            return Err(ConfigError::NoKeystreamSources());
+        }
+        self.keystream = Some(KeystreamSources::new(
+            sources,
+            self.nonce,
+            combiner,
+            self.transpose_automata.get_state(),
+            &self.rule,
+            &self.transpose_init_matrix,
+        )?);
+        Ok(())
+    }
+    /// Returns the rule merging the keystream sources, or `None` if the session has no combined
+    /// keystream.
+    pub fn get_keystream_combiner(&self) -> Option<KeystreamCombiner> {
+        self.keystream.as_ref().map(KeystreamSources::get_combiner)
+    }
+    /// Reports every block processed by the session to `sink` as [`TraceEvent`]s, returning the
+    /// modified [`TalosCipher`]. Tracing does not change the output, and block indices count from
+    /// the first block processed after the sink is set.
+    ///
+    /// [`TraceEvent`]: crate::encrypt::TraceEvent
+    pub fn with_trace(mut self, sink: SharedTraceSink) -> Self {
+        self.set_trace(Some(sink));
+        self
+    }
+    /// Sets the sink reported to by the session, or stops tracing if `sink` is `None`. See
+    /// [`TalosCipher::with_trace`].
+    pub fn set_trace(&mut self, sink: Option<SharedTraceSink>) {
+        self.trace = sink.map(BlockTracer::new);
+    }
+    /// Sets how the automata move from one block to the next. Both sides of a session must use the
+    /// same mode. See [`BlockMode`].
+    pub(crate) fn set_mode(&mut self, mode: BlockMode) {
+        self.mode = mode;
+    }
+    /// Returns how the automata move from one block to the next.
+    pub fn get_mode(&self) -> BlockMode {
+        self.mode
+    }
+    /// Marks whether messages of the session are to be sealed with [`TalosCipher::seal`] rather
+    /// than encrypted alone.
+    pub(crate) fn set_authenticated(&mut self, authenticated: bool) {
+        self.authenticated = authenticated;
+    }
+    /// Returns whether messages of the session are to be sealed with [`TalosCipher::seal`] and
+    /// opened with [`TalosCipher::open`], as chosen with [`TalosCipherBuilder::authenticate`].
+    /// Consumers such as [`encrypt_stream`](crate::encrypt::encrypt_stream) follow it; the
+    /// session itself encrypts as asked.
+    pub fn is_authenticated(&self) -> bool {
+        self.authenticated
+    }
+    /// Returns the rule followed by both automata, or only by the keystream sources of a session
+    /// created with [`TalosCipher::from_sources`].
+    pub fn get_rule(&self) -> &AutomatonRule {
+        &self.rule
+    }
+    /// Returns the number of generations both automata advance before each block.
+    pub fn get_rounds(&self) -> u32 {
+        self.rounds
+    }
+    /// Returns the number of layers applied to each block.
+    pub fn get_layers(&self) -> u32 {
+        self.layers
+    }
+    /// Returns the shift and transpose automata of the session.
+    pub(crate) fn automata(&self) -> (&S, &S) {
+        (&self.shift_automata, &self.transpose_automata)
+    }
+    /// Returns the shift and transpose automata of the session mutably.
+    pub(crate) fn automata_mut(&mut self) -> (&mut S, &mut S) {
+        (&mut self.shift_automata, &mut self.transpose_automata)
+    }
+    /// Pads and encrypts `message`, see
+    /// [`encrypt_message_256`](crate::encrypt::encrypt_message_256), or
+    /// [`encrypt_message_ctr_256`](crate::encrypt::encrypt_message_ctr_256) in
+    /// [`BlockMode::Ctr`].
+    pub fn encrypt(&mut self, message: &[u8]) -> Vec<u8> {
+        let mut data = pad_message_256(message);
+        match self.mode {
+            BlockMode::Chained => {
+                for block in data.chunks_exact_mut(BLOCK_BYTES) {
+                    encrypt_session_block(
+                        block,
+                        self.rounds,
+                        self.layers,
+                        &mut self.shift_automata,
+                        &mut self.transpose_automata,
+                        self.keystream.as_mut(),
+                        self.trace.as_mut(),
+                    );
+                }
+            }
+            BlockMode::Ctr => self.apply_ctr(&mut data, TraceDirection::Encrypt),
+        }
+
+        data
+    }
+    /// Decrypts `ciphertext` and removes its padding, returning a [`DecryptError`] if the
+    /// ciphertext is not a whole number of blocks or the padding is invalid.
+    pub fn decrypt(&mut self, ciphertext: &[u8]) -> Result<Vec<u8>, DecryptError> {
+        check_ciphertext_len(ciphertext.len())?;
+        let mut data = ciphertext.to_vec();
+        match self.mode {
+            BlockMode::Chained => {
+                for block in data.chunks_exact_mut(BLOCK_BYTES) {
+                    decrypt_session_block(
+                        block,
+                        self.rounds,
+                        self.layers,
+                        &mut self.shift_automata,
+                        &mut self.transpose_automata,
+                        self.keystream.as_mut(),
+                        self.trace.as_mut(),
+                    );
+                }
+            }
+            BlockMode::Ctr => self.apply_ctr(&mut data, TraceDirection::Decrypt),
+        }
+
+        unpad_message_256(data)
+    }
+    /// Encrypts or decrypts the whole blocks of `data` in place in [`BlockMode::Ctr`], numbering
+    /// them on from the blocks of earlier calls, so later calls do not reuse the keys of this one.
+    /// Only the start and end of each block are traced.
+    fn apply_ctr(&mut self, data: &mut [u8], direction: TraceDirection) {
+        for block in data.chunks_exact_mut(BLOCK_BYTES) {
+            if let Some(tracer) = &mut self.trace {
+                tracer.block_start(direction, block);
+            }
+            match direction {
+                TraceDirection::Encrypt => {
+                    encrypt_ctr_block(
+                        block,
+                        self.nonce,
+                        self.ctr_block,
+                        self.rounds,
+                        self.layers,
+                        &self.shift_automata,
+                        &self.transpose_automata,
+                    );
+                    if let Some(keystream) = &mut self.keystream {
+                        keystream.apply(block, self.rounds);
+                    }
+                }
+                TraceDirection::Decrypt => {
+                    if let Some(keystream) = &mut self.keystream {
+                        keystream.apply(block, self.rounds);
+                    }
+                    decrypt_ctr_block(
+                        block,
+                        self.nonce,
+                        self.ctr_block,
+                        self.rounds,
+                        self.layers,
+                        &self.shift_automata,
+                        &self.transpose_automata,
+                    );
+                }
+            }
+            if let Some(tracer) = &mut self.trace {
+                tracer.block_end(block);
+            }
+            self.ctr_block += 1;
+        }
+    }
+    /// Replaces both automaton states and the state of every keystream source with states derived
+    /// from the current ones by a one-way step, so that states captured after the ratchet do not
+    /// reveal the states used before it. Both sides of a session must ratchet after the same
+    /// number of blocks.
+    ///
+    /// Copies of both automata encrypt the transpose state, the shift state and then the state of
+    /// each keystream source as single blocks, and each result is xored with the state it
+    /// encrypted, as in the Davies-Meyer construction. The first result becomes the new shift
+    /// state, the second the new transpose state and the rest the new states of the sources.
+    pub fn ratchet(&mut self) {
+        let mut shift_copy = self.shift_automata.clone();
+        let mut transpose_copy = self.transpose_automata.clone();
+        let (rounds, layers) = (self.rounds, self.layers);
+        let mut one_way = |state: &[u8]| {
+            let mut block = state.to_vec();
+            encrypt_block_in_place(
+                &mut block,
+                rounds,
+                layers,
+                &mut shift_copy,
+                &mut transpose_copy,
+            );
+            for (value, byte) in block.iter_mut().zip(state) {
+                *value ^= byte;
+            }
+            block
+        };
+
+        let mut shift_state = self.shift_automata.get_state().to_bytes();
+        let mut transpose_state = self.transpose_automata.get_state().to_bytes();
+        let mut new_shift = one_way(&transpose_state);
+        let mut new_transpose = one_way(&shift_state);
+        if let Some(keystream) = &mut self.keystream {
+            for source in keystream.automata_mut() {
+                let mut state = source.get_state().to_bytes();
+                let mut new_state = one_way(&state);
+                source.get_state_mut().set_cells(iter_bits(&new_state));
+                wipe(&mut state);
+                wipe(&mut new_state);
+            }
+        }
+
+        self.shift_automata
+            .get_state_mut()
+            .set_cells(iter_bits(&new_shift));
+        self.transpose_automata
+            .get_state_mut()
+            .set_cells(iter_bits(&new_transpose));
+        for bytes in [
+            &mut shift_state,
+            &mut transpose_state,
+            &mut new_shift,
+            &mut new_transpose,
+        ] {
+            wipe(bytes);
+        }
+    }
+    /// Encrypts `message` as [`TalosCipher::encrypt`] does and appends a tag binding the
+    /// ciphertext and `associated_data`, see
+    /// [`seal_authenticated_with_aad`](crate::encrypt::seal_authenticated_with_aad). The tag is
+    /// keyed by the state of the shift automaton before encryption.
+    pub fn seal(&mut self, message: &[u8], associated_data: &[u8]) -> Vec<u8> {
+        let key_automata = self.shift_automata.clone();
+        let mut sealed = self.encrypt(message);
+        let tag = mac_with_aad_256(associated_data, &sealed, &key_automata);
+        sealed.extend_from_slice(&tag);
+
+        sealed
+    }
+    /// Verifies the tag appended by [`TalosCipher::seal`] and decrypts the message, returning a
+    /// [`DecryptError::AuthenticationFailed`] without decrypting anything if the tag does not
+    /// match `associated_data` and the ciphertext.
+    pub fn open(&mut self, sealed: &[u8], associated_data: &[u8]) -> Result<Vec<u8>, DecryptError> {
+        let ciphertext = verify_tag_256(sealed, associated_data, &self.shift_automata)?;
+        self.decrypt(ciphertext)
+    }
+    /// Returns an [`EncryptWriter`] encrypting into `inner` with this session.
+    ///
+    /// # Panics
+    /// If the session is in [`BlockMode::Ctr`], which cannot be streamed.
+    pub fn encrypt_writer<W>(&mut self, inner: W) -> EncryptWriter<'_, W, T, S>
+    where
+        W: Write,
+    {
+        self.assert_streamable();
+        EncryptWriter::new(
+            inner,
+            &mut self.shift_automata,
+            &mut self.transpose_automata,
+        )
+        .with_rounds(self.rounds)
+        .with_layers(self.layers)
+        .with_keystream(self.keystream.as_mut())
+        .with_trace(self.trace.as_mut())
+    }
+    /// Returns an [`EncryptWriter`] sealing into `inner` with this session. The ciphertext is
+    /// authenticated with a [`TalosMac`] as it is written and [`EncryptWriter::finish`] appends
+    /// the tag, so the output matches [`TalosCipher::seal`] on the whole message without holding
+    /// it in memory.
+    ///
+    /// # Panics
+    /// If the session is in [`BlockMode::Ctr`], which cannot be streamed.
+    pub fn seal_writer<W>(&mut self, inner: W, associated_data: &[u8]) -> EncryptWriter<'_, W, T, S>
+    where
+        W: Write,
+    {
+        let mac = TalosMac::with_associated_data(&self.shift_automata, associated_data);
+        self.encrypt_writer(inner).with_mac(Some(mac))
+    }
+    /// Returns an [`EncryptBlocks`] iterator encrypting the bytes of `input` with this session.
+    ///
+    /// # Panics
+    /// If the session is in [`BlockMode::Ctr`], which cannot be streamed.
+    pub fn encrypt_blocks<I>(&mut self, input: I) -> EncryptBlocks<'_, I::IntoIter, T, S>
+    where
+        I: IntoIterator<Item = u8>,
+    {
+        self.assert_streamable();
+        encrypt_blocks(
+            input,
+            &mut self.shift_automata,
+            &mut self.transpose_automata,
+        )
+        .with_rounds(self.rounds)
+        .with_layers(self.layers)
+        .with_keystream(self.keystream.as_mut())
+        .with_trace(self.trace.as_mut())
+    }
+    /// Returns a [`DecryptReader`] decrypting from `inner` with this session.
+    ///
+    /// # Panics
+    /// If the session is in [`BlockMode::Ctr`], which cannot be streamed.
+    pub fn decrypt_reader<R>(&mut self, inner: R) -> DecryptReader<'_, R, T, S>
+    where
+        R: Read,
+    {
+        self.assert_streamable();
+        DecryptReader::new(
+            inner,
+            &mut self.shift_automata,
+            &mut self.transpose_automata,
+        )
+        .with_rounds(self.rounds)
+        .with_layers(self.layers)
+        .with_keystream(self.keystream.as_mut())
+        .with_trace(self.trace.as_mut())
+    }
+    fn assert_streamable(&self) {
+        assert_eq!(
+            self.mode,
+            BlockMode::Chained,
+            "only the chained block mode can be streamed"
+        );
+    }
+}
+
+impl TalosCipher {
+    /// Returns a builder for a session configured beyond the defaults of [`TalosCipher::new`],
+    /// with automaton states of any storage.
+    pub fn builder() -> TalosCipherBuilder {
+        TalosCipherBuilder::default()
+    }
+}
+
+impl TalosCipher<Box<dyn DynBinaryMatrix>> {
+    /// Creates a new session as [`TalosCipher::new`] does, storing the automaton states in
+    /// `storage`.
+    pub fn with_storage(schedule: &KeySchedule, nonce: u64, storage: MatrixStorage) -> Self {
+        // the initial matrices are valid 16x16 tables
+        Self::with_states(schedule, nonce, |table| storage.new_matrix(table)).unwrap()
+    }
+}
+
+/// Encrypts a single 32 byte block of a session in place with `layers` layers, advancing the
+/// automata `rounds` generations before each, then xors in the combined `keystream` if the
+/// session has one. Reports the block to `tracer` if the session is traced.
+pub(crate) fn encrypt_session_block<T, S>(
+    block: &mut [u8],
+    rounds: u32,
+    layers: u32,
+    shift_automata: &mut S,
+    transpose_automata: &mut S,
+    keystream: Option<&mut KeystreamSources<T>>,
+    mut tracer: Option<&mut BlockTracer>,
+) where
+    T: ToroidalBinaryMatrix + Clone,
+    S: KeystreamSource<State = T>,
+{
+    match &mut tracer {
+        Some(tracer) => {
+            tracer.block_start(TraceDirection::Encrypt, block);
+            encrypt_block_in_place_traced(
+                block,
+                rounds,
+                layers,
+                shift_automata,
+                transpose_automata,
+                tracer,
+            );
+        }
+        None => encrypt_block_in_place(block, rounds, layers, shift_automata, transpose_automata),
+    }
+    if let Some(keystream) = keystream {
+        keystream.apply(block, rounds);
+    }
+    if let Some(tracer) = tracer {
+        tracer.block_end(block);
+    }
+}
+
+/// Decrypts a single 32 byte block encrypted by [`encrypt_session_block`] in place.
+pub(crate) fn decrypt_session_block<T, S>(
+    block: &mut [u8],
+    rounds: u32,
+    layers: u32,
+    shift_automata: &mut S,
+    transpose_automata: &mut S,
+    keystream: Option<&mut KeystreamSources<T>>,
+    mut tracer: Option<&mut BlockTracer>,
+) where
+    T: ToroidalBinaryMatrix + Clone,
+    S: KeystreamSource<State = T>,
+{
+    if let Some(tracer) = &mut tracer {
+        tracer.block_start(TraceDirection::Decrypt, block);
+    }
+    if let Some(keystream) = keystream {
+        keystream.apply(block, rounds);
+    }
+    match tracer {
+        Some(tracer) => {
+            decrypt_block_in_place_traced(
+                block,
+                rounds,
+                layers,
+                shift_automata,
+                transpose_automata,
+                tracer,
+            );
+            tracer.block_end(block);
+        }
+        None => decrypt_block_in_place(block, rounds, layers, shift_automata, transpose_automata),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::automata::{CompositeAutomaton, ElementaryAutomaton, RULE_30};
+    use crate::encrypt::Key;
+    use crate::matrix::ToroidalBitMatrix;
+
+    fn keystream_cipher() -> TalosCipher<ToroidalBitMatrix> {
+        let schedule = Key::new(&[5; 16]).unwrap().schedule();
+        let sources = [
+            Key::new(&[6; 16]).unwrap().schedule(),
+            Key::new(&[7; 16]).unwrap().schedule(),
+        ];
+        TalosCipher::new(&schedule, 3)
+            .with_keystream(&sources, KeystreamCombiner::Xor)
+            .unwrap()
+    }
+
+    fn source_states(cipher: &mut TalosCipher<ToroidalBitMatrix>) -> Vec<Vec<u8>> {
+        let keystream = cipher.keystream.as_mut().unwrap();
+        keystream
+            .automata_mut()
+            .iter()
+            .map(|source| source.get_state().to_bytes())
+            .collect()
+    }
+
+    #[test]
+    fn ratchet_replaces_keystream_sources() {
+        let mut cipher = keystream_cipher();
+        let before = source_states(&mut cipher);
+        cipher.ratchet();
+        let after = source_states(&mut cipher);
+        for (before, after) in before.iter().zip(&after) {
+            assert_ne!(before, after);
+        }
+    }
+
+    #[test]
+    fn ratcheted_sessions_round_trip() {
+        let message = b"ratchet between the first and second message";
+        let (mut encrypter, mut decrypter) = (keystream_cipher(), keystream_cipher());
+        let first = encrypter.encrypt(message);
+        encrypter.ratchet();
+        let second = encrypter.encrypt(message);
+        assert_eq!(decrypter.decrypt(&first).unwrap(), message);
+        decrypter.ratchet();
+        assert_eq!(decrypter.decrypt(&second).unwrap(), message);
+    }
+
+    fn state(seed: u8) -> ToroidalBitMatrix {
+        let bytes = (0..BLOCK_BYTES as u8)
+            .map(|i| i.wrapping_mul(seed).rotate_left(3) ^ seed)
+            .collect::<Vec<u8>>();
+        ToroidalBitMatrix::from_bytes(16, 16, &bytes).unwrap()
+    }
+
+    fn round_trip<S>(shift: S, transpose: S)
+    where
+        S: KeystreamSource<State = ToroidalBitMatrix>,
+    {
+        let message = b"driven by another dynamical system".repeat(2);
+        for mode in [BlockMode::Chained, BlockMode::Ctr] {
+            let mut encrypter = TalosCipher::from_sources(shift.clone(), transpose.clone(), 11);
+            let mut decrypter = TalosCipher::from_sources(shift.clone(), transpose.clone(), 11);
+            encrypter.set_mode(mode);
+            decrypter.set_mode(mode);
+            let ciphertext = encrypter.encrypt(&message);
+            assert_ne!(ciphertext[..BLOCK_BYTES], message[..BLOCK_BYTES]);
+            assert_eq!(decrypter.decrypt(&ciphertext).unwrap(), message);
+        }
+    }
+
+    #[test]
+    fn elementary_sources_round_trip() {
+        round_trip(
+            ElementaryAutomaton::new(state(3), RULE_30),
+            ElementaryAutomaton::new(state(5), RULE_30),
+        );
+    }
+
+    #[test]
+    fn composite_sources_round_trip() {
+        let composite = |primary, driver| {
+            CompositeAutomaton::new(
+                Automaton::new(state(primary), &TALOS_RULE),
+                ElementaryAutomaton::new(state(driver), RULE_30),
+            )
+            .unwrap()
+        };
+        round_trip(composite(3, 7), composite(5, 9));
+    }
+}
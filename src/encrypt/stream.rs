@@ -0,0 +1,454 @@
+// 2025 Steven Chiacchira
+use crate::automata::{Automaton, KeystreamSource};
+use crate::encrypt::{
+    decrypt_session_block, encrypt_session_block, pad_message_256, unpad_message_256, wipe,
+    BlockTracer, DecryptError, KeystreamSources, TalosMac, BLOCK_BYTES, DEFAULT_LAYERS,
+    DEFAULT_ROUNDS,
+};
+use crate::matrix::ToroidalBinaryMatrix;
+use std::io::{self, Read, Write};
+
+/// Buffer of plaintext bytes, cleared when dropped if the `zeroize` feature is enabled.
+#[cfg(feature = "zeroize")]
+type PlaintextBuffer = zeroize::Zeroizing<Vec<u8>>;
+/// Buffer of plaintext bytes, cleared when dropped if the `zeroize` feature is enabled.
+#[cfg(not(feature = "zeroize"))]
+type PlaintextBuffer = Vec<u8>;
+
+/// Block of plaintext, cleared when dropped if the `zeroize` feature is enabled.
+#[cfg(feature = "zeroize")]
+type PlaintextBlock = zeroize::Zeroizing<[u8; BLOCK_BYTES]>;
+/// Block of plaintext, cleared when dropped if the `zeroize` feature is enabled.
+#[cfg(not(feature = "zeroize"))]
+type PlaintextBlock = [u8; BLOCK_BYTES];
+
+/// Encrypts data written to it block by block with the Talos algorithm, writing the ciphertext to
+/// an inner writer. The ciphertext is identical to that of
+/// [`encrypt_message_256`](crate::encrypt::encrypt_message_256) on the whole message, but only a
+/// single block is ever held in memory.
+///
+/// The final block is only written once [`EncryptWriter::finish`] is called, since it must be
+/// padded; dropping the writer without finishing it loses the end of the message.
+/// Notably *DOES NOT* perform the temporal seeding as defined in RFC-1.
+pub struct EncryptWriter<'a, W, T, S = Automaton<T>>
+where
+    W: Write,
+    T: ToroidalBinaryMatrix + Clone,
+    S: KeystreamSource<State = T>,
+{
+    inner: W,
+    shift_automata: &'a mut S,
+    transpose_automata: &'a mut S,
+    rounds: u32,
+    layers: u32,
+    keystream: Option<&'a mut KeystreamSources<T>>,
+    trace: Option<&'a mut BlockTracer>,
+    /// MAC absorbing the ciphertext as it is written, whose tag is appended by
+    /// [`EncryptWriter::finish`].
+    mac: Option<TalosMac<S>>,
+    /// Plaintext bytes which do not yet fill a block.
+    buffer: PlaintextBuffer,
+}
+
+impl<'a, W, T, S> EncryptWriter<'a, W, T, S>
+where
+    W: Write,
+    T: ToroidalBinaryMatrix + Clone,
+    S: KeystreamSource<State = T>,
+{
+    /// Creates a new writer encrypting into `inner` with the given automata.
+    pub fn new(inner: W, shift_automata: &'a mut S, transpose_automata: &'a mut S) -> Self {
+        Self {
+            inner,
+            shift_automata,
+            transpose_automata,
+            rounds: DEFAULT_ROUNDS,
+            layers: DEFAULT_LAYERS,
+            keystream: None,
+            trace: None,
+            mac: None,
+            buffer: PlaintextBuffer::from(Vec::with_capacity(BLOCK_BYTES)),
+        }
+    }
+    /// Sets the number of generations the automata advance before each block, as configured on
+    /// a [`TalosCipher`](crate::encrypt::TalosCipher).
+    pub(crate) fn with_rounds(mut self, rounds: u32) -> Self {
+        self.rounds = rounds;
+        self
+    }
+    /// Sets the number of layers applied to each block, as configured on a
+    /// [`TalosCipher`](crate::encrypt::TalosCipher).
+    pub(crate) fn with_layers(mut self, layers: u32) -> Self {
+        self.layers = layers;
+        self
+    }
+    /// Sets the combined keystream xored into each block, as configured on a
+    /// [`TalosCipher`](crate::encrypt::TalosCipher).
+    pub(crate) fn with_keystream(mut self, keystream: Option<&'a mut KeystreamSources<T>>) -> Self {
+        self.keystream = keystream;
+        self
+    }
+    /// Sets the tracer reported to for each block, as configured on a
+    /// [`TalosCipher`](crate::encrypt::TalosCipher).
+    pub(crate) fn with_trace(mut self, trace: Option<&'a mut BlockTracer>) -> Self {
+        self.trace = trace;
+        self
+    }
+    /// Sets the MAC absorbing each ciphertext block, as created by
+    /// [`TalosCipher::seal_writer`](crate::encrypt::TalosCipher::seal_writer).
+    pub(crate) fn with_mac(mut self, mac: Option<TalosMac<S>>) -> Self {
+        self.mac = mac;
+        self
+    }
+    /// Returns a reference to the inner writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+    /// Pads and encrypts the final block, appends the tag if the writer seals, flushes the inner
+    /// writer and returns it.
+    pub fn finish(mut self) -> io::Result<W> {
+        let mut block = pad_message_256(&self.buffer);
+        wipe(&mut *self.buffer);
+        self.write_block(&block)?;
+        wipe(&mut block);
+        if let Some(mac) = self.mac.take() {
+            self.inner.write_all(&mac.finalize())?;
+        }
+        self.inner.flush()?;
+
+        Ok(self.inner)
+    }
+    /// Encrypts a single 32 byte block and writes it to the inner writer.
+    fn write_block(&mut self, block: &[u8]) -> io::Result<()> {
+        let mut block: [u8; BLOCK_BYTES] = block.try_into().expect("blocks must be 32 bytes");
+        encrypt_session_block(
+            &mut block,
+            self.rounds,
+            self.layers,
+            self.shift_automata,
+            self.transpose_automata,
+            self.keystream.as_deref_mut(),
+            self.trace.as_deref_mut(),
+        );
+        if let Some(mac) = &mut self.mac {
+            mac.update(&block);
+        }
+        self.inner.write_all(&block)
+    }
+}
+
+impl<W, T, S> Write for EncryptWriter<'_, W, T, S>
+where
+    W: Write,
+    T: ToroidalBinaryMatrix + Clone,
+    S: KeystreamSource<State = T>,
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut remaining = buf;
+        if !self.buffer.is_empty() {
+            let n_taken = remaining.len().min(BLOCK_BYTES - self.buffer.len());
+            self.buffer.extend_from_slice(&remaining[..n_taken]);
+            remaining = &remaining[n_taken..];
+            if self.buffer.len() < BLOCK_BYTES {
+                return Ok(buf.len());
+            }
+            let block = std::mem::replace(
+                &mut self.buffer,
+                PlaintextBuffer::from(Vec::with_capacity(BLOCK_BYTES)),
+            );
+            self.write_block(&block)?;
+        }
+
+        let mut blocks = remaining.chunks_exact(BLOCK_BYTES);
+        for block in &mut blocks {
+            self.write_block(block)?;
+        }
+        self.buffer.extend_from_slice(blocks.remainder());
+
+        Ok(buf.len())
+    }
+    /// Flushes the inner writer. Bytes which do not yet fill a block remain buffered.
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Iterator over the ciphertext blocks of a message, encrypting each block only when it is
+/// requested. The blocks are identical to those of
+/// [`encrypt_message_256`](crate::encrypt::encrypt_message_256) on the whole message, but only a
+/// single block of the message is ever held in memory. Created by [`encrypt_blocks`].
+/// Notably *DOES NOT* perform the temporal seeding as defined in RFC-1.
+pub struct EncryptBlocks<'a, I, T, S = Automaton<T>>
+where
+    I: Iterator<Item = u8>,
+    T: ToroidalBinaryMatrix + Clone,
+    S: KeystreamSource<State = T>,
+{
+    input: I,
+    shift_automata: &'a mut S,
+    transpose_automata: &'a mut S,
+    rounds: u32,
+    layers: u32,
+    keystream: Option<&'a mut KeystreamSources<T>>,
+    trace: Option<&'a mut BlockTracer>,
+    /// Whether the padded final block has been yielded.
+    finished: bool,
+}
+
+impl<'a, I, T, S> EncryptBlocks<'a, I, T, S>
+where
+    I: Iterator<Item = u8>,
+    T: ToroidalBinaryMatrix + Clone,
+    S: KeystreamSource<State = T>,
+{
+    /// Sets the number of generations the automata advance before each block, as configured on
+    /// a [`TalosCipher`](crate::encrypt::TalosCipher).
+    pub(crate) fn with_rounds(mut self, rounds: u32) -> Self {
+        self.rounds = rounds;
+        self
+    }
+    /// Sets the number of layers applied to each block, as configured on a
+    /// [`TalosCipher`](crate::encrypt::TalosCipher).
+    pub(crate) fn with_layers(mut self, layers: u32) -> Self {
+        self.layers = layers;
+        self
+    }
+    /// Sets the combined keystream xored into each block, as configured on a
+    /// [`TalosCipher`](crate::encrypt::TalosCipher).
+    pub(crate) fn with_keystream(mut self, keystream: Option<&'a mut KeystreamSources<T>>) -> Self {
+        self.keystream = keystream;
+        self
+    }
+    /// Sets the tracer reported to for each block, as configured on a
+    /// [`TalosCipher`](crate::encrypt::TalosCipher).
+    pub(crate) fn with_trace(mut self, trace: Option<&'a mut BlockTracer>) -> Self {
+        self.trace = trace;
+        self
+    }
+}
+
+impl<I, T, S> Iterator for EncryptBlocks<'_, I, T, S>
+where
+    I: Iterator<Item = u8>,
+    T: ToroidalBinaryMatrix + Clone,
+    S: KeystreamSource<State = T>,
+{
+    type Item = [u8; BLOCK_BYTES];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let mut block = [0_u8; BLOCK_BYTES];
+        let n_read = block
+            .iter_mut()
+            .zip(&mut self.input)
+            .map(|(byte, value)| *byte = value)
+            .count();
+        if n_read < BLOCK_BYTES {
+            // the message ran out, so this is the final block and carries the padding
+            block[n_read..].fill((BLOCK_BYTES - n_read) as u8);
+            self.finished = true;
+        }
+        encrypt_session_block(
+            &mut block,
+            self.rounds,
+            self.layers,
+            self.shift_automata,
+            self.transpose_automata,
+            self.keystream.as_deref_mut(),
+            self.trace.as_deref_mut(),
+        );
+
+        Some(block)
+    }
+}
+
+/// Returns an iterator encrypting the bytes of `input` one block at a time as they are needed.
+/// Notably *DOES NOT* perform the temporal seeding as defined in RFC-1.
+pub fn encrypt_blocks<'a, I, T, S>(
+    input: I,
+    shift_automata: &'a mut S,
+    transpose_automata: &'a mut S,
+) -> EncryptBlocks<'a, I::IntoIter, T, S>
+where
+    I: IntoIterator<Item = u8>,
+    T: ToroidalBinaryMatrix + Clone,
+    S: KeystreamSource<State = T>,
+{
+    EncryptBlocks {
+        input: input.into_iter(),
+        shift_automata,
+        transpose_automata,
+        rounds: DEFAULT_ROUNDS,
+        layers: DEFAULT_LAYERS,
+        keystream: None,
+        trace: None,
+        finished: false,
+    }
+}
+
+/// Decrypts ciphertext read from an inner reader block by block with the Talos algorithm. The
+/// plaintext is identical to that of
+/// [`decrypt_message_256`](crate::encrypt::decrypt_message_256) on the whole ciphertext, but only
+/// a couple of blocks are ever held in memory.
+///
+/// The most recently decrypted block is held back until the next one is read, so that its padding
+/// can be removed once the end of the ciphertext is reached. A ciphertext which is not a whole
+/// number of blocks or invalid padding is reported as an [`io::ErrorKind::InvalidData`] error
+/// wrapping a [`DecryptError`].
+/// Notably *DOES NOT* perform the temporal seeding as defined in RFC-1.
+pub struct DecryptReader<'a, R, T, S = Automaton<T>>
+where
+    R: Read,
+    T: ToroidalBinaryMatrix + Clone,
+    S: KeystreamSource<State = T>,
+{
+    inner: R,
+    shift_automata: &'a mut S,
+    transpose_automata: &'a mut S,
+    rounds: u32,
+    layers: u32,
+    keystream: Option<&'a mut KeystreamSources<T>>,
+    trace: Option<&'a mut BlockTracer>,
+    /// The most recently decrypted block, which may still hold padding.
+    held_block: Option<PlaintextBlock>,
+    /// Decrypted bytes ready to be returned.
+    pending: PlaintextBuffer,
+    /// Position of the next byte of `pending` to return.
+    pending_pos: usize,
+    finished: bool,
+}
+
+impl<'a, R, T, S> DecryptReader<'a, R, T, S>
+where
+    R: Read,
+    T: ToroidalBinaryMatrix + Clone,
+    S: KeystreamSource<State = T>,
+{
+    /// Creates a new reader decrypting from `inner` with the given automata.
+    pub fn new(inner: R, shift_automata: &'a mut S, transpose_automata: &'a mut S) -> Self {
+        Self {
+            inner,
+            shift_automata,
+            transpose_automata,
+            rounds: DEFAULT_ROUNDS,
+            layers: DEFAULT_LAYERS,
+            keystream: None,
+            trace: None,
+            held_block: None,
+            pending: PlaintextBuffer::from(Vec::with_capacity(BLOCK_BYTES)),
+            pending_pos: 0,
+            finished: false,
+        }
+    }
+    /// Sets the number of generations the automata advance before each block, as configured on
+    /// a [`TalosCipher`](crate::encrypt::TalosCipher).
+    pub(crate) fn with_rounds(mut self, rounds: u32) -> Self {
+        self.rounds = rounds;
+        self
+    }
+    /// Sets the number of layers applied to each block, as configured on a
+    /// [`TalosCipher`](crate::encrypt::TalosCipher).
+    pub(crate) fn with_layers(mut self, layers: u32) -> Self {
+        self.layers = layers;
+        self
+    }
+    /// Sets the combined keystream xored into each block, as configured on a
+    /// [`TalosCipher`](crate::encrypt::TalosCipher).
+    pub(crate) fn with_keystream(mut self, keystream: Option<&'a mut KeystreamSources<T>>) -> Self {
+        self.keystream = keystream;
+        self
+    }
+    /// Sets the tracer reported to for each block, as configured on a
+    /// [`TalosCipher`](crate::encrypt::TalosCipher).
+    pub(crate) fn with_trace(mut self, trace: Option<&'a mut BlockTracer>) -> Self {
+        self.trace = trace;
+        self
+    }
+    /// Returns a reference to the inner reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+    /// Reads up to a full block from the inner reader, returning the number of bytes read. Fewer
+    /// than [`BLOCK_BYTES`] bytes are only returned at the end of the inner reader.
+    fn read_block(&mut self, block: &mut [u8; BLOCK_BYTES]) -> io::Result<usize> {
+        let mut n_read = 0;
+        while n_read < BLOCK_BYTES {
+            match self.inner.read(&mut block[n_read..]) {
+                Ok(0) => break,
+                Ok(n) => n_read += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(n_read)
+    }
+    /// Decrypts blocks until decrypted bytes are available or the ciphertext is exhausted.
+    fn fill_pending(&mut self) -> io::Result<()> {
+        self.pending.clear();
+        self.pending_pos = 0;
+        while self.pending.is_empty() && !self.finished {
+            let mut block = [0_u8; BLOCK_BYTES];
+            match self.read_block(&mut block)? {
+                0 => {
+                    self.finished = true;
+                    let last_block = self
+                        .held_block
+                        .take()
+                        .ok_or_else(|| invalid_data(DecryptError::TruncatedCiphertext()))?;
+                    let mut message =
+                        unpad_message_256(last_block.to_vec()).map_err(invalid_data)?;
+                    self.pending.extend_from_slice(&message);
+                    wipe(&mut message);
+                }
+                BLOCK_BYTES => {
+                    decrypt_session_block(
+                        &mut block,
+                        self.rounds,
+                        self.layers,
+                        self.shift_automata,
+                        self.transpose_automata,
+                        self.keystream.as_deref_mut(),
+                        self.trace.as_deref_mut(),
+                    );
+                    if let Some(previous) = self.held_block.replace(PlaintextBlock::from(block)) {
+                        self.pending.extend_from_slice(&previous[..]);
+                    }
+                    wipe(&mut block);
+                }
+                _ => {
+                    self.finished = true;
+                    return Err(invalid_data(DecryptError::TruncatedCiphertext()));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<R, T, S> Read for DecryptReader<'_, R, T, S>
+where
+    R: Read,
+    T: ToroidalBinaryMatrix + Clone,
+    S: KeystreamSource<State = T>,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.pending_pos == self.pending.len() {
+            self.fill_pending()?;
+        }
+        let available = &self.pending[self.pending_pos..];
+        let n_copied = available.len().min(buf.len());
+        buf[..n_copied].copy_from_slice(&available[..n_copied]);
+        self.pending_pos += n_copied;
+
+        Ok(n_copied)
+    }
+}
+
+/// Wraps a [`DecryptError`] in an [`io::Error`] of kind [`io::ErrorKind::InvalidData`].
+fn invalid_data(error: DecryptError) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
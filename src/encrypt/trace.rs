@@ -0,0 +1,137 @@
+// 2025 Steven Chiacchira
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+/// Whether a traced block is being encrypted or decrypted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TraceDirection {
+    /// The block is plaintext being encrypted.
+    Encrypt,
+    /// The block is ciphertext being decrypted.
+    Decrypt,
+}
+
+/// Event reported to a [`TraceSink`] by a traced [`TalosCipher`](crate::encrypt::TalosCipher),
+/// see [`TalosCipher::with_trace`](crate::encrypt::TalosCipher::with_trace).
+///
+/// Blocks are given as their 32 bytes, which load into 16x16 matrices with
+/// [`ToroidalBinaryMatrix::from_bytes`](crate::matrix::ToroidalBinaryMatrix::from_bytes). Block
+/// indices count every block of the session, across calls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceEvent<'a> {
+    /// A block is about to be processed. Holds the block as given: the plaintext block when
+    /// encrypting and the ciphertext block when decrypting.
+    BlockStart {
+        block_index: u64,
+        direction: TraceDirection,
+        block: &'a [u8],
+    },
+    /// Both automata were advanced for layer `layer` of a block. Holds the generations the shift
+    /// and transpose automata have reached, see
+    /// [`Automaton::get_generation`](crate::automata::Automaton::get_generation). When decrypting,
+    /// every layer is keyed before the first is undone.
+    LayerKeyed {
+        block_index: u64,
+        layer: u32,
+        shift_generation: u64,
+        transpose_generation: u64,
+    },
+    /// Layer `layer` of a block was scrambled. Holds the block before and after scrambling, so
+    /// when decrypting `after` is the block being unscrambled and `before` the result.
+    Scrambled {
+        block_index: u64,
+        layer: u32,
+        before: &'a [u8],
+        after: &'a [u8],
+    },
+    /// A block is finished. Holds the result: the ciphertext block when encrypting and the
+    /// plaintext block when decrypting.
+    BlockEnd { block_index: u64, block: &'a [u8] },
+}
+
+/// Receiver of the [`TraceEvent`]s of a traced [`TalosCipher`](crate::encrypt::TalosCipher).
+/// Tracing is opt-in: a session without a sink builds no events. Events carry plaintext and key
+/// material, so sinks are meant for debugging and test vectors only.
+pub trait TraceSink {
+    /// Receives `event`. Called in the order the cipher processes each block.
+    fn event(&mut self, event: TraceEvent<'_>);
+}
+
+impl<F> TraceSink for F
+where
+    F: FnMut(TraceEvent<'_>),
+{
+    fn event(&mut self, event: TraceEvent<'_>) {
+        self(event)
+    }
+}
+
+/// A [`TraceSink`] shared between a session and its caller, which keeps a clone to inspect the
+/// sink afterwards. Clones of a traced session report to the same sink.
+pub type SharedTraceSink = Arc<Mutex<dyn TraceSink + Send>>;
+
+/// The sink of a traced session and the number of blocks it has been told about.
+#[derive(Clone)]
+pub(crate) struct BlockTracer {
+    sink: SharedTraceSink,
+    n_blocks: u64,
+}
+
+impl BlockTracer {
+    pub(crate) fn new(sink: SharedTraceSink) -> Self {
+        Self { sink, n_blocks: 0 }
+    }
+    /// Reports the start of the next block.
+    pub(crate) fn block_start(&mut self, direction: TraceDirection, block: &[u8]) {
+        self.report(TraceEvent::BlockStart {
+            block_index: self.n_blocks,
+            direction,
+            block,
+        });
+    }
+    /// Reports the generations reached by the automata for `layer` of the current block.
+    pub(crate) fn layer_keyed(
+        &mut self,
+        layer: u32,
+        shift_generation: u64,
+        transpose_generation: u64,
+    ) {
+        self.report(TraceEvent::LayerKeyed {
+            block_index: self.n_blocks,
+            layer,
+            shift_generation,
+            transpose_generation,
+        });
+    }
+    /// Reports the scrambling of `layer` of the current block.
+    pub(crate) fn scrambled(&mut self, layer: u32, before: &[u8], after: &[u8]) {
+        self.report(TraceEvent::Scrambled {
+            block_index: self.n_blocks,
+            layer,
+            before,
+            after,
+        });
+    }
+    /// Reports the end of the current block, moving on to the next.
+    pub(crate) fn block_end(&mut self, block: &[u8]) {
+        self.report(TraceEvent::BlockEnd {
+            block_index: self.n_blocks,
+            block,
+        });
+        self.n_blocks += 1;
+    }
+
+    fn report(&self, event: TraceEvent<'_>) {
+        // a sink which panicked has already lost events, so later ones are still delivered
+        let mut sink = self.sink.lock().unwrap_or_else(|e| e.into_inner());
+        sink.event(event);
+    }
+}
+
+impl fmt::Debug for BlockTracer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BlockTracer")
+            .field("n_blocks", &self.n_blocks)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,152 @@
+// 2025 Steven Chiacchira
+use crate::automata::Automaton;
+use crate::encrypt::{
+    seed_key, Key, KeySchedule, SHIFT_INIT_MATRIX, TALOS_RULE, TRANSPOSE_INIT_MATRIX,
+};
+use crate::matrix::{PackedBitMatrix, ToroidalBinaryMatrix};
+use crate::parse::{parse_bool_table, TemporalSeedMap};
+use std::fmt;
+
+/// Fraction of alive cells below which, or above one minus which, an automaton set up by a key is
+/// degenerate.
+pub const WEAK_KEY_MIN_DENSITY: f64 = 0.1;
+
+/// Number of generations after setup within which an automaton falling into a cycle marks its key
+/// as weak. Encrypting a block advances the automata [`DEFAULT_ROUNDS`] generations per layer, so
+/// a cycle within this many generations repeats keys within a short message.
+///
+/// [`DEFAULT_ROUNDS`]: crate::encrypt::DEFAULT_ROUNDS
+pub const WEAK_KEY_CYCLE_GENERATIONS: u32 = 1024;
+
+/// Which automaton of a key made it weak.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyAutomaton {
+    /// The automaton keying the row and column shifts.
+    Shift,
+    /// The automaton keying the scrambling transpositions.
+    Transpose,
+}
+
+/// Reason a key is weak, found by [`detect_weak_key`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WeakKeyReason {
+    /// The automaton is nearly all dead or nearly all alive, see [`WEAK_KEY_MIN_DENSITY`]. Holds
+    /// the fraction of its cells which are alive.
+    Degenerate(KeyAutomaton, f64),
+    /// The automaton falls into a cycle within [`WEAK_KEY_CYCLE_GENERATIONS`] generations. Holds
+    /// the period of the cycle.
+    ShortCycle(KeyAutomaton, u32),
+    /// The state of the automaton equals a translation, reflection or the transpose of itself.
+    /// The Talos rule treats every neighbor alike, so such a symmetry is never lost.
+    Symmetric(KeyAutomaton),
+}
+
+impl fmt::Display for WeakKeyReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WeakKeyReason::Degenerate(automaton, density) => write!(
+                f,
+                "{} automaton has only {:.1}% of its cells {}",
+                automaton,
+                100.0 * density.min(1.0 - density),
+                if *density < 0.5 { "alive" } else { "dead" }
+            ),
+            WeakKeyReason::ShortCycle(automaton, period) => write!(
+                f,
+                "{} automaton repeats every {} generations",
+                automaton, period
+            ),
+            WeakKeyReason::Symmetric(automaton) => {
+                write!(f, "{} automaton has a symmetric state", automaton)
+            }
+        }
+    }
+}
+
+impl fmt::Display for KeyAutomaton {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyAutomaton::Shift => write!(f, "shift"),
+            KeyAutomaton::Transpose => write!(f, "transpose"),
+        }
+    }
+}
+
+/// Checks whether `key` sets up degenerate automata, returning the first [`WeakKeyReason`] found
+/// or `None` if the key is fine. See [`detect_weak_schedule`].
+pub fn detect_weak_key(key: &Key) -> Option<WeakKeyReason> {
+    detect_weak_schedule(&key.schedule())
+}
+
+/// Checks whether `schedule` sets up degenerate automata. Both automata are set up from the
+/// default init matrices and seeded with the key alone, since a nonce is seeded on top of the
+/// same states for every message, then checked in turn for a density outside
+/// [`WEAK_KEY_MIN_DENSITY`], a symmetric state and a cycle within
+/// [`WEAK_KEY_CYCLE_GENERATIONS`] generations.
+pub fn detect_weak_schedule(schedule: &KeySchedule) -> Option<WeakKeyReason> {
+    let char_map = schedule.char_map();
+    let setups = [
+        (
+            KeyAutomaton::Shift,
+            SHIFT_INIT_MATRIX,
+            schedule.shift_offset,
+            &schedule.shift_seeds,
+        ),
+        (
+            KeyAutomaton::Transpose,
+            TRANSPOSE_INIT_MATRIX,
+            schedule.transpose_offset,
+            &schedule.transpose_seeds,
+        ),
+    ];
+
+    for (kind, init_matrix, offset, seeds) in setups {
+        // the default init matrices are valid 16x16 tables
+        let table = parse_bool_table(init_matrix, &char_map).unwrap();
+        let mut automaton = Automaton::new(PackedBitMatrix::new(table).unwrap(), &TALOS_RULE);
+        seed_key(
+            &mut automaton,
+            offset,
+            seeds,
+            &TemporalSeedMap::from_init_matrix(init_matrix),
+        );
+
+        let density = automaton.get_density();
+        if !(WEAK_KEY_MIN_DENSITY..=1.0 - WEAK_KEY_MIN_DENSITY).contains(&density) {
+            return Some(WeakKeyReason::Degenerate(kind, density));
+        }
+        if is_symmetric(automaton.get_state()) {
+            return Some(WeakKeyReason::Symmetric(kind));
+        }
+        if let Some(cycle) = automaton.find_cycle(WEAK_KEY_CYCLE_GENERATIONS) {
+            return Some(WeakKeyReason::ShortCycle(kind, cycle.period));
+        }
+    }
+
+    None
+}
+
+/// Returns whether `state` equals a nontrivial translation of itself, its reflection about
+/// either axis or, when square, its transpose.
+fn is_symmetric<T>(state: &T) -> bool
+where
+    T: ToroidalBinaryMatrix + Clone,
+{
+    let bytes = state.to_bytes();
+    let matches = |transform: &dyn Fn(&mut T)| {
+        let mut copy = state.clone();
+        transform(&mut copy);
+        copy.to_bytes() == bytes
+    };
+
+    let (rows, cols) = (state.get_rows() as isize, state.get_cols() as isize);
+    let translated = (0..rows)
+        .flat_map(|dy| (0..cols).map(move |dx| (dx, dy)))
+        .skip(1)
+        .any(|(dx, dy)| matches(&|copy: &mut T| copy.shift(dx, dy)));
+
+    translated
+        || matches(&|copy: &mut T| copy.flip_horizontal())
+        || matches(&|copy: &mut T| copy.flip_vertical())
+        || (rows == cols && matches(&|copy: &mut T| copy.transpose()))
+}
@@ -0,0 +1,155 @@
+// 2025 Steven Chiacchira
+use crate::automata::{DensityError, NeighborhoodError, RuleParseError};
+use crate::encrypt::frames::FrameError;
+use crate::encrypt::{ConfigError, DecodeError, DecryptError, FileError, KeyError};
+use crate::matrix::{MatrixConstructError, MatrixOpError};
+use crate::parse::binary::StateReadError;
+use crate::parse::image::ImageReadError;
+use crate::parse::seed_phrase::SeedPhraseError;
+use crate::parse::{
+    CharMapError, HexStateError, LifeReadError, SeedMapError, TableReadError, TableWriteError,
+};
+use crate::SelfTestError;
+use std::{error, fmt, io};
+
+/// Any error returned by this crate, so that callers working across several modules can
+/// propagate them all with `?`. Every error type of the crate converts into it with [`From`],
+/// and the error it wraps is given as its [`source`](error::Error::source).
+///
+/// A [`FileError`] is unwrapped into the [`Error::Io`], [`Error::Decrypt`] or [`Error::Config`]
+/// it holds.
+#[derive(Debug)]
+pub enum Error {
+    /// Reading input or writing output failed.
+    Io(io::Error),
+    /// A table of `bool` values could not be read, see [`TableReadError`].
+    TableRead(TableReadError),
+    /// A table of `bool` values could not be written, see [`TableWriteError`].
+    TableWrite(TableWriteError),
+    /// A matrix could not be constructed, see [`MatrixConstructError`].
+    MatrixConstruct(MatrixConstructError),
+    /// A matrix operation could not be applied, see [`MatrixOpError`].
+    MatrixOp(MatrixOpError),
+    /// A ciphertext could not be decrypted, including when its padding or tag is invalid, see
+    /// [`DecryptError`].
+    Decrypt(DecryptError),
+    /// A cipher could not be configured, see [`ConfigError`].
+    Config(ConfigError),
+    /// A key could not be created, see [`KeyError`].
+    Key(KeyError),
+    /// Encoded ciphertext could not be decoded, see [`DecodeError`].
+    Decode(DecodeError),
+    /// A framed ciphertext could not be read, see [`FrameError`].
+    Frame(FrameError),
+    /// A character map could not be built, see [`CharMapError`].
+    CharMap(CharMapError),
+    /// A seed map could not be built, see [`SeedMapError`].
+    SeedMap(SeedMapError),
+    /// A seed phrase could not be decoded, see [`SeedPhraseError`].
+    SeedPhrase(SeedPhraseError),
+    /// A hexadecimal state could not be read, see [`HexStateError`].
+    HexState(HexStateError),
+    /// A binary state could not be read, see [`StateReadError`].
+    StateRead(StateReadError),
+    /// A Life pattern could not be read, see [`LifeReadError`].
+    LifeRead(LifeReadError),
+    /// An image could not be read, see [`ImageReadError`].
+    ImageRead(ImageReadError),
+    /// An automaton rule could not be parsed, see [`RuleParseError`].
+    RuleParse(RuleParseError),
+    /// A neighborhood could not be created, see [`NeighborhoodError`].
+    Neighborhood(NeighborhoodError),
+    /// An automaton did not reach a density, see [`DensityError`].
+    Density(DensityError),
+    /// The self-test found a problem, see [`SelfTestError`].
+    SelfTest(SelfTestError),
+}
+
+impl Error {
+    /// Returns the wrapped error.
+    fn inner(&self) -> &(dyn error::Error + 'static) {
+        match self {
+            Error::Io(e) => e,
+            Error::TableRead(e) => e,
+            Error::TableWrite(e) => e,
+            Error::MatrixConstruct(e) => e,
+            Error::MatrixOp(e) => e,
+            Error::Decrypt(e) => e,
+            Error::Config(e) => e,
+            Error::Key(e) => e,
+            Error::Decode(e) => e,
+            Error::Frame(e) => e,
+            Error::CharMap(e) => e,
+            Error::SeedMap(e) => e,
+            Error::SeedPhrase(e) => e,
+            Error::HexState(e) => e,
+            Error::StateRead(e) => e,
+            Error::LifeRead(e) => e,
+            Error::ImageRead(e) => e,
+            Error::RuleParse(e) => e,
+            Error::Neighborhood(e) => e,
+            Error::Density(e) => e,
+            Error::SelfTest(e) => e,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    /// Writes the wrapped error, which already names what failed.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.inner())
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        Some(self.inner())
+    }
+}
+
+impl From<FileError> for Error {
+    fn from(error: FileError) -> Self {
+        match error {
+            FileError::Io(e) => Error::Io(e),
+            FileError::Decrypt(e) => Error::Decrypt(e),
+            FileError::Config(e) => Error::Config(e),
+        }
+    }
+}
+
+/// Implements [`From`] for [`Error`] for each error type and the variant wrapping it.
+macro_rules! impl_from_error {
+    ($($source:ty => $variant:ident),* $(,)?) => {
+        $(
+            impl From<$source> for Error {
+                fn from(error: $source) -> Self {
+                    Error::$variant(error)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_error! {
+    io::Error => Io,
+    TableReadError => TableRead,
+    TableWriteError => TableWrite,
+    MatrixConstructError => MatrixConstruct,
+    MatrixOpError => MatrixOp,
+    DecryptError => Decrypt,
+    ConfigError => Config,
+    KeyError => Key,
+    DecodeError => Decode,
+    FrameError => Frame,
+    CharMapError => CharMap,
+    SeedMapError => SeedMap,
+    SeedPhraseError => SeedPhrase,
+    HexStateError => HexState,
+    StateReadError => StateRead,
+    LifeReadError => LifeRead,
+    ImageReadError => ImageRead,
+    RuleParseError => RuleParse,
+    NeighborhoodError => Neighborhood,
+    DensityError => Density,
+    SelfTestError => SelfTest,
+}
@@ -0,0 +1,240 @@
+// 2025 Steven Chiacchira
+//! A sponge-like hash built on the Talos automaton rule, with a keyed variant usable as a MAC.
+//!
+//! The sponge state is a pair of 16x16 automaton states, `previous` and `current`, stepped as a
+//! second-order automaton: each generation replaces `current` with the [`TALOS_RULE`] generation
+//! of `current` xored with `previous`, and `previous` with the old `current`. Since the old
+//! `previous` can be recovered from the new pair, each generation is a permutation of the 512 bit
+//! state whatever the rule does. Messages are absorbed 32 bytes at a time into `current`, and the
+//! digest is squeezed out of `current`, so `previous` is the capacity of the sponge. Neighboring
+//! cells of an automaton state are strongly correlated, so the splitmix64 finalizer is applied to
+//! each 8 byte word of the digest.
+use crate::automata::Automaton;
+use crate::encrypt::{
+    splitmix64, splitmix64_words, wipe, Key, GOLDEN_GAMMA, MIN_ROUNDS, TALOS_RULE,
+};
+use crate::matrix::{ToroidalBinaryMatrix, ToroidalBitMatrix};
+use std::mem;
+
+/// Number of bytes in a digest.
+pub const DIGEST_BYTES: usize = 256 / 8;
+
+/// Number of message bytes absorbed per permutation, the size of one automaton state.
+pub const RATE_BYTES: usize = 256 / 8;
+
+/// Number of generations of the second-order automaton per permutation. A change to a cell takes
+/// [`MIN_ROUNDS`] generations to reach the whole of `current`, and another [`MIN_ROUNDS`] to
+/// reach back through `previous`.
+pub const HASH_ROUNDS: u32 = 2 * MIN_ROUNDS;
+
+/// Side length of the automaton states.
+const STATE_SIDE: usize = 16;
+
+/// Domain of the unkeyed hash, mixed into the initial state.
+const HASH_DOMAIN: u64 = 0x68;
+/// Domain of the keyed hash, mixed into the initial state together with the key length.
+const KEYED_HASH_DOMAIN: u64 = 0x6b;
+
+/// Incremental hash state. Data passed to [`TalosHash::update`] is buffered into 32 byte blocks
+/// and absorbed, and [`TalosHash::finalize`] pads the remainder and returns the digest.
+#[derive(Debug, Clone)]
+pub struct TalosHash {
+    previous: ToroidalBitMatrix,
+    current: Automaton<ToroidalBitMatrix>,
+    buffer: Vec<u8>,
+}
+
+impl TalosHash {
+    /// Creates a new unkeyed hash state.
+    pub fn new() -> Self {
+        Self::with_domain(HASH_DOMAIN)
+    }
+
+    /// Creates a new hash state keyed by `key`, usable as a MAC. The key length is mixed into the
+    /// initial state and the key is absorbed as the first block, so digests under different keys
+    /// or without a key are unrelated.
+    pub fn new_keyed(key: &Key) -> Self {
+        let bytes = key.get_bytes();
+        let mut hash = Self::with_domain(KEYED_HASH_DOMAIN | (bytes.len() as u64) << u8::BITS);
+        let mut block = [0_u8; RATE_BYTES];
+        block[..bytes.len()].copy_from_slice(bytes);
+        hash.absorb_block(&block);
+        wipe(&mut block);
+
+        hash
+    }
+
+    /// Absorbs `data` into the hash.
+    pub fn update(&mut self, mut data: &[u8]) {
+        if !self.buffer.is_empty() {
+            let n_taken = data.len().min(RATE_BYTES - self.buffer.len());
+            self.buffer.extend_from_slice(&data[..n_taken]);
+            data = &data[n_taken..];
+            if self.buffer.len() < RATE_BYTES {
+                return;
+            }
+            let mut block = [0_u8; RATE_BYTES];
+            block.copy_from_slice(&self.buffer);
+            wipe(&mut self.buffer);
+            self.buffer.clear();
+            self.absorb_block(&block);
+            wipe(&mut block);
+        }
+
+        let mut blocks = data.chunks_exact(RATE_BYTES);
+        for block in &mut blocks {
+            self.absorb_block(block);
+        }
+        self.buffer.extend_from_slice(blocks.remainder());
+    }
+
+    /// Pads the absorbed data with a `0x01` byte, zero bytes and a final `0x80` bit to a whole
+    /// block, absorbs it, applies one more permutation and returns `current` with the splitmix64
+    /// finalizer applied to each 8 byte word as the digest.
+    pub fn finalize(mut self) -> [u8; DIGEST_BYTES] {
+        let mut block = [0_u8; RATE_BYTES];
+        block[..self.buffer.len()].copy_from_slice(&self.buffer);
+        block[self.buffer.len()] ^= 0x01;
+        block[RATE_BYTES - 1] ^= 0x80;
+        self.absorb_block(&block);
+        wipe(&mut block);
+        wipe(&mut self.buffer);
+        // the last block has only been through one permutation, which does not spread it fully
+        self.permute();
+
+        let mut digest = [0_u8; DIGEST_BYTES];
+        digest.copy_from_slice(&self.current.get_state().to_bytes());
+        splitmix64_words(&mut digest);
+        digest
+    }
+
+    /// Creates a new hash state with `previous` filled from `domain` and `current` empty.
+    fn with_domain(domain: u64) -> Self {
+        let seed = splitmix64(domain);
+        let capacity = (0..(STATE_SIDE * STATE_SIDE / u64::BITS as usize) as u64)
+            .flat_map(|i| {
+                splitmix64(seed.wrapping_add((i + 1).wrapping_mul(GOLDEN_GAMMA))).to_le_bytes()
+            })
+            .collect::<Vec<u8>>();
+
+        // the byte counts match the state size
+        Self {
+            previous: ToroidalBitMatrix::from_bytes(STATE_SIDE, STATE_SIDE, &capacity).unwrap(),
+            current: Automaton::new(
+                ToroidalBitMatrix::from_bytes(STATE_SIDE, STATE_SIDE, &[0; RATE_BYTES]).unwrap(),
+                &TALOS_RULE,
+            ),
+            buffer: Vec::with_capacity(RATE_BYTES),
+        }
+    }
+
+    /// Xors the [`RATE_BYTES`] byte `block` into `current` and applies the permutation.
+    fn absorb_block(&mut self, block: &[u8]) {
+        // blocks always hold RATE_BYTES bytes
+        let block = ToroidalBitMatrix::from_bytes(STATE_SIDE, STATE_SIDE, block).unwrap();
+        // both states are 16x16
+        self.current.get_state_mut().bitwise_xor(&block).unwrap();
+        self.permute();
+    }
+
+    /// Advances the second-order automaton [`HASH_ROUNDS`] generations.
+    fn permute(&mut self) {
+        for _ in 0..HASH_ROUNDS {
+            let mut next_previous = self.current.get_state().clone();
+            self.current.iter_rule(1);
+            self.current
+                .get_state_mut()
+                .bitwise_xor(&self.previous)
+                .unwrap();
+            mem::swap(&mut self.previous, &mut next_previous);
+        }
+    }
+}
+
+impl Default for TalosHash {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the [`DIGEST_BYTES`] byte digest of `message`.
+pub fn hash(message: &[u8]) -> [u8; DIGEST_BYTES] {
+    let mut hash = TalosHash::new();
+    hash.update(message);
+    hash.finalize()
+}
+
+/// Returns the [`DIGEST_BYTES`] byte digest of `message` keyed by `key`, see
+/// [`TalosHash::new_keyed`].
+pub fn keyed_hash(key: &Key, message: &[u8]) -> [u8; DIGEST_BYTES] {
+    let mut hash = TalosHash::new_keyed(key);
+    hash.update(message);
+    hash.finalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn differing_bits(a: &[u8; DIGEST_BYTES], b: &[u8; DIGEST_BYTES]) -> u32 {
+        a.iter().zip(b).map(|(a, b)| (a ^ b).count_ones()).sum()
+    }
+
+    #[test]
+    fn incremental_hash_matches_one_shot() {
+        let message = (0..100_u8).collect::<Vec<u8>>();
+        let digest = hash(&message);
+        assert_eq!(hash(&message), digest);
+        for split in [0, 1, RATE_BYTES - 1, RATE_BYTES, 70, message.len()] {
+            let mut hash = TalosHash::new();
+            hash.update(&message[..split]);
+            hash.update(&message[split..]);
+            assert_eq!(hash.finalize(), digest);
+        }
+    }
+
+    #[test]
+    fn padding_separates_messages() {
+        let digests = [
+            hash(b""),
+            hash(&[0]),
+            hash(&[0x01]),
+            hash(&[0; RATE_BYTES]),
+            hash(&[0; RATE_BYTES + 1]),
+        ];
+        for (i, a) in digests.iter().enumerate() {
+            for b in &digests[i + 1..] {
+                assert_ne!(a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn flipped_bit_changes_about_half_the_digest() {
+        let message = [0x5a_u8; 40];
+        let digest = hash(&message);
+        for bit in [0, 7, 100, 319] {
+            let mut flipped = message;
+            flipped[bit / 8] ^= 1 << (bit % 8);
+            let n_differing = differing_bits(&digest, &hash(&flipped));
+            assert!(
+                (64..=192).contains(&n_differing),
+                "{} bits differ",
+                n_differing
+            );
+        }
+    }
+
+    #[test]
+    fn keyed_hash_depends_on_the_key() {
+        let message = b"keyed hash message";
+        let key = Key::new(&[1; 16]).unwrap();
+        let other_key = Key::new(&[2; 16]).unwrap();
+        let longer_key = Key::new(&[1; 17]).unwrap();
+        let digest = keyed_hash(&key, message);
+        assert_eq!(keyed_hash(&key, message), digest);
+        assert_ne!(keyed_hash(&other_key, message), digest);
+        assert_ne!(keyed_hash(&longer_key, message), digest);
+        assert_ne!(hash(message), digest);
+    }
+}
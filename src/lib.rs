@@ -1,10 +1,16 @@
-///! # Talos
-///! An Experimental Symmetric Encryption Algorithm base on Cellular Automata
-///! 
-///! Implementation of a novel [cellular
-///! automata](https://en.wikipedia.org/wiki/Cellular_automaton) based symmetric encryption
-///! algorithm.
+//! # Talos
+//! An Experimental Symmetric Encryption Algorithm base on Cellular Automata
+//!
+//! Implementation of a novel [cellular
+//! automata](https://en.wikipedia.org/wiki/Cellular_automaton) based symmetric encryption
+//! algorithm.
 pub mod automata;
 pub mod encrypt;
+mod error;
+pub mod hash;
 pub mod matrix;
 pub mod parse;
+mod self_test;
+
+pub use error::*;
+pub use self_test::*;
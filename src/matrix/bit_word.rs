@@ -0,0 +1,83 @@
+// 2025 Steven Chiacchira
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not, Shl, Shr};
+
+/// Trait for unsigned integer words used as packed storage by a
+/// [`ToroidalBitMatrix`](crate::matrix::ToroidalBitMatrix).
+pub trait BitWord:
+    Copy
+    + Debug
+    + Eq
+    + Ord
+    + Hash
+    + BitAnd<Output = Self>
+    + BitAndAssign
+    + BitOr<Output = Self>
+    + BitOrAssign
+    + BitXor<Output = Self>
+    + BitXorAssign
+    + Not<Output = Self>
+    + Shl<usize, Output = Self>
+    + Shr<usize, Output = Self>
+{
+    /// The number of bits stored in a single word.
+    const BITS: usize;
+    /// The word with no bits set.
+    const ZERO: Self;
+    /// The word with only the least significant bit set.
+    const ONE: Self;
+    /// Returns the number of set bits in the word.
+    fn count_ones(self) -> u32;
+    /// Returns the word with the order of its bits reversed.
+    fn reverse_bits(self) -> Self;
+    /// Writes the word into the `BITS / 8` bytes of `bytes`, least significant byte first,
+    /// regardless of the byte order of the target.
+    fn write_le_bytes(self, bytes: &mut [u8]);
+    /// Reads a word written by [`BitWord::write_le_bytes`] from the `BITS / 8` bytes of `bytes`.
+    fn read_le_bytes(bytes: &[u8]) -> Self;
+    /// Sets the word to zero in a way which is not optimized away, for clearing matrices holding
+    /// secrets.
+    #[cfg(feature = "zeroize")]
+    fn wipe(&mut self);
+}
+
+/// Order in which a sequence of bits is packed into an integer word.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BitOrder {
+    /// The first bit of the sequence is stored in the least significant bit of the word.
+    LsbFirst,
+    /// The first bit of the sequence is stored in the most significant bit of the word.
+    MsbFirst,
+}
+
+macro_rules! impl_bit_word {
+    ($($t:ty),*) => {
+        $(
+            impl BitWord for $t {
+                const BITS: usize = <$t>::BITS as usize;
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+                fn count_ones(self) -> u32 {
+                    <$t>::count_ones(self)
+                }
+                fn reverse_bits(self) -> Self {
+                    <$t>::reverse_bits(self)
+                }
+                fn write_le_bytes(self, bytes: &mut [u8]) {
+                    bytes.copy_from_slice(&<$t>::to_le_bytes(self))
+                }
+                fn read_le_bytes(bytes: &[u8]) -> Self {
+                    // callers pass exactly the bytes of one word
+                    <$t>::from_le_bytes(bytes.try_into().unwrap())
+                }
+                #[cfg(feature = "zeroize")]
+                fn wipe(&mut self) {
+                    zeroize::Zeroize::zeroize(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_bit_word!(u8, u16, u32, u64, u128);
@@ -0,0 +1,254 @@
+// 2025 Steven Chiacchira
+use crate::matrix::{
+    MatrixConstructError, MatrixIndex, MatrixOpError, ToroidalBinaryMatrix, ToroidalBitMatrix,
+    ToroidalBitMatrix64, ToroidalBoolMatrix,
+};
+use std::any::Any;
+use std::fmt;
+use std::ops::Index;
+
+/// Object-safe counterpart of [`ToroidalBinaryMatrix`], allowing the storage type of a matrix to
+/// be chosen at runtime.
+///
+/// Every `ToroidalBinaryMatrix` implements this trait, and `Box<dyn DynBinaryMatrix>` in turn
+/// implements `ToroidalBinaryMatrix`, so a boxed matrix can be used anywhere a concrete one can,
+/// including as the state of an [`Automaton`](crate::automata::Automaton).
+pub trait DynBinaryMatrix: fmt::Debug {
+    /// Returns the number of rows the matrix has.
+    fn dyn_rows(&self) -> usize;
+    /// Returns the number of columns the matrix has.
+    fn dyn_cols(&self) -> usize;
+    /// Returns the value of the matrix element at `idx`, wrapping as
+    /// [`ToroidalBinaryMatrix::at`] does.
+    fn dyn_at(&self, idx: MatrixIndex) -> bool;
+    /// Sets the value of the matrix element at `idx` to `value` and returns the original value,
+    /// wrapping as [`ToroidalBinaryMatrix::set`] does.
+    fn dyn_set(&mut self, idx: MatrixIndex, value: bool) -> bool;
+    /// Performs bitwise xor of this matrix with `other`, which may use a different storage type.
+    fn dyn_bitwise_xor(&mut self, other: &dyn DynBinaryMatrix) -> Result<(), MatrixOpError>;
+    /// Performs bitwise and of this matrix with `other`, which may use a different storage type.
+    fn dyn_bitwise_and(&mut self, other: &dyn DynBinaryMatrix) -> Result<(), MatrixOpError>;
+    /// Performs bitwise or of this matrix with `other`, which may use a different storage type.
+    fn dyn_bitwise_or(&mut self, other: &dyn DynBinaryMatrix) -> Result<(), MatrixOpError>;
+    /// Inverts every element of this matrix.
+    fn dyn_bitwise_not(&mut self);
+    /// Translates every element of this matrix as [`ToroidalBinaryMatrix::shift`] does.
+    fn dyn_shift(&mut self, dx: isize, dy: isize);
+    /// Transposes this matrix, keeping its storage type.
+    fn dyn_transpose(&mut self);
+    /// Resizes this matrix as [`ToroidalBinaryMatrix::resize`] does, keeping its storage type.
+    fn dyn_resize(
+        &mut self,
+        new_rows: usize,
+        new_cols: usize,
+        fill: bool,
+    ) -> Result<(), MatrixConstructError>;
+    /// Returns the neighbor counts of every element, as [`ToroidalBinaryMatrix::neighbor_counts`]
+    /// does.
+    fn dyn_neighbor_counts(&self) -> Vec<u8>;
+    /// Returns the number of 'alive' (1) elements in the matrix.
+    fn dyn_popcount(&self) -> u32;
+    /// Copies the contents of the matrix into a table of `bool` values.
+    fn to_table(&self) -> Vec<Vec<bool>>;
+    /// Copies the elements of the matrix in row-major order, as
+    /// [`ToroidalBinaryMatrix::iter_cells`] yields them.
+    fn dyn_cells(&self) -> Vec<bool>;
+    /// Overwrites the elements of the matrix as [`ToroidalBinaryMatrix::set_cells`] does.
+    fn dyn_set_cells(&mut self, bits: &[bool]);
+    /// Returns a boxed copy of this matrix with the same storage type.
+    fn clone_box(&self) -> Box<dyn DynBinaryMatrix>;
+    /// Returns this matrix as [`Any`], so that operations between matrices of the same storage
+    /// type can use their specialized implementations.
+    fn as_any(&self) -> &dyn Any;
+}
+
+/// Converts `other` into the storage type `T` and applies `op` to `matrix` and the result. When
+/// `other` already uses `T`, no conversion is performed.
+fn apply_dyn<T, F>(matrix: &mut T, other: &dyn DynBinaryMatrix, op: F) -> Result<(), MatrixOpError>
+where
+    T: ToroidalBinaryMatrix + 'static,
+    F: FnOnce(&mut T, &T) -> Result<(), MatrixOpError>,
+{
+    match other.as_any().downcast_ref::<T>() {
+        Some(other) => op(matrix, other),
+        None => {
+            // the table of a non-empty matrix is always a valid table
+            let converted = T::new(other.to_table()).unwrap();
+            op(matrix, &converted)
+        }
+    }
+}
+
+impl<T> DynBinaryMatrix for T
+where
+    T: ToroidalBinaryMatrix + Clone + fmt::Debug + 'static,
+{
+    fn dyn_rows(&self) -> usize {
+        self.get_rows()
+    }
+    fn dyn_cols(&self) -> usize {
+        self.get_cols()
+    }
+    fn dyn_at(&self, idx: MatrixIndex) -> bool {
+        self.at(idx)
+    }
+    fn dyn_set(&mut self, idx: MatrixIndex, value: bool) -> bool {
+        self.set(idx, value)
+    }
+    fn dyn_bitwise_xor(&mut self, other: &dyn DynBinaryMatrix) -> Result<(), MatrixOpError> {
+        apply_dyn(self, other, T::bitwise_xor)
+    }
+    fn dyn_bitwise_and(&mut self, other: &dyn DynBinaryMatrix) -> Result<(), MatrixOpError> {
+        apply_dyn(self, other, T::bitwise_and)
+    }
+    fn dyn_bitwise_or(&mut self, other: &dyn DynBinaryMatrix) -> Result<(), MatrixOpError> {
+        apply_dyn(self, other, T::bitwise_or)
+    }
+    fn dyn_bitwise_not(&mut self) {
+        self.bitwise_not()
+    }
+    fn dyn_shift(&mut self, dx: isize, dy: isize) {
+        self.shift(dx, dy)
+    }
+    fn dyn_transpose(&mut self) {
+        self.transpose()
+    }
+    fn dyn_resize(
+        &mut self,
+        new_rows: usize,
+        new_cols: usize,
+        fill: bool,
+    ) -> Result<(), MatrixConstructError> {
+        self.resize(new_rows, new_cols, fill)
+    }
+    fn dyn_neighbor_counts(&self) -> Vec<u8> {
+        self.neighbor_counts()
+    }
+    fn dyn_popcount(&self) -> u32 {
+        self.popcount()
+    }
+    fn to_table(&self) -> Vec<Vec<bool>> {
+        self.iter_rows().collect()
+    }
+    fn dyn_cells(&self) -> Vec<bool> {
+        self.iter_cells().collect()
+    }
+    fn dyn_set_cells(&mut self, bits: &[bool]) {
+        self.set_cells(bits.iter().copied())
+    }
+    fn clone_box(&self) -> Box<dyn DynBinaryMatrix> {
+        Box::new(self.clone())
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+// The boxed matrix itself satisfies the blanket implementation of `DynBinaryMatrix`, so the
+// implementations below dereference explicitly to reach the boxed value rather than recursing.
+impl Clone for Box<dyn DynBinaryMatrix> {
+    fn clone(&self) -> Self {
+        (**self).clone_box()
+    }
+}
+
+/// Bits cannot be borrowed from every storage type, so indexing returns a reference to a `static`
+/// `bool` equal to the element.
+impl Index<MatrixIndex> for Box<dyn DynBinaryMatrix> {
+    type Output = bool;
+
+    fn index(&self, idx: MatrixIndex) -> &bool {
+        if (**self).dyn_at(idx) {
+            &true
+        } else {
+            &false
+        }
+    }
+}
+
+/// Operations are forwarded to the boxed matrix, so they keep its storage type. Matrices created
+/// through [`ToroidalBinaryMatrix::new`] or [`ToroidalBinaryMatrix::from_bytes`] have no storage
+/// type to inherit and use a [`ToroidalBoolMatrix`]; use [`MatrixStorage::new_matrix`] to choose
+/// one explicitly.
+impl ToroidalBinaryMatrix for Box<dyn DynBinaryMatrix> {
+    fn new(table: Vec<Vec<bool>>) -> Result<Self, MatrixConstructError> {
+        MatrixStorage::Bool.new_matrix(table)
+    }
+    fn get_rows(&self) -> usize {
+        (**self).dyn_rows()
+    }
+    fn get_cols(&self) -> usize {
+        (**self).dyn_cols()
+    }
+    fn at(&self, idx: impl Into<MatrixIndex>) -> bool {
+        (**self).dyn_at(idx.into())
+    }
+    fn set(&mut self, idx: impl Into<MatrixIndex>, value: bool) -> bool {
+        (**self).dyn_set(idx.into(), value)
+    }
+    fn iter_cells(&self) -> impl Iterator<Item = bool> + '_ {
+        (**self).dyn_cells().into_iter()
+    }
+    fn set_cells(&mut self, bits: impl IntoIterator<Item = bool>) {
+        (**self).dyn_set_cells(&bits.into_iter().collect::<Vec<bool>>())
+    }
+    fn bitwise_xor(&mut self, other: &Self) -> Result<(), MatrixOpError> {
+        (**self).dyn_bitwise_xor(&**other)
+    }
+    fn bitwise_and(&mut self, other: &Self) -> Result<(), MatrixOpError> {
+        (**self).dyn_bitwise_and(&**other)
+    }
+    fn bitwise_or(&mut self, other: &Self) -> Result<(), MatrixOpError> {
+        (**self).dyn_bitwise_or(&**other)
+    }
+    fn bitwise_not(&mut self) {
+        (**self).dyn_bitwise_not()
+    }
+    fn shift(&mut self, dx: isize, dy: isize) {
+        (**self).dyn_shift(dx, dy)
+    }
+    fn transpose(&mut self) {
+        (**self).dyn_transpose()
+    }
+    fn resize(
+        &mut self,
+        new_rows: usize,
+        new_cols: usize,
+        fill: bool,
+    ) -> Result<(), MatrixConstructError> {
+        (**self).dyn_resize(new_rows, new_cols, fill)
+    }
+    fn neighbor_counts(&self) -> Vec<u8> {
+        (**self).dyn_neighbor_counts()
+    }
+    fn popcount(&self) -> u32 {
+        (**self).dyn_popcount()
+    }
+}
+
+/// Storage types which can back a `Box<dyn DynBinaryMatrix>` chosen at runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum MatrixStorage {
+    /// One `bool` per element, see [`ToroidalBoolMatrix`].
+    #[default]
+    Bool,
+    /// Elements packed into `u32` words, see [`ToroidalBitMatrix`].
+    Bit,
+    /// Elements packed into `u64` words, see [`ToroidalBitMatrix64`].
+    Bit64,
+}
+
+impl MatrixStorage {
+    /// Creates a new boxed matrix of this storage type with entries from a table of `bool`
+    /// values.
+    pub fn new_matrix(
+        self,
+        table: Vec<Vec<bool>>,
+    ) -> Result<Box<dyn DynBinaryMatrix>, MatrixConstructError> {
+        Ok(match self {
+            MatrixStorage::Bool => Box::new(ToroidalBoolMatrix::new(table)?),
+            MatrixStorage::Bit => Box::new(ToroidalBitMatrix::<u32>::new(table)?),
+            MatrixStorage::Bit64 => Box::new(ToroidalBitMatrix64::new(table)?),
+        })
+    }
+}
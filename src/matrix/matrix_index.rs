@@ -0,0 +1,135 @@
+// 2025 Steven Chiacchira
+use std::ops::{Add, AddAssign, Neg, Sub, SubAssign};
+
+/// Type used to specify elements of a [`ToroidalBinaryMatrix`](crate::matrix::ToroidalBinaryMatrix).
+///
+/// Coordinates may be negative or exceed the matrix's shape; toroidal accessors wrap them. Any
+/// `(row, col)` tuple of `isize` converts into a [`MatrixIndex`], so methods accepting
+/// `impl Into<MatrixIndex>` can be called with either.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct MatrixIndex {
+    pub row: isize,
+    pub col: isize,
+}
+
+/// Offsets of the eight cells in the [Moore
+/// neighborhood](https://en.wikipedia.org/wiki/Moore_neighborhood) of a cell.
+pub(crate) const MOORE_OFFSETS: [MatrixIndex; 8] = [
+    MatrixIndex::new(-1, -1),
+    MatrixIndex::new(-1, 0),
+    MatrixIndex::new(-1, 1),
+    MatrixIndex::new(0, -1),
+    MatrixIndex::new(0, 1),
+    MatrixIndex::new(1, -1),
+    MatrixIndex::new(1, 0),
+    MatrixIndex::new(1, 1),
+];
+
+/// Offsets of the four cells in the [von Neumann
+/// neighborhood](https://en.wikipedia.org/wiki/Von_Neumann_neighborhood) of a cell.
+pub(crate) const VON_NEUMANN_OFFSETS: [MatrixIndex; 4] = [
+    MatrixIndex::new(-1, 0),
+    MatrixIndex::new(0, -1),
+    MatrixIndex::new(0, 1),
+    MatrixIndex::new(1, 0),
+];
+
+impl MatrixIndex {
+    /// Creates a new [`MatrixIndex`] referring to `row` and `col`.
+    pub const fn new(row: isize, col: isize) -> Self {
+        MatrixIndex { row, col }
+    }
+    /// Returns this index moved `rows` rows and `cols` columns.
+    pub const fn offset(self, rows: isize, cols: isize) -> Self {
+        MatrixIndex::new(self.row + rows, self.col + cols)
+    }
+    /// Returns this index moved `rows` rows.
+    pub const fn offset_rows(self, rows: isize) -> Self {
+        self.offset(rows, 0)
+    }
+    /// Returns this index moved `cols` columns.
+    pub const fn offset_cols(self, cols: isize) -> Self {
+        self.offset(0, cols)
+    }
+    /// Returns this index with both coordinates wrapped into a `rows` by `cols` matrix.
+    pub fn wrapped(self, rows: usize, cols: usize) -> Self {
+        MatrixIndex::new(
+            self.row.rem_euclid(rows as isize),
+            self.col.rem_euclid(cols as isize),
+        )
+    }
+    /// Returns an iterator over the eight indices in the [Moore
+    /// neighborhood](https://en.wikipedia.org/wiki/Moore_neighborhood) of this index, in
+    /// row-major order. The indices are not wrapped.
+    pub fn moore_neighbors(self) -> impl Iterator<Item = MatrixIndex> {
+        MOORE_OFFSETS.into_iter().map(move |offset| self + offset)
+    }
+    /// Returns an iterator over the four indices in the [von Neumann
+    /// neighborhood](https://en.wikipedia.org/wiki/Von_Neumann_neighborhood) of this index, in
+    /// row-major order. The indices are not wrapped.
+    pub fn von_neumann_neighbors(self) -> impl Iterator<Item = MatrixIndex> {
+        VON_NEUMANN_OFFSETS
+            .into_iter()
+            .map(move |offset| self + offset)
+    }
+}
+
+impl From<(isize, isize)> for MatrixIndex {
+    fn from(idx: (isize, isize)) -> Self {
+        MatrixIndex::new(idx.0, idx.1)
+    }
+}
+
+impl From<&(isize, isize)> for MatrixIndex {
+    fn from(idx: &(isize, isize)) -> Self {
+        MatrixIndex::new(idx.0, idx.1)
+    }
+}
+
+impl From<&MatrixIndex> for MatrixIndex {
+    fn from(idx: &MatrixIndex) -> Self {
+        *idx
+    }
+}
+
+impl From<MatrixIndex> for (isize, isize) {
+    fn from(idx: MatrixIndex) -> Self {
+        (idx.row, idx.col)
+    }
+}
+
+impl Add for MatrixIndex {
+    type Output = MatrixIndex;
+
+    fn add(self, rhs: MatrixIndex) -> MatrixIndex {
+        MatrixIndex::new(self.row + rhs.row, self.col + rhs.col)
+    }
+}
+
+impl AddAssign for MatrixIndex {
+    fn add_assign(&mut self, rhs: MatrixIndex) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for MatrixIndex {
+    type Output = MatrixIndex;
+
+    fn sub(self, rhs: MatrixIndex) -> MatrixIndex {
+        MatrixIndex::new(self.row - rhs.row, self.col - rhs.col)
+    }
+}
+
+impl SubAssign for MatrixIndex {
+    fn sub_assign(&mut self, rhs: MatrixIndex) {
+        *self = *self - rhs;
+    }
+}
+
+impl Neg for MatrixIndex {
+    type Output = MatrixIndex;
+
+    fn neg(self) -> MatrixIndex {
+        MatrixIndex::new(-self.row, -self.col)
+    }
+}
@@ -0,0 +1,173 @@
+// 2025 Steven Chiacchira
+use crate::matrix::{MatrixIndex, ToroidalBinaryMatrix};
+
+/// A read-only window onto a rectangular region of a [`ToroidalBinaryMatrix`].
+///
+/// The region starts at `origin` in the underlying matrix and may extend past its edges, in which
+/// case it wraps around the torus. Indices into the view are themselves toroidal over the view's
+/// shape, so `(0, 0)` always refers to `origin` and `(rows, 0)` wraps back to it.
+#[derive(Debug)]
+pub struct MatrixView<'a, T> {
+    matrix: &'a T,
+    origin: MatrixIndex,
+    rows: usize,
+    cols: usize,
+}
+
+/// A mutable window onto a rectangular region of a [`ToroidalBinaryMatrix`]. See
+/// [`MatrixView`] for how indices are mapped onto the underlying matrix.
+#[derive(Debug)]
+pub struct MatrixViewMut<'a, T> {
+    matrix: &'a mut T,
+    origin: MatrixIndex,
+    rows: usize,
+    cols: usize,
+}
+
+/// Maps `idx` within a view of shape `rows` by `cols` starting at `origin` onto an index in the
+/// underlying matrix.
+fn view_to_matrix_index(
+    origin: MatrixIndex,
+    rows: usize,
+    cols: usize,
+    idx: MatrixIndex,
+) -> MatrixIndex {
+    origin + idx.wrapped(rows, cols)
+}
+
+impl<'a, T> MatrixView<'a, T>
+where
+    T: ToroidalBinaryMatrix,
+{
+    /// Creates a view of the `rows` by `cols` region of `matrix` whose first element is at
+    /// `origin`.
+    pub fn new(matrix: &'a T, origin: impl Into<MatrixIndex>, rows: usize, cols: usize) -> Self {
+        debug_assert!(rows > 0 && cols > 0);
+        MatrixView {
+            matrix,
+            origin: origin.into(),
+            rows,
+            cols,
+        }
+    }
+    /// Returns the number of rows in the view.
+    pub fn get_rows(&self) -> usize {
+        self.rows
+    }
+    /// Returns the number of columns in the view.
+    pub fn get_cols(&self) -> usize {
+        self.cols
+    }
+    /// Returns the index in the underlying matrix of the view's first element.
+    pub fn get_origin(&self) -> MatrixIndex {
+        self.origin
+    }
+    /// Returns the value of the element at `idx` relative to the view's origin.
+    pub fn at(&self, idx: impl Into<MatrixIndex>) -> bool {
+        self.matrix.at(view_to_matrix_index(
+            self.origin,
+            self.rows,
+            self.cols,
+            idx.into(),
+        ))
+    }
+    /// Returns the number of 'alive' (1) elements in the view.
+    pub fn popcount(&self) -> u32 {
+        (0..self.rows as isize)
+            .flat_map(|row| (0..self.cols as isize).map(move |col| (row, col)))
+            .map(|idx| self.at(idx) as u32)
+            .sum()
+    }
+    /// Copies the contents of the view into a table of `bool` values, suitable for
+    /// [`ToroidalBinaryMatrix::new`].
+    pub fn to_table(&self) -> Vec<Vec<bool>> {
+        (0..self.rows as isize)
+            .map(|row| {
+                (0..self.cols as isize)
+                    .map(|col| self.at((row, col)))
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+impl<'a, T> MatrixViewMut<'a, T>
+where
+    T: ToroidalBinaryMatrix,
+{
+    /// Creates a mutable view of the `rows` by `cols` region of `matrix` whose first element is at
+    /// `origin`.
+    pub fn new(
+        matrix: &'a mut T,
+        origin: impl Into<MatrixIndex>,
+        rows: usize,
+        cols: usize,
+    ) -> Self {
+        debug_assert!(rows > 0 && cols > 0);
+        MatrixViewMut {
+            matrix,
+            origin: origin.into(),
+            rows,
+            cols,
+        }
+    }
+    /// Returns a read-only [`MatrixView`] of the same region.
+    pub fn as_view(&self) -> MatrixView<'_, T> {
+        MatrixView::new(self.matrix, self.origin, self.rows, self.cols)
+    }
+    /// Returns the number of rows in the view.
+    pub fn get_rows(&self) -> usize {
+        self.rows
+    }
+    /// Returns the number of columns in the view.
+    pub fn get_cols(&self) -> usize {
+        self.cols
+    }
+    /// Returns the index in the underlying matrix of the view's first element.
+    pub fn get_origin(&self) -> MatrixIndex {
+        self.origin
+    }
+    /// Returns the value of the element at `idx` relative to the view's origin.
+    pub fn at(&self, idx: impl Into<MatrixIndex>) -> bool {
+        self.matrix.at(view_to_matrix_index(
+            self.origin,
+            self.rows,
+            self.cols,
+            idx.into(),
+        ))
+    }
+    /// Sets the value of the element at `idx` relative to the view's origin to `value` and returns
+    /// the original value.
+    pub fn set(&mut self, idx: impl Into<MatrixIndex>, value: bool) -> bool {
+        self.matrix.set(
+            view_to_matrix_index(self.origin, self.rows, self.cols, idx.into()),
+            value,
+        )
+    }
+    /// Sets every element of the view to `value`.
+    pub fn fill(&mut self, value: bool) {
+        for row in 0..self.rows as isize {
+            for col in 0..self.cols as isize {
+                self.set((row, col), value);
+            }
+        }
+    }
+    /// Swaps the two rows indexed by `row1` and `row2` of the view, leaving elements of the
+    /// underlying matrix outside the view untouched.
+    pub fn swap_rows(&mut self, row1: isize, row2: isize) {
+        for col in 0..self.cols as isize {
+            let temp = self.at((row1, col));
+            self.set((row1, col), self.at((row2, col)));
+            self.set((row2, col), temp);
+        }
+    }
+    /// Swaps the two columns indexed by `col1` and `col2` of the view, leaving elements of the
+    /// underlying matrix outside the view untouched.
+    pub fn swap_cols(&mut self, col1: isize, col2: isize) {
+        for row in 0..self.rows as isize {
+            let temp = self.at((row, col1));
+            self.set((row, col1), self.at((row, col2)));
+            self.set((row, col2), temp);
+        }
+    }
+}
@@ -1,8 +1,34 @@
 // 2025 Steven Chiacchira
+mod bit_word;
+mod dyn_matrix;
+mod matrix_index;
+mod matrix_view;
+#[cfg(feature = "ndarray")]
+mod ndarray_interop;
 mod toroidal_binary_matrix;
 mod toroidal_bit_matrix;
+#[cfg(feature = "bitvec")]
+mod toroidal_bitvec_matrix;
 mod toroidal_bool_matrix;
+mod toroidal_byte_matrix;
 
+pub use bit_word::*;
+pub use dyn_matrix::*;
+pub use matrix_index::*;
+pub use matrix_view::*;
 pub use toroidal_binary_matrix::*;
 pub use toroidal_bit_matrix::*;
+#[cfg(feature = "bitvec")]
+pub use toroidal_bitvec_matrix::*;
 pub use toroidal_bool_matrix::*;
+pub use toroidal_byte_matrix::*;
+
+/// The bit-packed matrix used when a storage-agnostic packed representation is wanted. This is a
+/// [`ToroidalBitVecMatrix`] when the `bitvec` feature is enabled.
+#[cfg(feature = "bitvec")]
+pub type PackedBitMatrix = ToroidalBitVecMatrix;
+
+/// The bit-packed matrix used when a storage-agnostic packed representation is wanted. This is a
+/// [`ToroidalBitMatrix`] unless the `bitvec` feature is enabled.
+#[cfg(not(feature = "bitvec"))]
+pub type PackedBitMatrix = ToroidalBitMatrix;
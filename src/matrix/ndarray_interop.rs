@@ -0,0 +1,76 @@
+// 2025 Steven Chiacchira
+use crate::matrix::{
+    BitWord, MatrixConstructError, ToroidalBinaryMatrix, ToroidalBitMatrix, ToroidalBoolMatrix,
+};
+use ndarray::Array2;
+
+/// Builds a [`ToroidalBinaryMatrix`] from the rows of `array`.
+fn matrix_from_array<T>(array: &Array2<bool>) -> Result<T, MatrixConstructError>
+where
+    T: ToroidalBinaryMatrix,
+{
+    T::new(array.rows().into_iter().map(|row| row.to_vec()).collect())
+}
+
+/// Builds an array with the same shape as `matrix`, mapping each element through `f`.
+fn array_from_matrix<T, A>(matrix: &T, f: impl Fn(bool) -> A) -> Array2<A>
+where
+    T: ToroidalBinaryMatrix,
+{
+    Array2::from_shape_fn((matrix.get_rows(), matrix.get_cols()), |(row, col)| {
+        f(matrix.at((row as isize, col as isize)))
+    })
+}
+
+impl TryFrom<Array2<bool>> for ToroidalBoolMatrix {
+    type Error = MatrixConstructError;
+
+    /// Converts an `Array2<bool>` into a matrix, failing if the array has no elements.
+    fn try_from(array: Array2<bool>) -> Result<Self, Self::Error> {
+        matrix_from_array(&array)
+    }
+}
+
+impl<W> TryFrom<Array2<bool>> for ToroidalBitMatrix<W>
+where
+    W: BitWord,
+{
+    type Error = MatrixConstructError;
+
+    /// Converts an `Array2<bool>` into a matrix, failing if the array has no elements.
+    fn try_from(array: Array2<bool>) -> Result<Self, Self::Error> {
+        matrix_from_array(&array)
+    }
+}
+
+impl From<&ToroidalBoolMatrix> for Array2<bool> {
+    fn from(matrix: &ToroidalBoolMatrix) -> Self {
+        array_from_matrix(matrix, |value| value)
+    }
+}
+
+impl From<&ToroidalBoolMatrix> for Array2<u8> {
+    /// Converts a matrix into an array of `0` and `1` values, convenient for numeric work.
+    fn from(matrix: &ToroidalBoolMatrix) -> Self {
+        array_from_matrix(matrix, u8::from)
+    }
+}
+
+impl<W> From<&ToroidalBitMatrix<W>> for Array2<bool>
+where
+    W: BitWord,
+{
+    fn from(matrix: &ToroidalBitMatrix<W>) -> Self {
+        array_from_matrix(matrix, |value| value)
+    }
+}
+
+impl<W> From<&ToroidalBitMatrix<W>> for Array2<u8>
+where
+    W: BitWord,
+{
+    /// Converts a matrix into an array of `0` and `1` values, convenient for numeric work.
+    fn from(matrix: &ToroidalBitMatrix<W>) -> Self {
+        array_from_matrix(matrix, u8::from)
+    }
+}
@@ -1,6 +1,14 @@
 // 2025 Steven Chiacchira
-/// Type used to specify elements of a [`ToroidalBinaryMatrix`].
-pub type MatrixIndex = (isize, isize);
+use crate::matrix::{MatrixIndex, MatrixView, MatrixViewMut};
+use std::ops::Index;
+use std::{error, fmt};
+
+/// The character used to represent a `true` element of a [`ToroidalBinaryMatrix`] in files and
+/// String representations.
+pub const TRUE_CHAR: char = '#';
+/// The character used to represent a `false` element of a [`ToroidalBinaryMatrix`] in files and
+/// String representations.
+pub const FALSE_CHAR: char = '.';
 
 /// Error occurring during Matrix initialization
 #[derive(Debug)]
@@ -13,19 +21,278 @@ pub enum MatrixConstructError {
     InvalidStorage(),
 }
 
+impl fmt::Display for MatrixConstructError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MatrixConstructError::RaggedTable() => {
+                write!(f, "table rows have different numbers of columns")
+            }
+            MatrixConstructError::EmptyTable() => write!(f, "table has no cells"),
+            MatrixConstructError::InvalidStorage() => {
+                write!(f, "storage does not match the number of elements")
+            }
+        }
+    }
+}
+
+impl error::Error for MatrixConstructError {}
+
 /// Error arising from applying a matrix operation
 #[derive(Debug)]
 pub enum MatrixOpError {
-    /// Some operations require matrices to have the same shape.
-    DifferentShapes(),
+    /// Some operations require matrices to have the same shape. `lhs` and `rhs` are the
+    /// `(rows, cols)` shapes of the matrix being modified and the other operand respectively.
+    DimensionMismatch {
+        lhs: (usize, usize),
+        rhs: (usize, usize),
+    },
     /// Some operations require matrices to have compatible shapes.
     IncompatibleShapes(),
+    /// A permutation must contain every index from `0` to its length exactly once.
+    InvalidPermutation(),
+}
+
+impl fmt::Display for MatrixOpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MatrixOpError::DimensionMismatch { lhs, rhs } => write!(
+                f,
+                "matrix of shape {}x{} cannot be combined with one of shape {}x{}",
+                lhs.0, lhs.1, rhs.0, rhs.1
+            ),
+            MatrixOpError::IncompatibleShapes() => write!(f, "matrix shapes are incompatible"),
+            MatrixOpError::InvalidPermutation() => {
+                write!(f, "permutation does not hold every index exactly once")
+            }
+        }
+    }
+}
+
+impl error::Error for MatrixOpError {}
+
+/// Returns a [`MatrixOpError::DimensionMismatch`] if `lhs` and `rhs` have different shapes.
+pub fn check_same_shape<T>(lhs: &T, rhs: &T) -> Result<(), MatrixOpError>
+where
+    T: ToroidalBinaryMatrix,
+{
+    let lhs_shape = (lhs.get_rows(), lhs.get_cols());
+    let rhs_shape = (rhs.get_rows(), rhs.get_cols());
+    if lhs_shape != rhs_shape {
+        return Err(MatrixOpError::DimensionMismatch {
+            lhs: lhs_shape,
+            rhs: rhs_shape,
+        });
+    }
+    Ok(())
 }
 
+/// Returns the inverse of `permutation`, so that applying `permutation` and then its inverse with
+/// [`ToroidalBinaryMatrix::permute_rows`] or [`ToroidalBinaryMatrix::permute_cols`] restores the
+/// original matrix. `permutation` must contain every index from `0` to its length exactly once.
+pub fn invert_permutation(permutation: &[usize]) -> Vec<usize> {
+    let mut inverse = vec![0; permutation.len()];
+    for (i, p) in permutation.iter().enumerate() {
+        inverse[*p] = i;
+    }
+
+    inverse
+}
+
+/// Returns whether `permutation` contains every index from `0` to `len` exactly once.
+pub(crate) fn is_permutation(permutation: &[usize], len: usize) -> bool {
+    let mut seen = vec![false; len];
+    permutation.len() == len
+        && permutation
+            .iter()
+            .all(|p| *p < len && !std::mem::replace(&mut seen[*p], true))
+}
+
+/// Transposes a `rows` by `cols` matrix stored in row-major order in place by following the
+/// cycles of the transposition permutation. `replace` must write a value to the given storage
+/// index and return the value previously stored there. Only a one bit per element scratch buffer
+/// recording visited indices is allocated.
+pub(crate) fn transpose_cycles<F>(rows: usize, cols: usize, mut replace: F)
+where
+    F: FnMut(usize, bool) -> bool,
+{
+    let n = rows * cols;
+    if n < 3 {
+        return;
+    }
+    // the element at index i = row * cols + col moves to col * rows + row = (i * rows) mod (n - 1);
+    // the first and last elements never move
+    let destination = |i: usize| (i * rows) % (n - 1);
+    let mut visited = vec![0_u64; n.div_ceil(64)];
+    for start in 1..n - 1 {
+        if visited[start / 64] >> (start % 64) & 1 != 0 {
+            continue;
+        }
+        // the placeholder written to `start` is overwritten when the cycle closes
+        let mut value = replace(start, false);
+        let mut current = start;
+        loop {
+            current = destination(current);
+            visited[current / 64] |= 1 << (current % 64);
+            value = replace(current, value);
+            if current == start {
+                break;
+            }
+        }
+    }
+}
+
+/// Mutable handle to a single element of a [`ToroidalBinaryMatrix`], returned by
+/// [`ToroidalBinaryMatrix::at_mut`]. Bit-packed matrices cannot hand out `&mut bool`, so writes
+/// go through this proxy instead.
+#[derive(Debug)]
+pub struct CellMut<'a, T> {
+    matrix: &'a mut T,
+    idx: MatrixIndex,
+}
+
+impl<T> CellMut<'_, T>
+where
+    T: ToroidalBinaryMatrix,
+{
+    /// Returns the current value of the element.
+    pub fn get(&self) -> bool {
+        self.matrix.at(self.idx)
+    }
+    /// Sets the value of the element to `value` and returns the original value.
+    pub fn set(&mut self, value: bool) -> bool {
+        self.matrix.set(self.idx, value)
+    }
+    /// Inverts the element and returns the original value.
+    pub fn flip(&mut self) -> bool {
+        let value = self.get();
+        self.set(!value)
+    }
+}
+
+/// Extension trait for collecting an iterator of `bool` values into a matrix of a given shape.
+/// ex.
+/// ```txt
+/// let inverted: ToroidalBitMatrix = matrix.iter_cells().map(|v| !v).collect_matrix(4, 4)?;
+/// ```
+pub trait CollectMatrix: Iterator<Item = bool> + Sized {
+    /// Collects the elements of this iterator into a `rows` by `cols` matrix in row-major order,
+    /// as [`ToroidalBinaryMatrix::from_bits`] does.
+    fn collect_matrix<T>(self, rows: usize, cols: usize) -> Result<T, MatrixConstructError>
+    where
+        T: ToroidalBinaryMatrix,
+    {
+        T::from_bits(rows, cols, self)
+    }
+}
+
+impl<I> CollectMatrix for I where I: Iterator<Item = bool> {}
+
+/// Extension trait for collecting an iterator of rows, such as those produced by
+/// [`ToroidalBinaryMatrix::iter_rows`], into a matrix.
+/// ex.
+/// ```txt
+/// let mirrored: ToroidalBitMatrix = matrix
+///     .iter_rows()
+///     .map(|row| row.into_iter().rev().collect())
+///     .collect_rows()?;
+/// ```
+pub trait CollectRows: Iterator<Item = Vec<bool>> + Sized {
+    /// Collects the rows of this iterator into a matrix as [`ToroidalBinaryMatrix::new`] does,
+    /// returning an error if there are no rows or the rows have different lengths.
+    fn collect_rows<T>(self) -> Result<T, MatrixConstructError>
+    where
+        T: ToroidalBinaryMatrix,
+    {
+        T::new(self.collect())
+    }
+}
+
+impl<I> CollectRows for I where I: Iterator<Item = Vec<bool>> {}
+
 /// Trait specifying methods for matrices with binary entries on a torus.
-pub trait ToroidalBinaryMatrix: Sized {
+///
+/// Every matrix type in this module implements this trait, so code which only needs toroidal
+/// access to binary cells (such as [`Automaton`](crate::automata::Automaton) and the functions in
+/// [`encrypt`](crate::encrypt)) should be generic over it rather than a concrete storage type.
+/// Elements can also be read with index syntax, `matrix[(row, col)]`, which wraps in the same way
+/// as [`ToroidalBinaryMatrix::at`]. Methods taking an index accept either a [`MatrixIndex`] or a
+/// `(row, col)` tuple.
+pub trait ToroidalBinaryMatrix: Sized + Index<MatrixIndex, Output = bool> {
     /// Creates a new instance of a matrix with entries from a table of `bool` values.
     fn new(table: Vec<Vec<bool>>) -> Result<Self, MatrixConstructError>;
+    /// Creates a new instance of a `rows` by `cols` matrix from packed bytes.
+    ///
+    /// Elements are read in row-major order, and each byte supplies eight consecutive elements
+    /// starting from its least significant bit, matching
+    /// [`explode_u8_to_bool`](crate::parse::explode_u8_to_bool). `bytes` must contain exactly
+    /// enough bytes to hold `rows * cols` bits; unused bits of the final byte are ignored.
+    ///
+    /// The encoding only depends on the elements, never on the storage of the matrix or the byte
+    /// order of the target, so bytes written on one platform read back the same on any other.
+    fn from_bytes(rows: usize, cols: usize, bytes: &[u8]) -> Result<Self, MatrixConstructError> {
+        if rows == 0 || cols == 0 {
+            return Err(MatrixConstructError::EmptyTable());
+        }
+        if bytes.len() != (rows * cols).div_ceil(u8::BITS as usize) {
+            return Err(MatrixConstructError::InvalidStorage());
+        }
+        let bits = bytes
+            .iter()
+            .flat_map(|byte| (0..u8::BITS).map(move |i| (byte >> i) & 1 != 0))
+            .take(rows * cols);
+
+        Self::from_bits(rows, cols, bits)
+    }
+    /// Creates a new instance of a `rows` by `cols` matrix from `bits`, read in row-major order.
+    /// Returns a [`MatrixConstructError`] if the shape is empty or `bits` does not yield exactly
+    /// `rows * cols` elements.
+    fn from_bits(
+        rows: usize,
+        cols: usize,
+        bits: impl IntoIterator<Item = bool>,
+    ) -> Result<Self, MatrixConstructError> {
+        if rows == 0 || cols == 0 {
+            return Err(MatrixConstructError::EmptyTable());
+        }
+        let bits = bits.into_iter().collect::<Vec<bool>>();
+        if bits.len() != rows * cols {
+            return Err(MatrixConstructError::InvalidStorage());
+        }
+
+        Self::new(bits.chunks(cols).map(|row| row.to_vec()).collect())
+    }
+    /// Creates a new instance of a `height` by `width` matrix from a grayscale pixel buffer, such
+    /// as the luma channel of an image, stored in row-major order with one byte per pixel.
+    /// Pixels at least as bright as `threshold` become `true` elements. Returns a
+    /// [`MatrixConstructError`] if `pixels` does not hold exactly `width * height` pixels.
+    /// ex.
+    /// the 3 by 2 buffer `[0, 200, 90, 255, 10, 128]` with threshold `128` becomes
+    /// ```txt
+    /// .#.
+    /// #.#
+    /// ```
+    fn from_luma_threshold(
+        width: usize,
+        height: usize,
+        pixels: &[u8],
+        threshold: u8,
+    ) -> Result<Self, MatrixConstructError> {
+        Self::from_bits(
+            height,
+            width,
+            pixels.iter().map(|pixel| *pixel >= threshold),
+        )
+    }
+    /// Packs the elements of this matrix into bytes using the bit order described in
+    /// [`ToroidalBinaryMatrix::from_bytes`]. Unused bits of the final byte are `0`.
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut result = vec![0; (self.get_rows() * self.get_cols()).div_ceil(u8::BITS as usize)];
+        for (i, value) in self.iter_cells().enumerate() {
+            result[i / u8::BITS as usize] |= (value as u8) << (i % u8::BITS as usize);
+        }
+
+        result
+    }
     /// Returns the number of rows the matrix has.
     fn get_rows(&self) -> usize;
     /// Returns the number of columns the matrix has.
@@ -33,31 +300,372 @@ pub trait ToroidalBinaryMatrix: Sized {
     /// Returns the value of the matrix element at `idx`. If the row or column coordinate in `idx` is
     /// negative or greater than the number of rows or columns of the matrix respectively, the
     /// modulo of the coordinate will be used. This property is what makes the matrix 'toroidal'.
-    fn at(&self, idx: MatrixIndex) -> bool;
+    fn at(&self, idx: impl Into<MatrixIndex>) -> bool;
+    /// Returns the value of the matrix element at `idx`, or `None` if either coordinate of `idx`
+    /// is negative or not less than the number of rows or columns respectively. Unlike
+    /// [`ToroidalBinaryMatrix::at`], no wrapping is applied.
+    fn try_at(&self, idx: impl Into<MatrixIndex>) -> Option<bool> {
+        let idx = idx.into();
+        let in_bounds = (0..self.get_rows() as isize).contains(&idx.row)
+            && (0..self.get_cols() as isize).contains(&idx.col);
+        in_bounds.then(|| self.at(idx))
+    }
+    /// Returns the value of the matrix element at `idx` without wrapping, panicking if `idx` is
+    /// outside the matrix. Intended for callers whose indices should always be in bounds, so that
+    /// an out of bounds index surfaces as a bug rather than silently wrapping.
+    fn get_unwrapped(&self, idx: impl Into<MatrixIndex>) -> bool {
+        let idx = idx.into();
+        match self.try_at(idx) {
+            Some(value) => value,
+            None => panic!(
+                "index {:?} out of bounds for {}x{} matrix",
+                idx,
+                self.get_rows(),
+                self.get_cols()
+            ),
+        }
+    }
     /// Sets the value of the matrix element at `idx` to `value` and returns the original value.
     /// If the row or column coordinate in `idx` is negative or greater than the number of rows
     /// or columns of the matrix respectively, the modulo of the coordinate will be used. This
     /// property is what makes the
     /// matrix 'toroidal'.
-    fn set(&mut self, idx: &MatrixIndex, value: bool) -> bool;
+    fn set(&mut self, idx: impl Into<MatrixIndex>, value: bool) -> bool;
+    /// Returns a [`CellMut`] handle for reading and writing the element at `idx`, wrapping
+    /// `idx` in the same way as [`ToroidalBinaryMatrix::at`].
+    fn at_mut(&mut self, idx: impl Into<MatrixIndex>) -> CellMut<'_, Self> {
+        CellMut {
+            matrix: self,
+            idx: idx.into(),
+        }
+    }
     /// Performs bitwise xor of this matrix with `other`, returning a [`MatrixOpError`] if the two
     /// matrices have different shapes.
     fn bitwise_xor(&mut self, other: &Self) -> Result<(), MatrixOpError>;
+    /// Performs bitwise and of this matrix with `other`, returning a [`MatrixOpError`] if the two
+    /// matrices have different shapes.
+    fn bitwise_and(&mut self, other: &Self) -> Result<(), MatrixOpError>;
+    /// Performs bitwise or of this matrix with `other`, returning a [`MatrixOpError`] if the two
+    /// matrices have different shapes.
+    fn bitwise_or(&mut self, other: &Self) -> Result<(), MatrixOpError>;
+    /// Inverts every element of this matrix.
+    fn bitwise_not(&mut self);
     /// Swaps the two rows indexed by `row1` and `row2` of this Matrix.
     fn swap_rows(&mut self, row1: isize, row2: isize) {
         for col in 0..self.get_cols() {
             let temp = self.at((row1, col as isize));
-            self.set(&(row2, col as isize), self.at((row1, col as isize)));
-            self.set(&(row1, col as isize), temp);
+            self.set((row1, col as isize), self.at((row2, col as isize)));
+            self.set((row2, col as isize), temp);
         }
     }
     /// Swaps the two columns indexed by `col1` and `col2` of this Matrix.
     fn swap_cols(&mut self, col1: isize, col2: isize) {
         for row in 0..self.get_rows() {
             let temp = self.at((row as isize, col1));
-            self.set(&(row as isize, col1), self.at((row as isize, col2)));
-            self.set(&(row as isize, col2), temp);
+            self.set((row as isize, col1), self.at((row as isize, col2)));
+            self.set((row as isize, col2), temp);
+        }
+    }
+    /// Returns a copy of the column indexed by `col` of this Matrix, ordered from the first row to
+    /// the last.
+    fn get_col(&self, col: isize) -> Vec<bool> {
+        (0..self.get_rows())
+            .map(|row| self.at((row as isize, col)))
+            .collect()
+    }
+    /// Overwrites the column indexed by `col` of this Matrix with `values`, returning a
+    /// [`MatrixOpError`] if `values` does not have exactly one entry per row.
+    fn set_col(&mut self, col: isize, values: &[bool]) -> Result<(), MatrixOpError> {
+        if values.len() != self.get_rows() {
+            return Err(MatrixOpError::DimensionMismatch {
+                lhs: (self.get_rows(), 1),
+                rhs: (values.len(), 1),
+            });
+        }
+        for (row, value) in values.iter().enumerate() {
+            self.set((row as isize, col), *value);
+        }
+        Ok(())
+    }
+    /// Rearranges the rows of this Matrix in one pass so that row `i` afterwards holds what was
+    /// previously row `permutation[i]`. Returns a [`MatrixOpError`] if `permutation` is not a
+    /// permutation of the row indices.
+    fn permute_rows(&mut self, permutation: &[usize]) -> Result<(), MatrixOpError> {
+        if !is_permutation(permutation, self.get_rows()) {
+            return Err(MatrixOpError::InvalidPermutation());
+        }
+        let original = self.iter_rows().collect::<Vec<Vec<bool>>>();
+        for (row, source) in permutation.iter().enumerate() {
+            for (col, value) in original[*source].iter().enumerate() {
+                self.set((row as isize, col as isize), *value);
+            }
+        }
+        Ok(())
+    }
+    /// Rearranges the columns of this Matrix in one pass so that column `i` afterwards holds what
+    /// was previously column `permutation[i]`. Returns a [`MatrixOpError`] if `permutation` is not
+    /// a permutation of the column indices.
+    fn permute_cols(&mut self, permutation: &[usize]) -> Result<(), MatrixOpError> {
+        if !is_permutation(permutation, self.get_cols()) {
+            return Err(MatrixOpError::InvalidPermutation());
+        }
+        let original = self.iter_rows().collect::<Vec<Vec<bool>>>();
+        for (row, values) in original.iter().enumerate() {
+            for (col, source) in permutation.iter().enumerate() {
+                self.set((row as isize, col as isize), values[*source]);
+            }
+        }
+        Ok(())
+    }
+    /// Circularly shifts the row indexed by `row` of this Matrix by `k` places, so the element at
+    /// `(row, col)` moves to `(row, col + k)`. Elements shifted past the last column wrap around
+    /// to the first, and negative `k` shifts towards the first column.
+    fn rotate_row(&mut self, row: isize, k: isize) {
+        let cols = self.get_cols() as isize;
+        let values = (0..cols)
+            .map(|col| self.at((row, col)))
+            .collect::<Vec<bool>>();
+        for (col, value) in values.into_iter().enumerate() {
+            self.set((row, col as isize + k), value);
+        }
+    }
+    /// Circularly shifts the column indexed by `col` of this Matrix by `k` places, so the element
+    /// at `(row, col)` moves to `(row + k, col)`. Elements shifted past the last row wrap around
+    /// to the first, and negative `k` shifts towards the first row.
+    fn rotate_col(&mut self, col: isize, k: isize) {
+        for (row, value) in self.get_col(col).into_iter().enumerate() {
+            self.set((row as isize + k, col), value);
+        }
+    }
+    /// Translates every element of this Matrix `dx` columns and `dy` rows across the torus, so the
+    /// element at `(row, col)` moves to `(row + dy, col + dx)`. Negative offsets translate towards
+    /// the first row or column.
+    fn shift(&mut self, dx: isize, dy: isize) {
+        let original = self.enumerate_cells().collect::<Vec<(MatrixIndex, bool)>>();
+        for (idx, value) in original {
+            self.set(idx.offset(dy, dx), value);
+        }
+    }
+    /// Transposes this Matrix, so the element at `(row, col)` moves to `(col, row)`. Square
+    /// matrices are transposed in place, while rectangular matrices are rebuilt with their row and
+    /// column counts exchanged. The matrix types in this module override this to transpose
+    /// rectangular matrices in place as well.
+    fn transpose(&mut self) {
+        let (rows, cols) = (self.get_rows(), self.get_cols());
+        if rows == cols {
+            for row in 0..rows as isize {
+                for col in (row + 1)..cols as isize {
+                    let temp = self.at((row, col));
+                    self.set((row, col), self.at((col, row)));
+                    self.set((col, row), temp);
+                }
+            }
+            return;
         }
+
+        let table = (0..cols as isize)
+            .map(|col| self.get_col(col))
+            .collect::<Vec<Vec<bool>>>();
+        // a non-empty matrix always produces a non-empty, non-ragged table
+        *self = Self::new(table).unwrap();
+    }
+    /// Mirrors this Matrix left to right, so the element at `(row, col)` moves to
+    /// `(row, cols - 1 - col)`.
+    fn flip_horizontal(&mut self) {
+        let cols = self.get_cols() as isize;
+        for col in 0..cols / 2 {
+            self.swap_cols(col, cols - 1 - col);
+        }
+    }
+    /// Mirrors this Matrix top to bottom, so the element at `(row, col)` moves to
+    /// `(rows - 1 - row, col)`.
+    fn flip_vertical(&mut self) {
+        let rows = self.get_rows() as isize;
+        for row in 0..rows / 2 {
+            self.swap_rows(row, rows - 1 - row);
+        }
+    }
+    /// Rotates this Matrix clockwise by `n` quarter turns; negative `n` rotates counterclockwise.
+    /// A single clockwise turn moves the element at `(row, col)` to `(col, rows - 1 - row)`, so
+    /// odd turns exchange the row and column counts. Two quarter turns are an involution.
+    /// ex.
+    /// the matrix
+    /// ```txt
+    /// #..
+    /// ##.
+    /// ```
+    /// rotated by one quarter turn becomes
+    /// ```txt
+    /// ##
+    /// #.
+    /// ..
+    /// ```
+    fn rotate_quarter_turns(&mut self, n: isize) {
+        match n.rem_euclid(4) {
+            1 => {
+                self.transpose();
+                self.flip_horizontal();
+            }
+            2 => {
+                self.flip_horizontal();
+                self.flip_vertical();
+            }
+            3 => {
+                self.transpose();
+                self.flip_vertical();
+            }
+            _ => {}
+        }
+    }
+    /// Writes the transpose of this Matrix into `dest`, so the element at `(row, col)` of this
+    /// Matrix is written to `(col, row)` of `dest`. Returns a [`MatrixOpError`] unless `dest` has
+    /// as many rows as this Matrix has columns and vice versa.
+    fn transpose_into(&self, dest: &mut Self) -> Result<(), MatrixOpError> {
+        let transposed_shape = (self.get_cols(), self.get_rows());
+        let dest_shape = (dest.get_rows(), dest.get_cols());
+        if dest_shape != transposed_shape {
+            return Err(MatrixOpError::DimensionMismatch {
+                lhs: dest_shape,
+                rhs: transposed_shape,
+            });
+        }
+        for (idx, value) in self.enumerate_cells() {
+            dest.set((idx.col, idx.row), value);
+        }
+        Ok(())
+    }
+    /// Returns an iterator over the values of every element of this Matrix in row-major order.
+    fn iter_cells(&self) -> impl Iterator<Item = bool> + '_ {
+        self.enumerate_cells().map(|(_, value)| value)
+    }
+    /// Overwrites the elements of this Matrix with `bits`, read in row-major order. Bits beyond the
+    /// last element are ignored, and elements past the last bit keep their values.
+    fn set_cells(&mut self, bits: impl IntoIterator<Item = bool>) {
+        let cols = self.get_cols() as isize;
+        let indices = (0..self.get_rows() as isize)
+            .flat_map(move |row| (0..cols).map(move |col| MatrixIndex::new(row, col)));
+        for (idx, bit) in indices.zip(bits) {
+            self.set(idx, bit);
+        }
+    }
+    /// Returns an iterator over the indices and values of every element of this Matrix in
+    /// row-major order.
+    fn enumerate_cells(&self) -> impl Iterator<Item = (MatrixIndex, bool)> + '_ {
+        let cols = self.get_cols() as isize;
+        (0..self.get_rows() as isize)
+            .flat_map(move |row| (0..cols).map(move |col| MatrixIndex::new(row, col)))
+            .map(|idx| (idx, self.at(idx)))
+    }
+    /// Returns an iterator over copies of the rows of this Matrix, from the first row to the last.
+    fn iter_rows(&self) -> impl Iterator<Item = Vec<bool>> + '_ {
+        (0..self.get_rows() as isize).map(|row| {
+            (0..self.get_cols() as isize)
+                .map(|col| self.at((row, col)))
+                .collect()
+        })
+    }
+    /// Resizes this Matrix to `new_rows` by `new_cols`. Elements within both the old and new
+    /// shapes keep their positions, elements outside the new shape are discarded, and newly added
+    /// elements are set to `fill`. Returns a [`MatrixConstructError`] if the new shape is empty.
+    fn resize(
+        &mut self,
+        new_rows: usize,
+        new_cols: usize,
+        fill: bool,
+    ) -> Result<(), MatrixConstructError> {
+        let (rows, cols) = (self.get_rows(), self.get_cols());
+        let table = (0..new_rows)
+            .map(|row| {
+                (0..new_cols)
+                    .map(|col| {
+                        if row < rows && col < cols {
+                            self.at((row as isize, col as isize))
+                        } else {
+                            fill
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        *self = Self::new(table)?;
+        Ok(())
+    }
+    /// Grows this Matrix with dead (`false`) elements along its last rows and columns until both
+    /// dimensions are a multiple of `block`, so it can be split evenly into `block` by `block`
+    /// tiles. `block` must be non-zero.
+    fn pad_to_multiple(&mut self, block: usize) {
+        debug_assert!(block > 0);
+        let new_rows = self.get_rows().next_multiple_of(block);
+        let new_cols = self.get_cols().next_multiple_of(block);
+        if (new_rows, new_cols) != (self.get_rows(), self.get_cols()) {
+            // growing a non-empty matrix cannot produce an empty one
+            self.resize(new_rows, new_cols, false).unwrap();
+        }
+    }
+    /// Represents this Matrix as a rectangular array of characters, writing `alive_char` for
+    /// `true` elements and `dead_char` for `false` elements. `separator` is placed between
+    /// adjacent elements of a row, and every row ends with a newline.
+    /// ex.
+    /// the matrix
+    /// ```txt
+    /// TFFT
+    /// TFTT
+    /// ```
+    /// formatted with `('#', '.', " ")` is represented as
+    /// ```txt
+    /// # . . #
+    /// # . # #
+    /// ```
+    fn format_with(&self, alive_char: char, dead_char: char, separator: &str) -> String {
+        let mut result = String::new();
+        for row in self.iter_rows() {
+            let cells = row
+                .into_iter()
+                .map(|value| if value { alive_char } else { dead_char }.to_string())
+                .collect::<Vec<String>>();
+            result.push_str(&cells.join(separator));
+            result.push('\n');
+        }
+
+        result
+    }
+    /// Returns a read-only [`MatrixView`] of the `rows` by `cols` region of this Matrix starting
+    /// at `origin`, wrapping around the torus where the region extends past an edge.
+    fn view(
+        &self,
+        origin: impl Into<MatrixIndex>,
+        rows: usize,
+        cols: usize,
+    ) -> MatrixView<'_, Self> {
+        MatrixView::new(self, origin, rows, cols)
+    }
+    /// Returns a [`MatrixViewMut`] of the `rows` by `cols` region of this Matrix starting at
+    /// `origin`, wrapping around the torus where the region extends past an edge.
+    fn view_mut(
+        &mut self,
+        origin: impl Into<MatrixIndex>,
+        rows: usize,
+        cols: usize,
+    ) -> MatrixViewMut<'_, Self> {
+        MatrixViewMut::new(self, origin, rows, cols)
+    }
+    /// Returns the number of alive elements in the [Moore
+    /// neighborhood](https://en.wikipedia.org/wiki/Moore_neighborhood) of every element of this
+    /// Matrix, in row-major order. The neighborhood wraps around the torus.
+    fn neighbor_counts(&self) -> Vec<u8> {
+        let (rows, cols) = (self.get_rows() as isize, self.get_cols() as isize);
+        let mut counts = Vec::with_capacity((rows * cols) as usize);
+        for row in 0..rows {
+            for col in 0..cols {
+                let count = MatrixIndex::new(row, col)
+                    .moore_neighbors()
+                    .map(|idx| self.at(idx) as u8)
+                    .sum();
+                counts.push(count);
+            }
+        }
+
+        counts
     }
     /// Returns the number of 'alive' (1) elements in the Matrix.
     fn popcount(&self) -> u32;
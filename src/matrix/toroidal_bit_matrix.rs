@@ -1,14 +1,56 @@
 // 2025 Steven Chiacchira
-use crate::matrix::{MatrixConstructError, MatrixIndex, MatrixOpError, ToroidalBinaryMatrix};
+use crate::matrix::{
+    check_same_shape, is_permutation, transpose_cycles, BitOrder, BitWord, MatrixConstructError,
+    MatrixIndex, MatrixOpError, ToroidalBinaryMatrix, FALSE_CHAR, TRUE_CHAR,
+};
+use std::borrow::Cow;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::Index;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Number of storage words handled by each task of the parallel operations.
+#[cfg(feature = "parallel")]
+const PARALLEL_CHUNK_WORDS: usize = 1024;
+
+/// Order in which the elements of a [`ToroidalBitMatrix`] are packed into its storage.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Layout {
+    /// Each row is stored contiguously, so operations along rows read consecutive bits.
+    #[default]
+    RowMajor,
+    /// Each column is stored contiguously, so operations along columns read consecutive bits.
+    ColMajor,
+}
 
 #[derive(Debug, Clone)]
-pub struct ToroidalBitMatrix {
+/// A [`ToroidalBinaryMatrix`] storing its entries as packed bits, in row-major order unless
+/// another [`Layout`] is chosen with [`ToroidalBitMatrix::set_layout`].
+/// The word type `W` used for storage defaults to `u32`; see also [`ToroidalBitMatrix64`].
+///
+/// Internally the storage is a sequence of 'lines': rows for [`Layout::RowMajor`] and columns
+/// for [`Layout::ColMajor`]. Whole lines can be moved a word at a time, while moving elements
+/// across lines touches one bit at a time.
+pub struct ToroidalBitMatrix<W = u32>
+where
+    W: BitWord,
+{
     pub rows: usize,
     pub cols: usize,
-    storage: Vec<u32>,
+    layout: Layout,
+    storage: Vec<W>,
 }
 
-impl ToroidalBinaryMatrix for ToroidalBitMatrix {
+/// A [`ToroidalBitMatrix`] backed by `u64` words, halving the number of words touched by bulk
+/// operations compared to the default `u32` storage.
+pub type ToroidalBitMatrix64 = ToroidalBitMatrix<u64>;
+
+impl<W> ToroidalBinaryMatrix for ToroidalBitMatrix<W>
+where
+    W: BitWord,
+{
     fn get_rows(&self) -> usize {
         self.rows
     }
@@ -31,16 +73,18 @@ impl ToroidalBinaryMatrix for ToroidalBitMatrix {
             return Err(MatrixConstructError::RaggedTable());
         }
 
-        let mut storage: Vec<u32> = Vec::with_capacity(rows * cols * u32::BITS as usize / 8);
+        let mut storage: Vec<W> = Vec::with_capacity((rows * cols).div_ceil(W::BITS));
         for chunk in table
             .into_iter()
             .flat_map(|r| r.into_iter())
             .collect::<Vec<bool>>()
-            .chunks(u32::BITS as usize)
+            .chunks(W::BITS)
         {
-            let mut next_element: u32 = 0;
-            for (i, b) in chunk.to_vec().into_iter().enumerate() {
-                next_element += if b { 2_u32.pow(i as u32) } else { 0 };
+            let mut next_element: W = W::ZERO;
+            for (i, b) in chunk.iter().enumerate() {
+                if *b {
+                    next_element |= W::ONE << i;
+                }
             }
             storage.push(next_element);
         }
@@ -48,75 +92,802 @@ impl ToroidalBinaryMatrix for ToroidalBitMatrix {
         Ok(Self {
             rows,
             cols,
+            layout: Layout::RowMajor,
             storage,
         })
     }
-    fn at(&self, idx: MatrixIndex) -> bool {
-        let row = idx.0.rem_euclid(self.rows as isize);
-        let col = idx.1.rem_euclid(self.cols as isize);
-        let bit_index = row as usize * self.cols + col as usize;
+    /// Reads whole little-endian words rather than single bits, with the same result as the
+    /// default implementation.
+    fn from_bytes(rows: usize, cols: usize, bytes: &[u8]) -> Result<Self, MatrixConstructError> {
+        if rows == 0 || cols == 0 {
+            return Err(MatrixConstructError::EmptyTable());
+        }
+        if bytes.len() != (rows * cols).div_ceil(u8::BITS as usize) {
+            return Err(MatrixConstructError::InvalidStorage());
+        }
+        let word_bytes = W::BITS / u8::BITS as usize;
+        let mut padded = bytes.to_vec();
+        padded.resize((rows * cols).div_ceil(W::BITS) * word_bytes, 0);
+        let words = padded
+            .chunks_exact(word_bytes)
+            .map(W::read_le_bytes)
+            .collect::<Vec<W>>();
 
-        let vec_idx: usize = bit_index / u32::BITS as usize;
-        let element_offset: usize = bit_index % u32::BITS as usize;
+        Self::from_words(rows, cols, &words, BitOrder::LsbFirst)
+    }
+    /// Writes whole little-endian words rather than single bits, with the same result as the
+    /// default implementation.
+    fn to_bytes(&self) -> Vec<u8> {
+        let word_bytes = W::BITS / u8::BITS as usize;
+        let words = self.to_words(BitOrder::LsbFirst);
+        let mut result = vec![0; words.len() * word_bytes];
+        for (word, bytes) in words.into_iter().zip(result.chunks_exact_mut(word_bytes)) {
+            word.write_le_bytes(bytes);
+        }
+        result.truncate((self.rows * self.cols).div_ceil(u8::BITS as usize));
 
-        (self.storage[vec_idx] >> element_offset) & 1 != 0
+        result
     }
-    fn set(&mut self, idx: &MatrixIndex, value: bool) -> bool {
-        let row = idx.0.rem_euclid(self.rows as isize);
-        let col = idx.1.rem_euclid(self.cols as isize);
-        let bit_index = row as usize * self.cols + col as usize;
+    fn at(&self, idx: impl Into<MatrixIndex>) -> bool {
+        let bit_index = self.bit_index(idx.into());
 
-        let vec_idx: usize = bit_index / u32::BITS as usize;
-        let element_offset: usize = bit_index % u32::BITS as usize;
-        
-        let original_value = self.storage[vec_idx] << (element_offset & 1 ) > 0;
-        if value {
-            self.storage[vec_idx] |= 1 << element_offset;
-        } else {
-            self.storage[vec_idx] &= !(1 << element_offset);
-        }
+        let vec_idx: usize = bit_index / W::BITS;
+        let element_offset: usize = bit_index % W::BITS;
 
-        original_value
+        (self.storage[vec_idx] >> element_offset) & W::ONE != W::ZERO
     }
-    fn bitwise_xor(&mut self, other: &ToroidalBitMatrix) -> Result<(), MatrixOpError> {
-        if self.rows != other.rows || self.cols != other.cols {
-            return Err(MatrixOpError::DifferentShapes());
+    fn set(&mut self, idx: impl Into<MatrixIndex>, value: bool) -> bool {
+        let bit_index = self.bit_index(idx.into());
+        self.replace_bit(bit_index, value)
+    }
+    fn bitwise_xor(&mut self, other: &ToroidalBitMatrix<W>) -> Result<(), MatrixOpError> {
+        check_same_shape(self, other)?;
+        let other_storage = other.storage_in_layout(self.layout);
+        for (i, element) in self.storage.iter_mut().enumerate() {
+            *element ^= other_storage[i];
+        }
+        Ok(())
+    }
+    fn bitwise_and(&mut self, other: &ToroidalBitMatrix<W>) -> Result<(), MatrixOpError> {
+        check_same_shape(self, other)?;
+        let other_storage = other.storage_in_layout(self.layout);
+        for (i, element) in self.storage.iter_mut().enumerate() {
+            *element &= other_storage[i];
+        }
+        Ok(())
+    }
+    fn bitwise_or(&mut self, other: &ToroidalBitMatrix<W>) -> Result<(), MatrixOpError> {
+        check_same_shape(self, other)?;
+        let other_storage = other.storage_in_layout(self.layout);
+        for (i, element) in self.storage.iter_mut().enumerate() {
+            *element |= other_storage[i];
+        }
+        Ok(())
+    }
+    fn bitwise_not(&mut self) {
+        for element in self.storage.iter_mut() {
+            *element = !*element;
+        }
+        self.clear_padding();
+    }
+    /// Follows the cycles of the transposition in place, so rectangular matrices are not copied.
+    fn transpose(&mut self) {
+        // transposing the lines x line_len grid of storage transposes the matrix in either layout
+        let (lines, line_len) = (self.lines(), self.line_len());
+        transpose_cycles(lines, line_len, |i, value| self.replace_bit(i, value));
+        std::mem::swap(&mut self.rows, &mut self.cols);
+    }
+    /// Moves whole lines a word at a time when permuting along the storage's lines, and otherwise
+    /// rearranges the bits within each line.
+    fn permute_rows(&mut self, permutation: &[usize]) -> Result<(), MatrixOpError> {
+        if !is_permutation(permutation, self.rows) {
+            return Err(MatrixOpError::InvalidPermutation());
+        }
+        match self.layout {
+            Layout::RowMajor => self.permute_lines(permutation),
+            Layout::ColMajor => self.permute_within_lines(permutation),
+        }
+        Ok(())
+    }
+    /// Moves whole lines a word at a time when permuting along the storage's lines, and otherwise
+    /// rearranges the bits within each line.
+    fn permute_cols(&mut self, permutation: &[usize]) -> Result<(), MatrixOpError> {
+        if !is_permutation(permutation, self.cols) {
+            return Err(MatrixOpError::InvalidPermutation());
         }
-        for (i, element) in (&mut self.storage).into_iter().enumerate() {
-            *element ^= other.storage[i as usize];
+        match self.layout {
+            Layout::RowMajor => self.permute_within_lines(permutation),
+            Layout::ColMajor => self.permute_lines(permutation),
         }
         Ok(())
     }
     fn popcount(&self) -> u32 {
         self.storage.iter().map(|e| e.count_ones()).sum()
     }
+    /// Moves bits a word at a time: each destination row is assembled from `W`-sized runs of its
+    /// source row, read with wrapping inside that row, so the cost scales with the number of
+    /// words rather than the number of elements.
+    fn shift(&mut self, dx: isize, dy: isize) {
+        let (lines, line_len) = (self.lines(), self.line_len());
+        let (line_shift, pos_shift) = match self.layout {
+            Layout::RowMajor => (dy, dx),
+            Layout::ColMajor => (dx, dy),
+        };
+        let mut storage = vec![W::ZERO; self.storage.len()];
+        let pos_shift = pos_shift.rem_euclid(line_len as isize) as usize;
+        for line in 0..lines {
+            let source_line = (line as isize - line_shift).rem_euclid(lines as isize) as usize;
+            let mut pos = 0;
+            while pos < line_len {
+                let len = (line_len - pos).min(W::BITS);
+                let source_pos = (pos + line_len - pos_shift) % line_len;
+                let bits = self.read_line_bits(source_line, source_pos, len);
+                write_bits(&mut storage, line * line_len + pos, len, bits);
+                pos += len;
+            }
+        }
+        self.storage = storage;
+    }
+    /// Computes every neighbor count at once with a bit-sliced adder: each line is split into
+    /// 64-bit lanes and the eight shifted neighbor lines are summed into four bit planes, so each
+    /// word operation updates 64 counts.
+    fn neighbor_counts(&self) -> Vec<u8> {
+        let lines: Vec<Vec<u64>> = (0..self.lines())
+            .map(|line| self.line_lanes(line))
+            .collect();
+        let counts = (0..self.lines())
+            .flat_map(|line| self.line_neighbor_counts(&lines, line))
+            .collect();
+        self.counts_to_row_major(counts)
+    }
+}
+
+/// Writes the low `len` bits of `value` into `storage` starting at bit index `start`. The bits
+/// being written must be clear.
+fn write_bits<W>(storage: &mut [W], start: usize, len: usize, value: W)
+where
+    W: BitWord,
+{
+    let (word, offset) = (start / W::BITS, start % W::BITS);
+    storage[word] |= value << offset;
+    if offset + len > W::BITS {
+        storage[word + 1] |= value >> (W::BITS - offset);
+    }
+}
+
+/// Adds the bits of `lanes` into the 4-bit counters stored across `planes`, where bit `i` of
+/// `planes[lane][j]` holds bit `j` of the counter for column `64 * lane + i`.
+fn add_lanes(planes: &mut [[u64; 4]], lanes: &[u64]) {
+    for (plane, lane) in planes.iter_mut().zip(lanes) {
+        // ripple-carry add of a single bit into the 4 bit planes
+        let mut carry = *lane;
+        for bit in plane.iter_mut() {
+            let sum = *bit ^ carry;
+            carry &= *bit;
+            *bit = sum;
+        }
+    }
+}
+
+/// Clears every element, leaving a matrix of the same shape with no elements set.
+#[cfg(feature = "zeroize")]
+impl<W> zeroize::Zeroize for ToroidalBitMatrix<W>
+where
+    W: BitWord,
+{
+    fn zeroize(&mut self) {
+        self.storage.iter_mut().for_each(BitWord::wipe);
+    }
+}
+
+/// Clears the storage, so automaton states do not linger in memory.
+#[cfg(feature = "zeroize")]
+impl<W> Drop for ToroidalBitMatrix<W>
+where
+    W: BitWord,
+{
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self);
+    }
+}
+
+/// Two matrices are equal when they have the same shape and elements, regardless of their
+/// layouts; unused bits in the final storage word are ignored.
+impl<W> PartialEq for ToroidalBitMatrix<W>
+where
+    W: BitWord,
+{
+    fn eq(&self, other: &Self) -> bool {
+        if self.rows != other.rows || self.cols != other.cols {
+            return false;
+        }
+        if self.layout == other.layout {
+            return self.normalized_words().eq(other.normalized_words());
+        }
+        self.to_words(BitOrder::LsbFirst) == other.to_words(BitOrder::LsbFirst)
+    }
+}
+
+impl<W> Eq for ToroidalBitMatrix<W> where W: BitWord {}
+
+impl<W> Hash for ToroidalBitMatrix<W>
+where
+    W: BitWord,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.rows.hash(state);
+        self.cols.hash(state);
+        // hash the row-major words so that equal matrices with different layouts hash equally
+        for word in self.to_words(BitOrder::LsbFirst) {
+            word.hash(state);
+        }
+    }
+}
+
+/// Represents the matrix as a rectangular array of characters; see
+/// [`ToroidalBinaryMatrix::format_with`].
+impl<W> fmt::Display for ToroidalBitMatrix<W>
+where
+    W: BitWord,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.format_with(TRUE_CHAR, FALSE_CHAR, ""))
+    }
+}
+
+/// Bits cannot be borrowed individually, so indexing returns a reference to a `static` `bool`
+/// equal to the element. Use [`ToroidalBinaryMatrix::set`] or [`ToroidalBinaryMatrix::at_mut`]
+/// to write elements.
+impl<W, I> Index<I> for ToroidalBitMatrix<W>
+where
+    W: BitWord,
+    I: Into<MatrixIndex>,
+{
+    type Output = bool;
+
+    fn index(&self, idx: I) -> &bool {
+        if self.at(idx) {
+            &true
+        } else {
+            &false
+        }
+    }
 }
 
-impl ToroidalBitMatrix {
-    /// Returns the storage backing the matrix.
-    pub fn get_storage(&self) -> &Vec<u32> {
+impl<W> ToroidalBitMatrix<W>
+where
+    W: BitWord,
+{
+    /// Clears the unused bits of the final storage word, which operations such as
+    /// [`ToroidalBinaryMatrix::bitwise_not`] would otherwise set, keeping
+    /// [`ToroidalBinaryMatrix::popcount`] accurate.
+    fn clear_padding(&mut self) {
+        let used_bits = (self.rows * self.cols) % W::BITS;
+        if used_bits != 0 {
+            if let Some(last) = self.storage.last_mut() {
+                *last &= !(!W::ZERO << used_bits);
+            }
+        }
+    }
+    /// Returns the number of lines in the storage: rows for [`Layout::RowMajor`] and columns for
+    /// [`Layout::ColMajor`].
+    fn lines(&self) -> usize {
+        match self.layout {
+            Layout::RowMajor => self.rows,
+            Layout::ColMajor => self.cols,
+        }
+    }
+    /// Returns the number of elements in each line of the storage.
+    fn line_len(&self) -> usize {
+        match self.layout {
+            Layout::RowMajor => self.cols,
+            Layout::ColMajor => self.rows,
+        }
+    }
+    /// Returns the position in the storage of the element at `idx`, wrapping `idx` onto the
+    /// matrix.
+    fn bit_index(&self, idx: MatrixIndex) -> usize {
+        let idx = idx.wrapped(self.rows, self.cols);
+        let (row, col) = (idx.row as usize, idx.col as usize);
+        match self.layout {
+            Layout::RowMajor => row * self.cols + col,
+            Layout::ColMajor => col * self.rows + row,
+        }
+    }
+    /// Returns the storage of this matrix as it would be packed in `layout`, converting only if
+    /// this matrix uses a different layout.
+    fn storage_in_layout(&self, layout: Layout) -> Cow<'_, [W]> {
+        if self.layout == layout {
+            Cow::Borrowed(&self.storage)
+        } else {
+            let mut converted = self.to_layout(layout);
+            Cow::Owned(std::mem::take(&mut converted.storage))
+        }
+    }
+    /// Reorders counts computed for each element in storage order into row-major order.
+    fn counts_to_row_major(&self, counts: Vec<u8>) -> Vec<u8> {
+        match self.layout {
+            Layout::RowMajor => counts,
+            Layout::ColMajor => (0..self.rows * self.cols)
+                .map(|i| counts[(i % self.cols) * self.rows + i / self.cols])
+                .collect(),
+        }
+    }
+    /// Rearranges the lines of the storage so that line `i` afterwards holds what was previously
+    /// line `permutation[i]`, copying a word at a time.
+    fn permute_lines(&mut self, permutation: &[usize]) {
+        let line_len = self.line_len();
+        let mut storage = vec![W::ZERO; self.storage.len()];
+        for (line, source) in permutation.iter().enumerate() {
+            let mut pos = 0;
+            while pos < line_len {
+                let len = (line_len - pos).min(W::BITS);
+                let bits = self.read_line_bits(*source, pos, len);
+                write_bits(&mut storage, line * line_len + pos, len, bits);
+                pos += len;
+            }
+        }
+        self.storage = storage;
+    }
+    /// Rearranges the elements within every line of the storage so that position `i` afterwards
+    /// holds what was previously at position `permutation[i]` of the same line.
+    fn permute_within_lines(&mut self, permutation: &[usize]) {
+        let line_len = self.line_len();
+        let original = self.storage.clone();
+        for line in 0..self.lines() {
+            let start = line * line_len;
+            for (pos, source) in permutation.iter().enumerate() {
+                let source = start + source;
+                let bit = (original[source / W::BITS] >> (source % W::BITS)) & W::ONE != W::ZERO;
+                self.replace_bit(start + pos, bit);
+            }
+        }
+    }
+    /// Sets the element at storage position `bit_index` to `value` and returns the original
+    /// value.
+    fn replace_bit(&mut self, bit_index: usize, value: bool) -> bool {
+        let vec_idx: usize = bit_index / W::BITS;
+        let element_offset: usize = bit_index % W::BITS;
+
+        let original_value = (self.storage[vec_idx] >> element_offset) & W::ONE != W::ZERO;
+        if value {
+            self.storage[vec_idx] |= W::ONE << element_offset;
+        } else {
+            self.storage[vec_idx] &= !(W::ONE << element_offset);
+        }
+
+        original_value
+    }
+    /// Returns `word`, the storage word at position `i`, with its unused bits cleared if it is
+    /// the final word.
+    fn mask_padding(&self, i: usize, word: W) -> W {
+        let used_bits = (self.rows * self.cols) % W::BITS;
+        if i == self.storage.len() - 1 && used_bits != 0 {
+            word & !(!W::ZERO << used_bits)
+        } else {
+            word
+        }
+    }
+    /// Returns the storage words with the unused bits of the final word cleared.
+    fn normalized_words(&self) -> impl Iterator<Item = W> + '_ {
+        self.storage
+            .iter()
+            .enumerate()
+            .map(|(i, word)| self.mask_padding(i, *word))
+    }
+    /// Reads `len` (at most `W::BITS`) consecutive elements of storage line `line` starting at
+    /// position `pos`, wrapping from the end of the line back to its start. The elements are
+    /// returned in the low bits of a word, first element least significant.
+    fn read_line_bits(&self, line: usize, pos: usize, len: usize) -> W {
+        let line_len = self.line_len();
+        let line_start = line * line_len;
+        let (mut pos, mut filled, mut result) = (pos, 0, W::ZERO);
+        while filled < len {
+            let bit_index = line_start + pos;
+            let offset = bit_index % W::BITS;
+            // read up to the end of the current word, the end of the line, or the requested length
+            let piece = (W::BITS - offset).min(line_len - pos).min(len - filled);
+            let mut bits = self.storage[bit_index / W::BITS] >> offset;
+            if piece < W::BITS {
+                bits &= !(!W::ZERO << piece);
+            }
+            result |= bits << filled;
+            filled += piece;
+            pos = (pos + piece) % line_len;
+        }
+
+        result
+    }
+    /// Returns the elements of storage line `line` packed into 64-bit lanes, least significant
+    /// bit first.
+    fn line_lanes(&self, line: usize) -> Vec<u64> {
+        let line_len = self.line_len();
+        let mut lanes = vec![0_u64; line_len.div_ceil(64)];
+        let line_start = line * line_len;
+        for pos in 0..line_len {
+            let bit_index = line_start + pos;
+            let bit = self.storage[bit_index / W::BITS] >> (bit_index % W::BITS) & W::ONE;
+            if bit != W::ZERO {
+                lanes[pos / 64] |= 1 << (pos % 64);
+            }
+        }
+
+        lanes
+    }
+    /// Rotates a line packed by [`ToroidalBitMatrix::line_lanes`] one position to the right, so
+    /// the bit at position `pos` moves to `pos + 1` and the last position wraps to the first.
+    fn shift_lanes_right(&self, lanes: &[u64]) -> Vec<u64> {
+        let line_len = self.line_len();
+        let last = line_len - 1;
+        let wrapped = (lanes[last / 64] >> (last % 64)) & 1;
+        let mut result: Vec<u64> = (0..lanes.len())
+            .map(|i| (lanes[i] << 1) | if i > 0 { lanes[i - 1] >> 63 } else { wrapped })
+            .collect();
+        if !line_len.is_multiple_of(64) {
+            result[last / 64] &= (1 << (line_len % 64)) - 1;
+        }
+
+        result
+    }
+    /// Rotates a line packed by [`ToroidalBitMatrix::line_lanes`] one position to the left, so
+    /// the bit at position `pos` moves to `pos - 1` and the first position wraps to the last.
+    fn shift_lanes_left(&self, lanes: &[u64]) -> Vec<u64> {
+        let last = self.line_len() - 1;
+        let wrapped = lanes[0] & 1;
+        let mut result: Vec<u64> = (0..lanes.len())
+            .map(|i| (lanes[i] >> 1) | lanes.get(i + 1).map_or(0, |next| next << 63))
+            .collect();
+        result[last / 64] |= wrapped << (last % 64);
+
+        result
+    }
+    /// Returns the neighbor counts of every element of storage line `line`, given every line of
+    /// the storage packed by [`ToroidalBitMatrix::line_lanes`]. The Moore neighborhood is
+    /// symmetric, so the same computation serves both layouts.
+    fn line_neighbor_counts(&self, lines: &[Vec<u64>], line: usize) -> Vec<u8> {
+        let n_lines = lines.len();
+        let above = &lines[(line + n_lines - 1) % n_lines];
+        let center = &lines[line];
+        let below = &lines[(line + 1) % n_lines];
+
+        let mut planes = vec![[0_u64; 4]; center.len()];
+        for lanes in [above, below] {
+            add_lanes(&mut planes, lanes);
+        }
+        for lanes in [above, center, below] {
+            add_lanes(&mut planes, &self.shift_lanes_right(lanes));
+            add_lanes(&mut planes, &self.shift_lanes_left(lanes));
+        }
+
+        (0..self.line_len())
+            .map(|pos| {
+                let plane = &planes[pos / 64];
+                let offset = pos % 64;
+                (0..4)
+                    .map(|i| (((plane[i] >> offset) & 1) as u8) << i)
+                    .sum()
+            })
+            .collect()
+    }
+    /// Returns the elements of the matrix packed into words in row-major order, with `order`
+    /// determining whether the first element of each word is its least or most significant bit.
+    /// Unused bits of the final word are `0`. Unlike [`ToroidalBitMatrix::get_storage`], the
+    /// result does not depend on the matrix's internal layout.
+    pub fn to_words(&self, order: BitOrder) -> Vec<W> {
+        self.storage_in_layout(Layout::RowMajor)
+            .iter()
+            .enumerate()
+            .map(|(i, word)| self.mask_padding(i, *word))
+            .map(|word| match order {
+                BitOrder::LsbFirst => word,
+                BitOrder::MsbFirst => word.reverse_bits(),
+            })
+            .collect()
+    }
+    /// Constructs a new `rows` by `cols` [`ToroidalBitMatrix`] from words packed as described in
+    /// [`ToroidalBitMatrix::to_words`]. Unused bits of the final word are ignored. Returns an
+    /// error if `words` is the wrong length for the specified matrix shape.
+    pub fn from_words(
+        rows: usize,
+        cols: usize,
+        words: &[W],
+        order: BitOrder,
+    ) -> Result<Self, MatrixConstructError> {
+        let storage = words
+            .iter()
+            .map(|word| match order {
+                BitOrder::LsbFirst => *word,
+                BitOrder::MsbFirst => word.reverse_bits(),
+            })
+            .collect();
+        let mut result = Self::from_storage(rows, cols, storage)?;
+        result.clear_padding();
+        Ok(result)
+    }
+    /// Returns the [`Layout`] of the matrix's storage.
+    pub fn get_layout(&self) -> Layout {
+        self.layout
+    }
+    /// Repacks the matrix's storage in `layout`. Element values and indices are unchanged.
+    pub fn set_layout(&mut self, layout: Layout) {
+        if self.layout != layout {
+            // the storage of one layout is the storage of the transpose in the other
+            let (lines, line_len) = (self.lines(), self.line_len());
+            transpose_cycles(lines, line_len, |i, value| self.replace_bit(i, value));
+            self.layout = layout;
+        }
+    }
+    /// Returns a copy of the matrix with its storage packed in `layout`.
+    pub fn to_layout(&self, layout: Layout) -> Self {
+        let mut result = self.clone();
+        result.set_layout(layout);
+        result
+    }
+    /// Returns the lexicographically smallest matrix among every translation of this matrix
+    /// across the torus combined with every rotation and reflection which keeps its shape: the
+    /// half turn and both flips, and for a square matrix the quarter turns and both diagonal
+    /// reflections as well. Matrices are compared by their elements in row-major order, with
+    /// `false` before `true`.
+    ///
+    /// Rules which treat every neighbor alike evolve equivalent states into equivalent states,
+    /// so comparing canonical forms counts each class of equivalent states once.
+    /// Ex:
+    /// ```txt
+    /// .#.     ...
+    /// ...  -> ...
+    /// ...     ..#
+    /// ```
+    pub fn canonical_form(&self) -> Self {
+        let mut canonical = self.clone();
+        let mut canonical_words = self.to_words(BitOrder::MsbFirst);
+        self.for_each_symmetric_image(|image| {
+            // with the first element as the most significant bit, word order is element order
+            let words = image.to_words(BitOrder::MsbFirst);
+            if words < canonical_words {
+                canonical.clone_from(image);
+                canonical_words = words;
+            }
+        });
+
+        canonical
+    }
+    /// Returns the number of distinct matrices which [`ToroidalBitMatrix::canonical_form`]
+    /// considers equivalent to this one, itself included. A matrix with no symmetry has an orbit
+    /// of `8 * rows * cols` matrices when square and `4 * rows * cols` otherwise; each symmetry
+    /// it has divides the orbit further.
+    pub fn orbit_size(&self) -> usize {
+        let words = self.to_words(BitOrder::MsbFirst);
+        let (mut n_images, mut n_fixed) = (0, 0);
+        self.for_each_symmetric_image(|image| {
+            n_images += 1;
+            if image.to_words(BitOrder::MsbFirst) == words {
+                n_fixed += 1;
+            }
+        });
+
+        // every image is reached by as many transformations as fix this matrix
+        n_images / n_fixed
+    }
+    /// Calls `f` with the image of this matrix under every combination of a translation and a
+    /// shape preserving rotation or reflection, see [`ToroidalBitMatrix::canonical_form`].
+    fn for_each_symmetric_image<F>(&self, mut f: F)
+    where
+        F: FnMut(&Self),
+    {
+        let transposes: &[bool] = if self.rows == self.cols {
+            &[false, true]
+        } else {
+            &[false]
+        };
+        for &transposed in transposes {
+            for (flip_rows, flip_cols) in
+                [(false, false), (false, true), (true, false), (true, true)]
+            {
+                let mut oriented = self.clone();
+                if transposed {
+                    oriented.transpose();
+                }
+                if flip_rows {
+                    oriented.flip_vertical();
+                }
+                if flip_cols {
+                    oriented.flip_horizontal();
+                }
+                for dy in 0..self.rows as isize {
+                    for dx in 0..self.cols as isize {
+                        let mut image = oriented.clone();
+                        image.shift(dx, dy);
+                        f(&image);
+                    }
+                }
+            }
+        }
+    }
+    /// Returns the storage backing the matrix, packed according to its [`Layout`]. The words hold
+    /// their elements as integer values, so their bytes in memory depend on the byte order of the
+    /// target; use [`ToroidalBinaryMatrix::to_bytes`] for an encoding shared by every platform.
+    pub fn get_storage(&self) -> &Vec<W> {
         &self.storage
     }
-    /// Constructs a new [`ToroidalBitMatrix`] from storage, as well as the count of rows and
-    /// columns. Returns an error if the storage is the wrong size for the specified matrix shape.
+    /// Constructs a new row-major [`ToroidalBitMatrix`] from storage, as well as the count of rows
+    /// and columns. Returns an error if the storage is the wrong size for the specified matrix
+    /// shape.
     pub fn from_storage(
         rows: usize,
         cols: usize,
-        storage: Vec<u32>,
+        storage: Vec<W>,
     ) -> Result<Self, MatrixConstructError> {
         if rows == 0 || cols == 0 {
             return Err(MatrixConstructError::EmptyTable());
         }
         let n_elements = rows * cols;
-        if storage.len()
-            != ((n_elements / u32::BITS as usize) + (n_elements % u32::BITS as usize > 0) as usize)
-        {
+        if storage.len() != n_elements.div_ceil(W::BITS) {
             return Err(MatrixConstructError::InvalidStorage());
         }
         Ok(Self {
             rows,
             cols,
+            layout: Layout::RowMajor,
             storage,
         })
     }
 }
+
+/// Parallel variants of the bulk operations, which split the storage into chunks of words and
+/// process them across the rayon thread pool. These only pay off for very large matrices, such as
+/// those used in analysis runs; the 16x16 blocks used for encryption should use the serial
+/// [`ToroidalBinaryMatrix`] methods.
+#[cfg(feature = "parallel")]
+impl<W> ToroidalBitMatrix<W>
+where
+    W: BitWord + Send + Sync,
+{
+    /// Performs bitwise xor of this matrix with `other` in parallel, returning a
+    /// [`MatrixOpError`] if the two matrices have different shapes.
+    pub fn par_bitwise_xor(&mut self, other: &ToroidalBitMatrix<W>) -> Result<(), MatrixOpError> {
+        self.par_zip_words(other, |lhs, rhs| *lhs ^= rhs)
+    }
+    /// Performs bitwise and of this matrix with `other` in parallel, returning a
+    /// [`MatrixOpError`] if the two matrices have different shapes.
+    pub fn par_bitwise_and(&mut self, other: &ToroidalBitMatrix<W>) -> Result<(), MatrixOpError> {
+        self.par_zip_words(other, |lhs, rhs| *lhs &= rhs)
+    }
+    /// Performs bitwise or of this matrix with `other` in parallel, returning a
+    /// [`MatrixOpError`] if the two matrices have different shapes.
+    pub fn par_bitwise_or(&mut self, other: &ToroidalBitMatrix<W>) -> Result<(), MatrixOpError> {
+        self.par_zip_words(other, |lhs, rhs| *lhs |= rhs)
+    }
+    /// Returns the number of 'alive' (1) elements in the matrix, counting in parallel.
+    pub fn par_popcount(&self) -> u32 {
+        self.storage
+            .par_chunks(PARALLEL_CHUNK_WORDS)
+            .map(|chunk| chunk.iter().map(|word| word.count_ones()).sum::<u32>())
+            .sum()
+    }
+    /// Computes the same counts as [`ToroidalBinaryMatrix::neighbor_counts`], handling rows in
+    /// parallel.
+    pub fn par_neighbor_counts(&self) -> Vec<u8> {
+        let lines: Vec<Vec<u64>> = (0..self.lines())
+            .into_par_iter()
+            .map(|line| self.line_lanes(line))
+            .collect();
+        let counts = (0..self.lines())
+            .into_par_iter()
+            .flat_map_iter(|line| self.line_neighbor_counts(&lines, line))
+            .collect();
+        self.counts_to_row_major(counts)
+    }
+    /// Applies `op` to each storage word of this matrix and the corresponding word of `other`,
+    /// one chunk of words per task.
+    fn par_zip_words<F>(&mut self, other: &ToroidalBitMatrix<W>, op: F) -> Result<(), MatrixOpError>
+    where
+        F: Fn(&mut W, W) + Send + Sync,
+    {
+        check_same_shape(self, other)?;
+        let other_storage = other.storage_in_layout(self.layout);
+        self.storage
+            .par_chunks_mut(PARALLEL_CHUNK_WORDS)
+            .zip(other_storage.par_chunks(PARALLEL_CHUNK_WORDS))
+            .for_each(|(lhs, rhs)| {
+                for (l, r) in lhs.iter_mut().zip(rhs) {
+                    op(l, *r);
+                }
+            });
+        Ok(())
+    }
+}
+
+// the byte encodings must not depend on the byte order of the target; run these on a big-endian
+// target with `cargo +nightly miri test --target s390x-unknown-linux-gnu --lib`
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matrix::ToroidalBoolMatrix;
+
+    /// Shapes which leave the final storage word and the final byte partly unused.
+    const SHAPES: [(usize, usize); 7] =
+        [(1, 1), (3, 5), (8, 8), (7, 13), (16, 16), (9, 64), (17, 33)];
+
+    /// Returns a `rows` by `cols` table whose pattern lines up with no word size or layout.
+    fn table(rows: usize, cols: usize) -> Vec<Vec<bool>> {
+        (0..rows)
+            .map(|row| {
+                (0..cols)
+                    .map(|col| (row * 7 + col * 3 + row * col) % 5 < 2)
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Checks the word-wise byte encoding of `ToroidalBitMatrix<W>` in both layouts against the
+    /// bitwise default implementation, which [`ToroidalBoolMatrix`] uses.
+    fn check_byte_round_trip<W>()
+    where
+        W: BitWord,
+    {
+        for (rows, cols) in SHAPES {
+            let expected = ToroidalBoolMatrix::new(table(rows, cols)).unwrap();
+            let bytes = expected.to_bytes();
+            for layout in [Layout::RowMajor, Layout::ColMajor] {
+                let matrix = ToroidalBitMatrix::<W>::new(table(rows, cols))
+                    .unwrap()
+                    .to_layout(layout);
+                assert_eq!(matrix.to_bytes(), bytes, "{rows}x{cols} {layout:?}");
+
+                let read = ToroidalBitMatrix::<W>::from_bytes(rows, cols, &bytes).unwrap();
+                assert!(read.iter_cells().eq(expected.iter_cells()), "{rows}x{cols}");
+                assert_eq!(
+                    read.to_layout(layout).to_bytes(),
+                    bytes,
+                    "{rows}x{cols} {layout:?}"
+                );
+            }
+
+            // unused bits of the final byte are ignored
+            let mut padded = bytes.clone();
+            if (rows * cols) % u8::BITS as usize != 0 {
+                *padded.last_mut().unwrap() |= 0xff << ((rows * cols) % u8::BITS as usize);
+            }
+            let read = ToroidalBitMatrix::<W>::from_bytes(rows, cols, &padded).unwrap();
+            assert_eq!(read.to_bytes(), bytes, "{rows}x{cols} padded");
+        }
+    }
+
+    #[test]
+    fn byte_round_trip_u8() {
+        check_byte_round_trip::<u8>();
+    }
+
+    #[test]
+    fn byte_round_trip_u16() {
+        check_byte_round_trip::<u16>();
+    }
+
+    #[test]
+    fn byte_round_trip_u32() {
+        check_byte_round_trip::<u32>();
+    }
+
+    #[test]
+    fn byte_round_trip_u64() {
+        check_byte_round_trip::<u64>();
+    }
+
+    #[test]
+    fn words_are_written_little_endian() {
+        let mut bytes = [0; 8];
+        0x0807_0605_0403_0201_u64.write_le_bytes(&mut bytes);
+        assert_eq!(bytes, [1, 2, 3, 4, 5, 6, 7, 8]);
+        assert_eq!(u64::read_le_bytes(&bytes), 0x0807_0605_0403_0201);
+
+        let mut bytes = [0; 4];
+        0x0403_0201_u32.write_le_bytes(&mut bytes);
+        assert_eq!(bytes, [1, 2, 3, 4]);
+        assert_eq!(u32::read_le_bytes(&bytes), 0x0403_0201);
+
+        let mut bytes = [0; 2];
+        0x0201_u16.write_le_bytes(&mut bytes);
+        assert_eq!(bytes, [1, 2]);
+        assert_eq!(u16::read_le_bytes(&bytes), 0x0201);
+    }
+}
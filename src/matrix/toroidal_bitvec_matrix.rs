@@ -0,0 +1,178 @@
+// 2025 Steven Chiacchira
+use crate::matrix::{
+    check_same_shape, transpose_cycles, BitOrder, MatrixConstructError, MatrixIndex, MatrixOpError,
+    ToroidalBinaryMatrix, ToroidalBitMatrix, FALSE_CHAR, TRUE_CHAR,
+};
+use bitvec::prelude::{BitVec, Lsb0};
+use std::fmt;
+use std::ops::Index;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// A [`ToroidalBinaryMatrix`] storing its entries in a [`BitVec`] in row-major order. The bit
+/// layout matches [`ToroidalBitMatrix`] with `u32` words, but bit addressing and bulk operations
+/// are delegated to `bitvec`.
+pub struct ToroidalBitVecMatrix {
+    pub rows: usize,
+    pub cols: usize,
+    storage: BitVec<u32, Lsb0>,
+}
+
+impl ToroidalBinaryMatrix for ToroidalBitVecMatrix {
+    fn get_rows(&self) -> usize {
+        self.rows
+    }
+    fn get_cols(&self) -> usize {
+        self.cols
+    }
+    fn new(table: Vec<Vec<bool>>) -> Result<Self, MatrixConstructError> {
+        let rows = table.len();
+        let cols = if rows == 0 { 0 } else { table[0].len() };
+        if cols == 0 {
+            return Err(MatrixConstructError::EmptyTable());
+        }
+
+        // if the table is ragged (every column is not the same size) then we reject the input and return an Err result
+        if table.iter().any(|row| row.len() != cols) {
+            return Err(MatrixConstructError::RaggedTable());
+        }
+
+        let storage = table.into_iter().flatten().collect();
+
+        Ok(Self {
+            rows,
+            cols,
+            storage,
+        })
+    }
+    fn at(&self, idx: impl Into<MatrixIndex>) -> bool {
+        self.storage[self.storage_index(idx.into())]
+    }
+    fn set(&mut self, idx: impl Into<MatrixIndex>, value: bool) -> bool {
+        let bit_index = self.storage_index(idx.into());
+        self.storage.replace(bit_index, value)
+    }
+    fn bitwise_xor(&mut self, other: &ToroidalBitVecMatrix) -> Result<(), MatrixOpError> {
+        check_same_shape(self, other)?;
+        self.storage ^= &other.storage;
+        Ok(())
+    }
+    fn bitwise_and(&mut self, other: &ToroidalBitVecMatrix) -> Result<(), MatrixOpError> {
+        check_same_shape(self, other)?;
+        self.storage &= &other.storage;
+        Ok(())
+    }
+    fn bitwise_or(&mut self, other: &ToroidalBitVecMatrix) -> Result<(), MatrixOpError> {
+        check_same_shape(self, other)?;
+        self.storage |= &other.storage;
+        Ok(())
+    }
+    fn bitwise_not(&mut self) {
+        let storage = std::mem::take(&mut self.storage);
+        self.storage = !storage;
+    }
+    fn swap_rows(&mut self, row1: isize, row2: isize) {
+        let start_1 = row1.rem_euclid(self.rows as isize) as usize * self.cols;
+        let start_2 = row2.rem_euclid(self.rows as isize) as usize * self.cols;
+        if start_1 == start_2 {
+            return;
+        }
+        let (low, high) = (start_1.min(start_2), start_1.max(start_2));
+        let (head, tail) = self.storage.split_at_mut(high);
+        head[low..low + self.cols].swap_with_bitslice(&mut tail[..self.cols]);
+    }
+    /// Follows the cycles of the transposition in place, so rectangular matrices are not copied.
+    fn transpose(&mut self) {
+        transpose_cycles(self.rows, self.cols, |i, value| {
+            self.storage.replace(i, value)
+        });
+        std::mem::swap(&mut self.rows, &mut self.cols);
+    }
+    fn popcount(&self) -> u32 {
+        self.storage.count_ones() as u32
+    }
+}
+
+/// Represents the matrix as a rectangular array of characters; see
+/// [`ToroidalBinaryMatrix::format_with`].
+/// Clears every element, leaving a matrix of the same shape with no elements set.
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for ToroidalBitVecMatrix {
+    fn zeroize(&mut self) {
+        zeroize::Zeroize::zeroize(self.storage.as_raw_mut_slice());
+    }
+}
+
+/// Clears the storage, so automaton states do not linger in memory.
+#[cfg(feature = "zeroize")]
+impl Drop for ToroidalBitVecMatrix {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self);
+    }
+}
+
+impl fmt::Display for ToroidalBitVecMatrix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.format_with(TRUE_CHAR, FALSE_CHAR, ""))
+    }
+}
+
+impl<I> Index<I> for ToroidalBitVecMatrix
+where
+    I: Into<MatrixIndex>,
+{
+    type Output = bool;
+
+    fn index(&self, idx: I) -> &bool {
+        if self.at(idx) {
+            &true
+        } else {
+            &false
+        }
+    }
+}
+
+impl ToroidalBitVecMatrix {
+    /// Returns the position in storage of the element at `idx`, applying toroidal wrapping.
+    fn storage_index(&self, idx: MatrixIndex) -> usize {
+        let idx = idx.wrapped(self.rows, self.cols);
+
+        idx.row as usize * self.cols + idx.col as usize
+    }
+    /// Returns the storage backing the matrix. Its `u32` words hold element `i` in bit `i % 32`,
+    /// so their bytes in memory depend on the byte order of the target; use
+    /// [`ToroidalBinaryMatrix::to_bytes`] for an encoding shared by every platform.
+    pub fn get_storage(&self) -> &BitVec<u32, Lsb0> {
+        &self.storage
+    }
+    /// Constructs a new [`ToroidalBitVecMatrix`] from storage, as well as the count of rows and
+    /// columns. Returns an error if the storage is the wrong size for the specified matrix shape.
+    pub fn from_storage(
+        rows: usize,
+        cols: usize,
+        storage: BitVec<u32, Lsb0>,
+    ) -> Result<Self, MatrixConstructError> {
+        if rows == 0 || cols == 0 {
+            return Err(MatrixConstructError::EmptyTable());
+        }
+        if storage.len() != rows * cols {
+            return Err(MatrixConstructError::InvalidStorage());
+        }
+        Ok(Self {
+            rows,
+            cols,
+            storage,
+        })
+    }
+}
+
+impl From<&ToroidalBitMatrix> for ToroidalBitVecMatrix {
+    fn from(matrix: &ToroidalBitMatrix) -> Self {
+        let mut storage = BitVec::from_vec(matrix.to_words(BitOrder::LsbFirst));
+        storage.truncate(matrix.rows * matrix.cols);
+        Self {
+            rows: matrix.rows,
+            cols: matrix.cols,
+            storage,
+        }
+    }
+}
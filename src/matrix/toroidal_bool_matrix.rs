@@ -1,7 +1,13 @@
 // 2025 Steven Chiacchira
-use crate::matrix::{MatrixConstructError, MatrixIndex, MatrixOpError, ToroidalBinaryMatrix};
+use crate::matrix::{
+    check_same_shape, transpose_cycles, MatrixConstructError, MatrixIndex, MatrixOpError,
+    ToroidalBinaryMatrix, FALSE_CHAR, TRUE_CHAR,
+};
+use std::fmt;
+use std::ops::{Index, IndexMut};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// A [`ToroidalBinaryMatrix`] storing each entry as a `bool` in row-major order.
 pub struct ToroidalBoolMatrix {
     pub rows: usize,
     pub cols: usize,
@@ -39,19 +45,18 @@ impl ToroidalBinaryMatrix for ToroidalBoolMatrix {
             storage,
         })
     }
-    fn at(&self, idx: MatrixIndex) -> bool {
-        let row = idx.0.rem_euclid(self.rows as isize);
-        let col = idx.1.rem_euclid(self.cols as isize);
-
-        let vec_idx: usize = row as usize * self.cols + col as usize;
-
-        self.storage[vec_idx]
+    fn from_bits(
+        rows: usize,
+        cols: usize,
+        bits: impl IntoIterator<Item = bool>,
+    ) -> Result<Self, MatrixConstructError> {
+        Self::from_storage(rows, cols, bits.into_iter().collect())
     }
-    fn set(&mut self, idx: &MatrixIndex, value: bool) -> bool {
-        let row = idx.0.rem_euclid(self.rows as isize);
-        let col = idx.1.rem_euclid(self.cols as isize);
-
-        let vec_idx: usize = row as usize * self.get_cols() as usize + col as usize;
+    fn at(&self, idx: impl Into<MatrixIndex>) -> bool {
+        self.storage[self.storage_index(idx.into())]
+    }
+    fn set(&mut self, idx: impl Into<MatrixIndex>, value: bool) -> bool {
+        let vec_idx: usize = self.storage_index(idx.into());
         let result = self.storage[vec_idx];
         self.storage[vec_idx] = value;
 
@@ -59,14 +64,25 @@ impl ToroidalBinaryMatrix for ToroidalBoolMatrix {
     }
 
     fn bitwise_xor(&mut self, other: &ToroidalBoolMatrix) -> Result<(), MatrixOpError> {
-        if self.rows != other.rows || self.cols != other.cols {
-            return Err(MatrixOpError::DifferentShapes());
-        }
-        for i in 0..(self.rows * self.cols) {
-            self.storage[i] = self.storage[i] != other.storage[i];
-        }
+        check_same_shape(self, other)?;
+        self.apply_packed(other, |lhs, rhs| lhs ^ rhs);
+        Ok(())
+    }
+    fn bitwise_and(&mut self, other: &ToroidalBoolMatrix) -> Result<(), MatrixOpError> {
+        check_same_shape(self, other)?;
+        self.apply_packed(other, |lhs, rhs| lhs & rhs);
+        Ok(())
+    }
+    fn bitwise_or(&mut self, other: &ToroidalBoolMatrix) -> Result<(), MatrixOpError> {
+        check_same_shape(self, other)?;
+        self.apply_packed(other, |lhs, rhs| lhs | rhs);
         Ok(())
     }
+    fn bitwise_not(&mut self) {
+        for element in self.storage.iter_mut() {
+            *element = !*element;
+        }
+    }
     fn swap_rows(&mut self, row1: isize, row2: isize) {
         let row_1_idx: usize = row1.rem_euclid(self.rows as isize) as usize;
         let row_2_idx: usize = row2.rem_euclid(self.rows as isize) as usize;
@@ -77,12 +93,124 @@ impl ToroidalBinaryMatrix for ToroidalBoolMatrix {
             self.storage.swap(offset_1 + i, offset_2 + i);
         }
     }
+    /// Follows the cycles of the transposition in place, so rectangular matrices are not copied.
+    fn transpose(&mut self) {
+        transpose_cycles(self.rows, self.cols, |i, value| {
+            std::mem::replace(&mut self.storage[i], value)
+        });
+        std::mem::swap(&mut self.rows, &mut self.cols);
+    }
+    fn iter_cells(&self) -> impl Iterator<Item = bool> + '_ {
+        self.storage.iter().copied()
+    }
+    fn set_cells(&mut self, bits: impl IntoIterator<Item = bool>) {
+        for (cell, bit) in self.storage.iter_mut().zip(bits) {
+            *cell = bit;
+        }
+    }
+    fn neighbor_counts(&self) -> Vec<u8> {
+        let (rows, cols) = (self.rows, self.cols);
+        // the wrapped neighbors of each row and column are found once rather than per element
+        let neighbor_cols: Vec<[usize; 3]> = (0..cols)
+            .map(|col| [(col + cols - 1) % cols, col, (col + 1) % cols])
+            .collect();
+        let mut counts = Vec::with_capacity(rows * cols);
+        for row in 0..rows {
+            let neighbor_rows = [(row + rows - 1) % rows, row, (row + 1) % rows];
+            for (col, window_cols) in neighbor_cols.iter().enumerate() {
+                let window: u8 = neighbor_rows
+                    .iter()
+                    .flat_map(|r| window_cols.iter().map(move |c| r * cols + c))
+                    .map(|i| self.storage[i] as u8)
+                    .sum();
+                counts.push(window - self.storage[row * cols + col] as u8);
+            }
+        }
+
+        counts
+    }
     fn popcount(&self) -> u32 {
-        self.storage.iter().map(|b| *b as u32).sum()
+        self.storage
+            .chunks(u64::BITS as usize)
+            .map(|lane| pack_lane(lane).count_ones())
+            .sum()
+    }
+}
+
+/// Represents the matrix as a rectangular array of characters; see
+/// [`ToroidalBinaryMatrix::format_with`].
+/// Clears every element, leaving a matrix of the same shape with no elements set.
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for ToroidalBoolMatrix {
+    fn zeroize(&mut self) {
+        zeroize::Zeroize::zeroize(self.storage.as_mut_slice());
+    }
+}
+
+/// Clears the storage, so automaton states do not linger in memory.
+#[cfg(feature = "zeroize")]
+impl Drop for ToroidalBoolMatrix {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self);
+    }
+}
+
+impl fmt::Display for ToroidalBoolMatrix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.format_with(TRUE_CHAR, FALSE_CHAR, ""))
+    }
+}
+
+impl<I> Index<I> for ToroidalBoolMatrix
+where
+    I: Into<MatrixIndex>,
+{
+    type Output = bool;
+
+    fn index(&self, idx: I) -> &bool {
+        &self.storage[self.storage_index(idx.into())]
+    }
+}
+
+impl<I> IndexMut<I> for ToroidalBoolMatrix
+where
+    I: Into<MatrixIndex>,
+{
+    fn index_mut(&mut self, idx: I) -> &mut bool {
+        let vec_idx = self.storage_index(idx.into());
+        &mut self.storage[vec_idx]
+    }
+}
+
+/// Packs up to 64 cells into the low bits of a `u64`, first cell least significant.
+fn pack_lane(lane: &[bool]) -> u64 {
+    lane.iter()
+        .enumerate()
+        .fold(0, |word, (i, value)| word | (*value as u64) << i)
+}
+
+/// Writes the low bits of `word` back into the cells of `lane`, the inverse of [`pack_lane`].
+fn unpack_lane(word: u64, lane: &mut [bool]) {
+    for (i, value) in lane.iter_mut().enumerate() {
+        *value = (word >> i) & 1 != 0;
     }
 }
 
 impl ToroidalBoolMatrix {
+    /// Combines this matrix with `other` 64 cells at a time, packing each run of cells into a
+    /// `u64`, applying `op`, and unpacking the result. Both matrices must have the same shape.
+    fn apply_packed(&mut self, other: &ToroidalBoolMatrix, op: impl Fn(u64, u64) -> u64) {
+        let lanes = self.storage.chunks_mut(u64::BITS as usize);
+        for (lhs, rhs) in lanes.zip(other.storage.chunks(u64::BITS as usize)) {
+            unpack_lane(op(pack_lane(lhs), pack_lane(rhs)), lhs);
+        }
+    }
+    /// Returns the position in storage of the element at `idx`, applying toroidal wrapping.
+    fn storage_index(&self, idx: MatrixIndex) -> usize {
+        let idx = idx.wrapped(self.rows, self.cols);
+
+        idx.row as usize * self.cols + idx.col as usize
+    }
     /// Returns the storage backing the matrix.
     pub fn get_storage(&self) -> &Vec<bool> {
         &self.storage
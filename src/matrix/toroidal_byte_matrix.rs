@@ -0,0 +1,228 @@
+// 2025 Steven Chiacchira
+use crate::matrix::{
+    check_same_shape, MatrixConstructError, MatrixIndex, MatrixOpError, ToroidalBinaryMatrix,
+};
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// A matrix on a torus storing each entry as a `u8` in row-major order.
+///
+/// Indexing wraps in the same way as [`ToroidalBinaryMatrix`], but cells can hold any of 256
+/// states, making this the storage for multi-state automata and for grids of neighbor counts.
+pub struct ToroidalByteMatrix {
+    pub rows: usize,
+    pub cols: usize,
+    storage: Vec<u8>,
+}
+
+impl ToroidalByteMatrix {
+    /// Creates a new instance of a matrix with entries from a table of `u8` values.
+    pub fn new(table: Vec<Vec<u8>>) -> Result<Self, MatrixConstructError> {
+        let rows = table.len();
+        let cols = if rows == 0 { 0 } else { table[0].len() };
+        if cols == 0 {
+            return Err(MatrixConstructError::EmptyTable());
+        }
+        if table.iter().any(|row| row.len() != cols) {
+            return Err(MatrixConstructError::RaggedTable());
+        }
+
+        Self::from_storage(rows, cols, table.into_iter().flatten().collect())
+    }
+    /// Creates a new `rows` by `cols` matrix with every element set to `value`.
+    pub fn filled(rows: usize, cols: usize, value: u8) -> Result<Self, MatrixConstructError> {
+        Self::from_storage(rows, cols, vec![value; rows * cols])
+    }
+    /// Creates a matrix of the same shape as `matrix` holding the number of alive elements in the
+    /// [Moore neighborhood](https://en.wikipedia.org/wiki/Moore_neighborhood) of each element, as
+    /// computed by [`ToroidalBinaryMatrix::neighbor_counts`].
+    pub fn neighbor_counts_of<T>(matrix: &T) -> Self
+    where
+        T: ToroidalBinaryMatrix,
+    {
+        Self {
+            rows: matrix.get_rows(),
+            cols: matrix.get_cols(),
+            storage: matrix.neighbor_counts(),
+        }
+    }
+    /// Returns the number of rows the matrix has.
+    pub fn get_rows(&self) -> usize {
+        self.rows
+    }
+    /// Returns the number of columns the matrix has.
+    pub fn get_cols(&self) -> usize {
+        self.cols
+    }
+    /// Returns the value of the matrix element at `idx`, wrapping coordinates outside the matrix
+    /// as [`ToroidalBinaryMatrix::at`] does.
+    pub fn at(&self, idx: impl Into<MatrixIndex>) -> u8 {
+        self.storage[self.storage_index(idx.into())]
+    }
+    /// Sets the value of the matrix element at `idx` to `value` and returns the original value,
+    /// wrapping coordinates outside the matrix as [`ToroidalBinaryMatrix::set`] does.
+    pub fn set(&mut self, idx: impl Into<MatrixIndex>, value: u8) -> u8 {
+        let vec_idx = self.storage_index(idx.into());
+        std::mem::replace(&mut self.storage[vec_idx], value)
+    }
+    /// Returns the value of the matrix element at `idx`, or `None` if `idx` is outside the
+    /// matrix. No wrapping is applied.
+    pub fn try_at(&self, idx: impl Into<MatrixIndex>) -> Option<u8> {
+        let idx = idx.into();
+        let in_bounds = (0..self.rows as isize).contains(&idx.row)
+            && (0..self.cols as isize).contains(&idx.col);
+        in_bounds.then(|| self.at(idx))
+    }
+    /// Returns the number of elements in the [Moore
+    /// neighborhood](https://en.wikipedia.org/wiki/Moore_neighborhood) of `idx` whose value is
+    /// `state`. The neighborhood wraps around the torus.
+    pub fn count_neighbors(&self, idx: impl Into<MatrixIndex>, state: u8) -> u8 {
+        idx.into()
+            .moore_neighbors()
+            .filter(|neighbor| self.at(*neighbor) == state)
+            .count() as u8
+    }
+    /// Returns an iterator over the values of every element of this matrix in row-major order.
+    pub fn iter_cells(&self) -> impl Iterator<Item = u8> + '_ {
+        self.storage.iter().copied()
+    }
+    /// Returns an iterator over the indices and values of every element of this matrix in
+    /// row-major order.
+    pub fn enumerate_cells(&self) -> impl Iterator<Item = (MatrixIndex, u8)> + '_ {
+        self.storage.iter().enumerate().map(|(i, value)| {
+            (
+                MatrixIndex::new((i / self.cols) as isize, (i % self.cols) as isize),
+                *value,
+            )
+        })
+    }
+    /// Returns a [`ToroidalBinaryMatrix`] of the same shape whose elements are `true` where
+    /// `predicate` holds for the corresponding element of this matrix.
+    /// ex.
+    /// the matrix
+    /// ```txt
+    /// 0 1 2
+    /// 2 0 1
+    /// ```
+    /// mapped with `|state| state == 1` becomes
+    /// ```txt
+    /// .#.
+    /// ..#
+    /// ```
+    pub fn to_binary<T, F>(&self, predicate: F) -> T
+    where
+        T: ToroidalBinaryMatrix,
+        F: Fn(u8) -> bool,
+    {
+        // the shape of a byte matrix is never empty
+        T::from_bits(self.rows, self.cols, self.iter_cells().map(predicate)).unwrap()
+    }
+    /// Splits this matrix into its 8 bit planes, where element `(row, col)` of plane `i` is bit `i`
+    /// of element `(row, col)`, so plane 0 holds the least significant bits.
+    pub fn split_bit_planes<T>(&self) -> [T; 8]
+    where
+        T: ToroidalBinaryMatrix,
+    {
+        std::array::from_fn(|plane| self.to_binary(|value| (value >> plane) & 1 != 0))
+    }
+    /// Recombines 8 bit planes produced by [`ToroidalByteMatrix::split_bit_planes`] into a single
+    /// matrix. Returns a [`MatrixOpError`] if the planes do not all have the same shape.
+    pub fn from_bit_planes<T>(planes: &[T; 8]) -> Result<Self, MatrixOpError>
+    where
+        T: ToroidalBinaryMatrix,
+    {
+        for plane in &planes[1..] {
+            check_same_shape(&planes[0], plane)?;
+        }
+        let (rows, cols) = (planes[0].get_rows(), planes[0].get_cols());
+        let mut storage = vec![0_u8; rows * cols];
+        for (bit, plane) in planes.iter().enumerate() {
+            for (value, cell) in storage.iter_mut().zip(plane.iter_cells()) {
+                *value |= (cell as u8) << bit;
+            }
+        }
+
+        Ok(Self {
+            rows,
+            cols,
+            storage,
+        })
+    }
+    /// Returns the storage backing the matrix.
+    pub fn get_storage(&self) -> &Vec<u8> {
+        &self.storage
+    }
+    /// Constructs a new [`ToroidalByteMatrix`] from storage, as well as the count of rows and
+    /// columns. Returns an error if the storage is the wrong size for the specified matrix shape.
+    pub fn from_storage(
+        rows: usize,
+        cols: usize,
+        storage: Vec<u8>,
+    ) -> Result<Self, MatrixConstructError> {
+        if rows == 0 || cols == 0 {
+            return Err(MatrixConstructError::EmptyTable());
+        }
+        if storage.len() != rows * cols {
+            return Err(MatrixConstructError::InvalidStorage());
+        }
+        Ok(Self {
+            rows,
+            cols,
+            storage,
+        })
+    }
+    /// Returns the index into the storage vector of the element at `idx`.
+    fn storage_index(&self, idx: MatrixIndex) -> usize {
+        let idx = idx.wrapped(self.rows, self.cols);
+
+        idx.row as usize * self.cols + idx.col as usize
+    }
+}
+
+/// Converts a binary matrix into a byte matrix holding `1` for `true` elements and `0` for
+/// `false` elements.
+impl<T> From<&T> for ToroidalByteMatrix
+where
+    T: ToroidalBinaryMatrix,
+{
+    fn from(matrix: &T) -> Self {
+        Self {
+            rows: matrix.get_rows(),
+            cols: matrix.get_cols(),
+            storage: matrix.iter_cells().map(u8::from).collect(),
+        }
+    }
+}
+
+/// Represents the matrix as rows of space-separated decimal values.
+impl fmt::Display for ToroidalByteMatrix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.storage.chunks(self.cols) {
+            let cells = row.iter().map(u8::to_string).collect::<Vec<String>>();
+            writeln!(f, "{}", cells.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+impl<I> Index<I> for ToroidalByteMatrix
+where
+    I: Into<MatrixIndex>,
+{
+    type Output = u8;
+
+    fn index(&self, idx: I) -> &u8 {
+        &self.storage[self.storage_index(idx.into())]
+    }
+}
+
+impl<I> IndexMut<I> for ToroidalByteMatrix
+where
+    I: Into<MatrixIndex>,
+{
+    fn index_mut(&mut self, idx: I) -> &mut u8 {
+        let vec_idx = self.storage_index(idx.into());
+        &mut self.storage[vec_idx]
+    }
+}
@@ -0,0 +1,113 @@
+// 2025 Steven Chiacchira
+//! Compact binary format for automaton states, for checkpoints too large to keep as text tables.
+//!
+//! A state is written as a [`STATE_HEADER_BYTES`] byte header, made of the [`STATE_MAGIC`] bytes,
+//! the [`STATE_FORMAT_VERSION`] byte and the row and column counts as little-endian `u32`s,
+//! followed by the elements packed as by [`ToroidalBinaryMatrix::to_bytes`].
+use crate::matrix::{MatrixConstructError, ToroidalBinaryMatrix, ToroidalBitMatrix};
+use std::io::{self, Read, Write};
+use std::{error, fmt};
+
+/// Bytes starting every encoded state.
+pub const STATE_MAGIC: [u8; 4] = *b"TLSt";
+
+/// Version of the format written by [`write_state`].
+pub const STATE_FORMAT_VERSION: u8 = 1;
+
+/// Number of bytes in the header of an encoded state.
+pub const STATE_HEADER_BYTES: usize = STATE_MAGIC.len() + 1 + 4 + 4;
+
+/// Error arising from reading an encoded state.
+#[derive(Debug)]
+pub enum StateReadError {
+    /// The state could not be read, including when the input ends early.
+    Io(io::Error),
+    /// An encoded state must start with [`STATE_MAGIC`].
+    InvalidMagic(),
+    /// Only states of a known format version can be read. Holds the version which was given.
+    UnsupportedVersion(u8),
+    /// The header must describe a valid matrix.
+    InvalidShape(MatrixConstructError),
+}
+
+impl fmt::Display for StateReadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StateReadError::Io(e) => write!(f, "I/O failed: {}", e),
+            StateReadError::InvalidMagic() => write!(f, "input is not an encoded state"),
+            StateReadError::UnsupportedVersion(version) => {
+                write!(f, "state format version {} is not supported", version)
+            }
+            StateReadError::InvalidShape(e) => write!(f, "state has an invalid shape: {}", e),
+        }
+    }
+}
+
+impl error::Error for StateReadError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            StateReadError::Io(e) => Some(e),
+            StateReadError::InvalidShape(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for StateReadError {
+    fn from(e: io::Error) -> Self {
+        StateReadError::Io(e)
+    }
+}
+
+/// Writes `state` to `writer` in the binary state format.
+///
+/// # Panics
+/// If `state` has more than `u32::MAX` rows or columns.
+pub fn write_state<W>(state: &ToroidalBitMatrix, mut writer: W) -> io::Result<()>
+where
+    W: Write,
+{
+    let rows = u32::try_from(state.get_rows()).expect("state exceeds u32::MAX rows");
+    let cols = u32::try_from(state.get_cols()).expect("state exceeds u32::MAX columns");
+
+    let mut header = [0_u8; STATE_HEADER_BYTES];
+    header[..4].copy_from_slice(&STATE_MAGIC);
+    header[4] = STATE_FORMAT_VERSION;
+    header[5..9].copy_from_slice(&rows.to_le_bytes());
+    header[9..].copy_from_slice(&cols.to_le_bytes());
+    writer.write_all(&header)?;
+    writer.write_all(&state.to_bytes())
+}
+
+/// Reads a state written by [`write_state`] from `reader`.
+pub fn read_state<R>(mut reader: R) -> Result<ToroidalBitMatrix, StateReadError>
+where
+    R: Read,
+{
+    let mut header = [0_u8; STATE_HEADER_BYTES];
+    reader.read_exact(&mut header)?;
+    if header[..4] != STATE_MAGIC {
+        return Err(StateReadError::InvalidMagic());
+    }
+    if header[4] != STATE_FORMAT_VERSION {
+        return Err(StateReadError::UnsupportedVersion(header[4]));
+    }
+    // the slices have the lengths of the integers they hold
+    let rows = u32::from_le_bytes(header[5..9].try_into().unwrap()) as usize;
+    let cols = u32::from_le_bytes(header[9..].try_into().unwrap()) as usize;
+    if rows == 0 || cols == 0 {
+        return Err(StateReadError::InvalidShape(
+            MatrixConstructError::EmptyTable(),
+        ));
+    }
+
+    // read incrementally, so a corrupted shape cannot allocate more than is actually sent
+    let n_bytes = (rows as u64 * cols as u64).div_ceil(u8::BITS as u64);
+    let mut bytes = Vec::new();
+    reader.take(n_bytes).read_to_end(&mut bytes)?;
+    if (bytes.len() as u64) < n_bytes {
+        return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+    }
+
+    ToroidalBitMatrix::from_bytes(rows, cols, &bytes).map_err(StateReadError::InvalidShape)
+}
@@ -0,0 +1,129 @@
+// 2025 Steven Chiacchira
+use crate::matrix::BitOrder;
+
+/// Iterator splitting the bytes of an inner iterator into their bits, eight per byte in the
+/// given [`BitOrder`]. Bytes are taken from the inner iterator only as their bits are needed, so
+/// a message never has to be held as a whole `Vec<bool>`.
+/// See also [`BitPacker`].
+#[derive(Debug, Clone)]
+pub struct BitUnpacker<I>
+where
+    I: Iterator<Item = u8>,
+{
+    bytes: I,
+    order: BitOrder,
+    /// The byte being split and the number of its bits already emitted.
+    current: Option<(u8, u32)>,
+}
+
+impl<I> BitUnpacker<I>
+where
+    I: Iterator<Item = u8>,
+{
+    /// Creates an unpacker over `bytes`, emitting the bits of each byte in `order`.
+    pub fn new<B>(bytes: B, order: BitOrder) -> Self
+    where
+        B: IntoIterator<IntoIter = I>,
+    {
+        Self {
+            bytes: bytes.into_iter(),
+            order,
+            current: None,
+        }
+    }
+}
+
+impl<I> Iterator for BitUnpacker<I>
+where
+    I: Iterator<Item = u8>,
+{
+    type Item = bool;
+
+    fn next(&mut self) -> Option<bool> {
+        let (byte, n_used) = match self.current {
+            Some(current) => current,
+            None => (self.bytes.next()?, 0),
+        };
+        let shift = match self.order {
+            BitOrder::LsbFirst => n_used,
+            BitOrder::MsbFirst => u8::BITS - 1 - n_used,
+        };
+        self.current = (n_used + 1 < u8::BITS).then_some((byte, n_used + 1));
+
+        Some((byte >> shift) & 1 != 0)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n_current = self
+            .current
+            .map_or(0, |(_, n_used)| (u8::BITS - n_used) as usize);
+        let (lower, upper) = self.bytes.size_hint();
+        let to_bits = |n: usize| n.checked_mul(u8::BITS as usize)?.checked_add(n_current);
+
+        (
+            to_bits(lower).unwrap_or(usize::MAX),
+            upper.and_then(to_bits),
+        )
+    }
+}
+
+/// Iterator joining the bits of an inner iterator into bytes, eight per byte in the given
+/// [`BitOrder`]. A final partial byte is padded with `0` bits. Bits are taken from the inner
+/// iterator only as bytes are needed, so a message never has to be held as a whole `Vec<bool>`.
+/// See also [`BitUnpacker`].
+#[derive(Debug, Clone)]
+pub struct BitPacker<I>
+where
+    I: Iterator<Item = bool>,
+{
+    bits: I,
+    order: BitOrder,
+}
+
+impl<I> BitPacker<I>
+where
+    I: Iterator<Item = bool>,
+{
+    /// Creates a packer over `bits`, storing them in each byte in `order`.
+    pub fn new<B>(bits: B, order: BitOrder) -> Self
+    where
+        B: IntoIterator<IntoIter = I>,
+    {
+        Self {
+            bits: bits.into_iter(),
+            order,
+        }
+    }
+}
+
+impl<I> Iterator for BitPacker<I>
+where
+    I: Iterator<Item = bool>,
+{
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        let mut byte = 0;
+        let mut n_bits = 0;
+        while n_bits < u8::BITS {
+            let Some(bit) = self.bits.next() else {
+                break;
+            };
+            let shift = match self.order {
+                BitOrder::LsbFirst => n_bits,
+                BitOrder::MsbFirst => u8::BITS - 1 - n_bits,
+            };
+            byte |= (bit as u8) << shift;
+            n_bits += 1;
+        }
+
+        (n_bits > 0).then_some(byte)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.bits.size_hint();
+        let to_bytes = |n: usize| n.div_ceil(u8::BITS as usize);
+
+        (to_bytes(lower), upper.map(to_bytes))
+    }
+}
@@ -0,0 +1,163 @@
+// 2025 Steven Chiacchira
+use crate::matrix::{FALSE_CHAR, TRUE_CHAR};
+use crate::parse::{seed_bindings, DEFAULT_KEYS};
+use std::collections::HashMap;
+use std::{error, fmt};
+
+/// Error occurring during the building of a [`CharMap`].
+#[derive(Debug)]
+pub enum CharMapError {
+    /// A seeded alphabet can hold at most one character per bit of the seed. Holds the number of
+    /// characters given.
+    AlphabetTooLong(usize),
+    /// Each character may only be bound to one value. Holds the character bound to both.
+    ConflictingBinding(char),
+}
+
+impl fmt::Display for CharMapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CharMapError::AlphabetTooLong(len) => write!(
+                f,
+                "alphabet has {} characters but a seed has only {} bits",
+                len,
+                u32::BITS
+            ),
+            CharMapError::ConflictingBinding(c) => {
+                write!(f, "character {:?} is bound to both true and false", c)
+            }
+        }
+    }
+}
+
+impl error::Error for CharMapError {}
+
+/// Map from the characters of a table to the `bool` values they stand for, used by
+/// [`parse_bool_table`](crate::parse::parse_bool_table).
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CharMap {
+    map: HashMap<char, bool>,
+}
+
+impl CharMap {
+    /// Returns a builder for a new map.
+    pub fn builder() -> CharMapBuilder {
+        CharMapBuilder::default()
+    }
+
+    /// Creates the map used for the initial automaton states: the base-32 digits bound by `seed`
+    /// as [`gen_char_map`](crate::parse::gen_char_map) does, [`TRUE_CHAR`] bound to `true` and
+    /// [`FALSE_CHAR`] bound to `false`.
+    pub fn seeded(seed: u32) -> Self {
+        // the default alphabet holds 32 characters, neither of which is a matrix symbol
+        Self::builder().seed(seed).matrix_symbols().build().unwrap()
+    }
+
+    /// Returns the value `c` stands for, or `None` if it is not bound.
+    pub fn get(&self, c: char) -> Option<bool> {
+        self.map.get(&c).copied()
+    }
+
+    /// Returns the character used to write `value`: [`TRUE_CHAR`] or [`FALSE_CHAR`] if it is bound
+    /// to `value`, and otherwise the lowest character bound to `value`. Returns `None` if no
+    /// character is bound to `value`.
+    pub fn char_for(&self, value: bool) -> Option<char> {
+        let symbol = if value { TRUE_CHAR } else { FALSE_CHAR };
+        if self.get(symbol) == Some(value) {
+            return Some(symbol);
+        }
+
+        self.map
+            .iter()
+            .filter(|(_, v)| **v == value)
+            .map(|(c, _)| *c)
+            .min()
+    }
+
+    /// Returns the number of bound characters.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns whether no characters are bound.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+}
+
+impl From<HashMap<char, bool>> for CharMap {
+    fn from(map: HashMap<char, bool>) -> Self {
+        Self { map }
+    }
+}
+
+/// Builder for a [`CharMap`]. Characters of the alphabet are bound to the bits of the seed, and
+/// further characters can be bound explicitly. Binding a character to both `true` and `false`
+/// makes [`CharMapBuilder::build`] fail.
+#[derive(Debug, Clone)]
+pub struct CharMapBuilder {
+    alphabet: Vec<char>,
+    seed: Option<u32>,
+    bindings: Vec<(char, bool)>,
+}
+
+impl Default for CharMapBuilder {
+    fn default() -> Self {
+        Self {
+            alphabet: DEFAULT_KEYS.chars().collect(),
+            seed: None,
+            bindings: Vec::new(),
+        }
+    }
+}
+
+impl CharMapBuilder {
+    /// Sets the characters bound by the seed, the `i`th character taking bit `i` of the seed.
+    /// Defaults to the base-32 digits `A` to `Z` and `2` to `7`.
+    pub fn alphabet(mut self, alphabet: &str) -> Self {
+        self.alphabet = alphabet.chars().collect();
+        self
+    }
+
+    /// Binds the characters of the alphabet to the bits of `seed`. Without a seed the alphabet is
+    /// left unbound.
+    pub fn seed(mut self, seed: u32) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Binds `c` to `value`.
+    pub fn bind(mut self, c: char, value: bool) -> Self {
+        self.bindings.push((c, value));
+        self
+    }
+
+    /// Binds [`TRUE_CHAR`] to `true` and [`FALSE_CHAR`] to `false`, the characters used for
+    /// matrices in files and strings.
+    pub fn matrix_symbols(self) -> Self {
+        self.bind(TRUE_CHAR, true).bind(FALSE_CHAR, false)
+    }
+
+    /// Builds the map, returning a [`CharMapError`] if the seeded alphabet is longer than 32
+    /// characters or a character is bound to both `true` and `false`.
+    pub fn build(self) -> Result<CharMap, CharMapError> {
+        let mut map = HashMap::new();
+        let seeded = match self.seed {
+            Some(seed) => {
+                if self.alphabet.len() > u32::BITS as usize {
+                    return Err(CharMapError::AlphabetTooLong(self.alphabet.len()));
+                }
+                seed_bindings(self.alphabet, seed).collect()
+            }
+            None => Vec::new(),
+        };
+
+        for (c, value) in seeded.into_iter().chain(self.bindings) {
+            if map.insert(c, value).is_some_and(|old| old != value) {
+                return Err(CharMapError::ConflictingBinding(c));
+            }
+        }
+
+        Ok(CharMap { map })
+    }
+}
@@ -0,0 +1,63 @@
+// 2025 Steven Chiacchira
+use crate::encrypt::{decode, encode, CiphertextEncoding, DecodeError};
+use crate::matrix::{MatrixConstructError, ToroidalBinaryMatrix};
+use std::{error, fmt};
+
+/// Error occurring during the reading of a hexadecimal matrix state.
+#[derive(Debug)]
+pub enum HexStateError {
+    /// The state must be valid hexadecimal text with two digits per byte.
+    InvalidHex(DecodeError),
+    /// The state must hold exactly enough bytes for a matrix of the given shape.
+    InvalidShape(MatrixConstructError),
+}
+
+impl fmt::Display for HexStateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HexStateError::InvalidHex(e) => write!(f, "state is not valid hexadecimal: {}", e),
+            HexStateError::InvalidShape(e) => {
+                write!(f, "state does not fit the matrix shape: {}", e)
+            }
+        }
+    }
+}
+
+impl error::Error for HexStateError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            HexStateError::InvalidHex(e) => Some(e),
+            HexStateError::InvalidShape(e) => Some(e),
+        }
+    }
+}
+
+/// Reads a `rows` by `cols` matrix from hexadecimal text of its packed bytes, as written by
+/// [`format_hex_state`]. Surrounding whitespace is ignored and uppercase digits are accepted.
+/// Ex:
+/// the 2 by 4 state `"a5"` specifies the matrix
+/// ```txt
+/// #.#.
+/// .#.#
+/// ```
+/// since elements are packed starting from the least significant bit, see
+/// [`ToroidalBinaryMatrix::from_bytes`].
+pub fn parse_hex_state<T>(rows: usize, cols: usize, string: &str) -> Result<T, HexStateError>
+where
+    T: ToroidalBinaryMatrix,
+{
+    let bytes =
+        decode(string.trim(), CiphertextEncoding::Hex).map_err(HexStateError::InvalidHex)?;
+
+    T::from_bytes(rows, cols, &bytes).map_err(HexStateError::InvalidShape)
+}
+
+/// Writes `matrix` as lowercase hexadecimal text of its packed bytes on a single line, see
+/// [`ToroidalBinaryMatrix::to_bytes`]. The shape is not included and must be passed to
+/// [`parse_hex_state`] separately.
+pub fn format_hex_state<T>(matrix: &T) -> String
+where
+    T: ToroidalBinaryMatrix,
+{
+    encode(&matrix.to_bytes(), CiphertextEncoding::Hex)
+}
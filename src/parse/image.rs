@@ -0,0 +1,285 @@
+// 2025 Steven Chiacchira
+//! Import of [Netpbm](https://netpbm.sourceforge.net/doc/) images as bool tables, so that drawn
+//! patterns can be used as automaton states or init matrices.
+//!
+//! Both the plain (`P1`, `P2`) and raw (`P4`, `P5`) variants of PBM bitmaps and PGM grayscale
+//! images are read. A PBM pixel is `true` when it is black, stored as `1`, and a PGM pixel is
+//! `true` when it is at least as bright as a threshold, as in
+//! [`from_luma_threshold`](crate::matrix::ToroidalBinaryMatrix::from_luma_threshold).
+//!
+//! With the `image` feature, PNG images can also be read into matrices with `from_png` and
+//! matrices written out as PNG images with `write_png`.
+#[cfg(feature = "image")]
+use crate::matrix::ToroidalBinaryMatrix;
+#[cfg(feature = "image")]
+use std::io::{self, Write};
+use std::{error, fmt};
+
+/// Error occurring during the reading of a Netpbm image.
+#[derive(Debug)]
+pub enum ImageReadError {
+    /// The image must start with the magic number of a supported format.
+    UnsupportedFormat(),
+    /// The header must hold a nonzero width and height, and for PGM a maximum value between 1 and
+    /// 65535, as decimal numbers.
+    InvalidHeader(),
+    /// The image must hold a sample for every pixel.
+    TruncatedData(),
+    /// Every sample must be at most the maximum value of the image, which is 1 for PBM. Holds the
+    /// first sample which was not.
+    InvalidSample(u32),
+    /// A PNG image must be decodable. Holds the error of the decoder.
+    #[cfg(feature = "image")]
+    InvalidPng(png::DecodingError),
+}
+
+impl fmt::Display for ImageReadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImageReadError::UnsupportedFormat() => write!(f, "image is not a PBM or PGM image"),
+            ImageReadError::InvalidHeader() => write!(f, "image header is invalid"),
+            ImageReadError::TruncatedData() => write!(f, "image ends before its last pixel"),
+            ImageReadError::InvalidSample(sample) => {
+                write!(f, "image sample {} exceeds the maximum value", sample)
+            }
+            #[cfg(feature = "image")]
+            ImageReadError::InvalidPng(e) => write!(f, "PNG image is invalid: {}", e),
+        }
+    }
+}
+
+impl error::Error for ImageReadError {}
+
+/// Reads a PBM bitmap in the plain `P1` or raw `P4` format as a bool table, with black pixels
+/// `true`.
+pub fn from_pbm(bytes: &[u8]) -> Result<Vec<Vec<bool>>, ImageReadError> {
+    let mut reader = NetpbmReader::new(bytes);
+    let raw = match reader.magic()? {
+        b"P1" => false,
+        b"P4" => true,
+        _ => return Err(ImageReadError::UnsupportedFormat()),
+    };
+    let (width, height) = reader.dimensions()?;
+
+    if raw {
+        let data = reader.raw_data()?;
+        let row_bytes = width.div_ceil(u8::BITS as usize);
+        let data = data
+            .get(..row_bytes * height)
+            .ok_or(ImageReadError::TruncatedData())?;
+        Ok(data
+            .chunks_exact(row_bytes)
+            .map(|row| {
+                (0..width)
+                    .map(|col| (row[col / 8] >> (7 - col % 8)) & 1 != 0)
+                    .collect()
+            })
+            .collect())
+    } else {
+        // plain PBM samples are single digits which need not be separated
+        let mut pixels = Vec::with_capacity((width * height).min(bytes.len()));
+        while pixels.len() < width * height {
+            reader.skip_whitespace();
+            match reader.next_byte() {
+                Some(b'0') => pixels.push(false),
+                Some(b'1') => pixels.push(true),
+                Some(c) if c.is_ascii_digit() => {
+                    return Err(ImageReadError::InvalidSample((c - b'0') as u32))
+                }
+                _ => return Err(ImageReadError::TruncatedData()),
+            }
+        }
+        Ok(pixels.chunks(width).map(|row| row.to_vec()).collect())
+    }
+}
+
+/// Reads a PGM grayscale image in the plain `P2` or raw `P5` format as a bool table, with pixels
+/// at least as bright as `threshold` `true`. `threshold` is compared with the samples as stored,
+/// so it is relative to the maximum value given in the header.
+pub fn from_pgm(bytes: &[u8], threshold: u16) -> Result<Vec<Vec<bool>>, ImageReadError> {
+    let mut reader = NetpbmReader::new(bytes);
+    let raw = match reader.magic()? {
+        b"P2" => false,
+        b"P5" => true,
+        _ => return Err(ImageReadError::UnsupportedFormat()),
+    };
+    let (width, height) = reader.dimensions()?;
+    let max_value = reader.number().ok_or(ImageReadError::InvalidHeader())?;
+    if max_value == 0 || max_value > u16::MAX as usize {
+        return Err(ImageReadError::InvalidHeader());
+    }
+
+    let n_pixels = width * height;
+    let samples = if raw {
+        // samples take two big-endian bytes when the maximum value does not fit in one
+        let sample_bytes = if max_value > u8::MAX as usize { 2 } else { 1 };
+        let data = reader.raw_data()?;
+        if data.len() / sample_bytes < n_pixels {
+            return Err(ImageReadError::TruncatedData());
+        }
+        data.chunks_exact(sample_bytes)
+            .take(n_pixels)
+            .map(|sample| sample.iter().fold(0, |acc, byte| acc << 8 | *byte as usize))
+            .collect::<Vec<usize>>()
+    } else {
+        (0..n_pixels)
+            .map(|_| reader.number().ok_or(ImageReadError::TruncatedData()))
+            .collect::<Result<Vec<usize>, _>>()?
+    };
+
+    if let Some(sample) = samples.iter().find(|sample| **sample > max_value) {
+        return Err(ImageReadError::InvalidSample(*sample as u32));
+    }
+    Ok(samples
+        .chunks(width)
+        .map(|row| row.iter().map(|s| *s >= threshold as usize).collect())
+        .collect())
+}
+
+/// Reads a PNG image of any color type as a matrix, with pixels whose luma is at least
+/// `threshold` `true`, see [`ToroidalBinaryMatrix::from_luma_threshold`]. Samples are reduced to
+/// 8 bits, and luma is computed with the Rec. 601 weights, ignoring any alpha channel.
+#[cfg(feature = "image")]
+pub fn from_png<T>(bytes: &[u8], threshold: u8) -> Result<T, ImageReadError>
+where
+    T: ToroidalBinaryMatrix,
+{
+    let mut decoder = png::Decoder::new(bytes);
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info().map_err(ImageReadError::InvalidPng)?;
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let info = reader
+        .next_frame(&mut buffer)
+        .map_err(ImageReadError::InvalidPng)?;
+    let samples = &buffer[..info.buffer_size()];
+
+    // indexed images are expanded to RGB by the transformations
+    let luma = match info.color_type {
+        png::ColorType::Grayscale | png::ColorType::Indexed => samples.to_vec(),
+        png::ColorType::GrayscaleAlpha => samples.iter().step_by(2).copied().collect(),
+        png::ColorType::Rgb | png::ColorType::Rgba => samples
+            .chunks_exact(info.color_type.samples())
+            .map(|pixel| {
+                let (r, g, b) = (pixel[0] as u32, pixel[1] as u32, pixel[2] as u32);
+                ((299 * r + 587 * g + 114 * b) / 1000) as u8
+            })
+            .collect(),
+    };
+
+    // the decoder produces one luma value per pixel, and PNG images are never empty
+    Ok(
+        T::from_luma_threshold(info.width as usize, info.height as usize, &luma, threshold)
+            .unwrap(),
+    )
+}
+
+/// Writes `matrix` to `writer` as an 8 bit grayscale PNG image with one pixel per element, white
+/// for `true` and black for `false`, so that [`from_png`] reads it back with any threshold above
+/// 0. An automaton state can be written with
+/// [`Automaton::get_state`](crate::automata::Automaton::get_state).
+///
+/// # Panics
+/// If `matrix` has more than `u32::MAX` rows or columns.
+#[cfg(feature = "image")]
+pub fn write_png<T, W>(matrix: &T, writer: W) -> io::Result<()>
+where
+    T: ToroidalBinaryMatrix,
+    W: Write,
+{
+    let width = u32::try_from(matrix.get_cols()).expect("matrix exceeds u32::MAX columns");
+    let height = u32::try_from(matrix.get_rows()).expect("matrix exceeds u32::MAX rows");
+    let pixels = matrix
+        .iter_cells()
+        .map(|value| if value { u8::MAX } else { 0 })
+        .collect::<Vec<u8>>();
+
+    let mut encoder = png::Encoder::new(writer, width, height);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut png_writer = encoder.write_header().map_err(io::Error::other)?;
+    png_writer
+        .write_image_data(&pixels)
+        .map_err(io::Error::other)?;
+    png_writer.finish().map_err(io::Error::other)
+}
+
+/// Cursor over the bytes of a Netpbm image.
+struct NetpbmReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> NetpbmReader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, position: 0 }
+    }
+
+    /// Returns the two byte magic number starting the image.
+    fn magic(&mut self) -> Result<&'a [u8], ImageReadError> {
+        let magic = self
+            .bytes
+            .get(..2)
+            .ok_or(ImageReadError::UnsupportedFormat())?;
+        self.position = 2;
+        Ok(magic)
+    }
+
+    /// Reads the nonzero width and height of the image.
+    fn dimensions(&mut self) -> Result<(usize, usize), ImageReadError> {
+        let width = self.number().ok_or(ImageReadError::InvalidHeader())?;
+        let height = self.number().ok_or(ImageReadError::InvalidHeader())?;
+        if width == 0 || height == 0 || width.checked_mul(height).is_none() {
+            return Err(ImageReadError::InvalidHeader());
+        }
+
+        Ok((width, height))
+    }
+
+    /// Reads the next decimal number, skipping whitespace and comments before it. Returns `None`
+    /// if there is no number or it does not fit in a `usize`.
+    fn number(&mut self) -> Option<usize> {
+        self.skip_whitespace();
+        let start = self.position;
+        while self
+            .bytes
+            .get(self.position)
+            .is_some_and(|c| c.is_ascii_digit())
+        {
+            self.position += 1;
+        }
+
+        // the bytes are ASCII digits
+        std::str::from_utf8(&self.bytes[start..self.position])
+            .unwrap()
+            .parse()
+            .ok()
+    }
+
+    /// Returns the pixel data of a raw image, which follows a single whitespace byte after the
+    /// header.
+    fn raw_data(&mut self) -> Result<&'a [u8], ImageReadError> {
+        match self.next_byte() {
+            Some(c) if c.is_ascii_whitespace() => Ok(&self.bytes[self.position..]),
+            _ => Err(ImageReadError::InvalidHeader()),
+        }
+    }
+
+    /// Skips whitespace and comments, which run from `#` to the end of the line.
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.bytes.get(self.position) {
+            if *c == b'#' {
+                while self.next_byte().is_some_and(|c| c != b'\n' && c != b'\r') {}
+            } else if c.is_ascii_whitespace() {
+                self.position += 1;
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn next_byte(&mut self) -> Option<u8> {
+        let byte = self.bytes.get(self.position).copied();
+        self.position += byte.is_some() as usize;
+        byte
+    }
+}
@@ -0,0 +1,111 @@
+// 2025 Steven Chiacchira
+use crate::matrix::{FALSE_CHAR, TRUE_CHAR};
+use crate::parse::DEFAULT_KEYS;
+use std::fmt;
+
+/// Problem found in an init matrix by [`validate_init_matrix`]. Lines and columns are counted
+/// from 1, with columns counted in characters.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum InitMatrixIssue {
+    /// The matrix must have the expected number of rows.
+    WrongRowCount { expected: usize, found: usize },
+    /// Every row must have the expected number of columns.
+    WrongColumnCount {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// Only the base-32 digits, [`TRUE_CHAR`] and [`FALSE_CHAR`] may appear in the matrix.
+    InvalidCharacter {
+        character: char,
+        line: usize,
+        column: usize,
+    },
+    /// Every base-32 digit must appear at least once, or the matching bit of a seed never reaches
+    /// the automaton. Holds the missing digit.
+    MissingSymbol(char),
+}
+
+impl fmt::Display for InitMatrixIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InitMatrixIssue::WrongRowCount { expected, found } => {
+                write!(f, "matrix has {} rows but should have {}", found, expected)
+            }
+            InitMatrixIssue::WrongColumnCount {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "line {} has {} columns but should have {}",
+                line, found, expected
+            ),
+            InitMatrixIssue::InvalidCharacter {
+                character,
+                line,
+                column,
+            } => write!(
+                f,
+                "invalid character {:?} at line {}, column {}",
+                character, line, column
+            ),
+            InitMatrixIssue::MissingSymbol(symbol) => {
+                write!(f, "symbol {:?} does not appear in the matrix", symbol)
+            }
+        }
+    }
+}
+
+/// Checks that `string` is usable as an init matrix, such as
+/// [`TRANSPOSE_INIT_MATRIX`](crate::encrypt::TRANSPOSE_INIT_MATRIX), of `expected_dims` rows and
+/// columns: it must have that shape, hold only base-32 digits, [`TRUE_CHAR`] and [`FALSE_CHAR`],
+/// and hold each of the 32 base-32 digits at least once so that every bit of a seed is seeded.
+/// Returns every problem found, in the order of the text, so an empty result means the matrix is
+/// valid.
+pub fn validate_init_matrix(string: &str, expected_dims: (usize, usize)) -> Vec<InitMatrixIssue> {
+    let (expected_rows, expected_cols) = expected_dims;
+    let mut issues = Vec::new();
+    let mut seen = [false; DEFAULT_KEYS.len()];
+
+    let mut n_rows = 0;
+    for (i, line) in string.lines().enumerate() {
+        n_rows += 1;
+        let mut n_cols = 0;
+        for (j, character) in line.chars().enumerate() {
+            n_cols += 1;
+            if let Some(symbol) = DEFAULT_KEYS.chars().position(|c| c == character) {
+                seen[symbol] = true;
+            } else if character != TRUE_CHAR && character != FALSE_CHAR {
+                issues.push(InitMatrixIssue::InvalidCharacter {
+                    character,
+                    line: i + 1,
+                    column: j + 1,
+                });
+            }
+        }
+        if n_cols != expected_cols {
+            issues.push(InitMatrixIssue::WrongColumnCount {
+                line: i + 1,
+                expected: expected_cols,
+                found: n_cols,
+            });
+        }
+    }
+    if n_rows != expected_rows {
+        issues.push(InitMatrixIssue::WrongRowCount {
+            expected: expected_rows,
+            found: n_rows,
+        });
+    }
+
+    issues.extend(
+        DEFAULT_KEYS
+            .chars()
+            .zip(seen)
+            .filter(|(_, seen)| !seen)
+            .map(|(symbol, _)| InitMatrixIssue::MissingSymbol(symbol)),
+    );
+
+    issues
+}
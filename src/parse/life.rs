@@ -0,0 +1,160 @@
+// 2025 Steven Chiacchira
+use std::{error, fmt};
+
+/// First line of a pattern in the Life 1.05 format.
+const LIFE_105_HEADER: &str = "#Life 1.05";
+/// First line of a pattern in the Life 1.06 format.
+const LIFE_106_HEADER: &str = "#Life 1.06";
+
+/// Error occurring during the reading of a Life 1.05 or Life 1.06 pattern.
+#[derive(Debug)]
+pub enum LifeReadError {
+    /// A pattern must start with the header line of its format.
+    MissingHeader(),
+    /// Life 1.05 cell lines may only hold `*` for live and `.` for dead cells. Holds the first
+    /// invalid character.
+    InvalidCharacter(char),
+    /// A `#P` line of a Life 1.05 pattern or a coordinate line of a Life 1.06 pattern must hold
+    /// exactly two integers. Holds the 1-based number of the offending line.
+    InvalidLine(usize),
+}
+
+impl fmt::Display for LifeReadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LifeReadError::MissingHeader() => {
+                write!(f, "pattern does not start with a Life header")
+            }
+            LifeReadError::InvalidCharacter(c) => {
+                write!(f, "pattern has invalid cell character {:?}", c)
+            }
+            LifeReadError::InvalidLine(line) => write!(f, "pattern line {} is invalid", line),
+        }
+    }
+}
+
+impl error::Error for LifeReadError {}
+
+/// Reads a pattern in the plaintext [Life 1.05](https://conwaylife.com/wiki/Life_1.05) format
+/// as a bool table.
+/// Ex:
+/// ```txt
+/// #Life 1.05
+/// #D A glider
+/// #N
+/// #P -1 -1
+/// .*.
+/// ..*
+/// ***
+/// ```
+///
+/// specifies the table
+/// ```txt
+/// FTF
+/// FFT
+/// TTT
+/// ```
+/// Each `#P x y` line places the cell lines below it with their first cell at column `x` and row
+/// `y`, and cell lines before any `#P` line start at column and row 0. Other lines starting with
+/// `#`, such as descriptions and rules, are ignored. The table spans the smallest rectangle
+/// holding every live cell, so it is empty if there are none.
+pub fn parse_life_105(string: &str) -> Result<Vec<Vec<bool>>, LifeReadError> {
+    let mut lines = string.lines().enumerate();
+    check_header(lines.next(), LIFE_105_HEADER)?;
+
+    let mut live_cells = Vec::new();
+    let (mut block_col, mut next_row) = (0, 0);
+    for (i, line) in lines {
+        let line = line.trim_end();
+        if let Some(position) = line.strip_prefix("#P") {
+            (block_col, next_row) = parse_coordinates(position, i)?;
+        } else if line.starts_with('#') || line.is_empty() {
+            continue;
+        } else {
+            for (col, c) in line.chars().enumerate() {
+                match c {
+                    '*' => live_cells.push((next_row, block_col + col as isize)),
+                    '.' => {}
+                    _ => return Err(LifeReadError::InvalidCharacter(c)),
+                }
+            }
+            next_row += 1;
+        }
+    }
+
+    Ok(cells_to_table(&live_cells))
+}
+
+/// Reads a pattern in the coordinate list [Life 1.06](https://conwaylife.com/wiki/Life_1.06)
+/// format as a bool table.
+/// Ex:
+/// ```txt
+/// #Life 1.06
+/// 0 -1
+/// 1 0
+/// -1 1
+/// 0 1
+/// 1 1
+/// ```
+///
+/// specifies the same glider as the example of [`parse_life_105`]. Each line after the header
+/// holds the column `x` and row `y` of one live cell. Blank lines and lines starting with `#` are
+/// ignored. The table spans the smallest rectangle holding every live cell, so it is empty if
+/// there are none.
+pub fn parse_life_106(string: &str) -> Result<Vec<Vec<bool>>, LifeReadError> {
+    let mut lines = string.lines().enumerate();
+    check_header(lines.next(), LIFE_106_HEADER)?;
+
+    let mut live_cells = Vec::new();
+    for (i, line) in lines {
+        let line = line.trim();
+        if line.starts_with('#') || line.is_empty() {
+            continue;
+        }
+        let (col, row) = parse_coordinates(line, i)?;
+        live_cells.push((row, col));
+    }
+
+    Ok(cells_to_table(&live_cells))
+}
+
+/// Returns a [`LifeReadError::MissingHeader`] unless `first_line` is `header`.
+fn check_header(first_line: Option<(usize, &str)>, header: &str) -> Result<(), LifeReadError> {
+    match first_line {
+        Some((_, line)) if line.trim_end() == header => Ok(()),
+        _ => Err(LifeReadError::MissingHeader()),
+    }
+}
+
+/// Parses the two whitespace separated integers `x y` of 0-based line `i`.
+fn parse_coordinates(string: &str, i: usize) -> Result<(isize, isize), LifeReadError> {
+    let values = string
+        .split_whitespace()
+        .map(|value| value.parse::<isize>())
+        .collect::<Result<Vec<isize>, _>>()
+        .map_err(|_| LifeReadError::InvalidLine(i + 1))?;
+    match values[..] {
+        [x, y] => Ok((x, y)),
+        _ => Err(LifeReadError::InvalidLine(i + 1)),
+    }
+}
+
+/// Builds the table spanning the smallest rectangle holding every `(row, col)` in `live_cells`.
+fn cells_to_table(live_cells: &[(isize, isize)]) -> Vec<Vec<bool>> {
+    let (Some(min_row), Some(max_row), Some(min_col), Some(max_col)) = (
+        live_cells.iter().map(|(row, _)| *row).min(),
+        live_cells.iter().map(|(row, _)| *row).max(),
+        live_cells.iter().map(|(_, col)| *col).min(),
+        live_cells.iter().map(|(_, col)| *col).max(),
+    ) else {
+        return Vec::new();
+    };
+
+    let mut table =
+        vec![vec![false; (max_col - min_col + 1) as usize]; (max_row - min_row + 1) as usize];
+    for (row, col) in live_cells {
+        table[(row - min_row) as usize][(col - min_col) as usize] = true;
+    }
+
+    table
+}
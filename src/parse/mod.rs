@@ -1,6 +1,21 @@
 // 2025 Steven Chiacchira
+pub mod binary;
+mod bit_stream;
+mod char_map;
+mod hex;
+pub mod image;
+mod init_matrix;
+mod life;
+mod seed_map;
+pub mod seed_phrase;
 mod table;
 mod typing;
 
+pub use bit_stream::*;
+pub use char_map::*;
+pub use hex::*;
+pub use init_matrix::*;
+pub use life::*;
+pub use seed_map::*;
 pub use table::*;
 pub use typing::*;
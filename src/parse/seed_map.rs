@@ -0,0 +1,129 @@
+// 2025 Steven Chiacchira
+use crate::matrix::MatrixIndex;
+use crate::parse::DEFAULT_KEYS;
+use std::ops::Deref;
+use std::{error, fmt};
+
+/// Error occurring during the building or validation of a [`TemporalSeedMap`].
+#[derive(Debug)]
+pub enum SeedMapError {
+    /// A seed map may cover at most the 32 bits of a seed. Holds the number of bits given.
+    TooManyBits(usize),
+    /// Every position must lie inside the matrix being seeded. Holds the bit and the position.
+    OutOfBounds { bit: usize, index: MatrixIndex },
+}
+
+impl fmt::Display for SeedMapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SeedMapError::TooManyBits(n_bits) => write!(
+                f,
+                "seed map has {} bits but a seed has only {}",
+                n_bits,
+                u32::BITS
+            ),
+            SeedMapError::OutOfBounds { bit, index } => write!(
+                f,
+                "bit {} is seeded at ({}, {}), outside the matrix",
+                bit, index.row, index.col
+            ),
+        }
+    }
+}
+
+impl error::Error for SeedMapError {}
+
+/// The matrix positions overwritten by each bit of a seed during temporal seeding, see
+/// [`temporal_seed_automata`](crate::encrypt::temporal_seed_automata). The `i`th entry lists the
+/// positions set to bit `i` of the seed, and holds at most 32 entries.
+///
+/// Dereferences to the slice of positions taken by the seeding functions.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct TemporalSeedMap {
+    positions: Vec<Vec<MatrixIndex>>,
+}
+
+impl TemporalSeedMap {
+    /// Creates the map described by the base-32 digits of an init matrix, such as
+    /// [`TRANSPOSE_INIT_MATRIX`](crate::encrypt::TRANSPOSE_INIT_MATRIX): bit `i` is seeded at
+    /// every cell holding the `i`th digit of `A` to `Z` and `2` to `7`.
+    pub fn from_init_matrix(string: &str) -> Self {
+        Self {
+            positions: DEFAULT_KEYS
+                .chars()
+                .map(|character| get_char_indices(string, character))
+                .collect(),
+        }
+    }
+
+    /// Creates the map seeding bit `i` at each index in `positions[i]`. Returns a
+    /// [`SeedMapError::TooManyBits`] if `positions` has more than 32 entries.
+    pub fn from_positions(positions: Vec<Vec<MatrixIndex>>) -> Result<Self, SeedMapError> {
+        if positions.len() > u32::BITS as usize {
+            return Err(SeedMapError::TooManyBits(positions.len()));
+        }
+
+        Ok(Self { positions })
+    }
+
+    /// Returns a [`SeedMapError::OutOfBounds`] naming the first position which does not lie
+    /// inside a `rows` by `cols` matrix. Seeding wraps such positions around the torus, which is
+    /// rarely intended.
+    pub fn validate(&self, rows: usize, cols: usize) -> Result<(), SeedMapError> {
+        for (bit, positions) in self.positions.iter().enumerate() {
+            let outside = positions.iter().find(|index| {
+                !(0..rows as isize).contains(&index.row) || !(0..cols as isize).contains(&index.col)
+            });
+            if let Some(index) = outside {
+                return Err(SeedMapError::OutOfBounds { bit, index: *index });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the positions seeded by each bit.
+    pub fn get_positions(&self) -> &[Vec<MatrixIndex>] {
+        &self.positions
+    }
+}
+
+impl Deref for TemporalSeedMap {
+    type Target = [Vec<MatrixIndex>];
+
+    fn deref(&self) -> &Self::Target {
+        &self.positions
+    }
+}
+
+impl From<TemporalSeedMap> for Vec<Vec<MatrixIndex>> {
+    fn from(map: TemporalSeedMap) -> Self {
+        map.positions
+    }
+}
+
+/// Returns a vector of vectors specifying the
+/// [`ToroidalBinaryMatrix`](crate::matrix::ToroidalBinaryMatrix) positions corresponding to
+/// each bit of a key.
+/// Ex.
+/// The first entry of the returned vector is a list of matrix indices associated with the first
+/// bit index of the key.
+///
+/// See also [`TemporalSeedMap::from_init_matrix`].
+pub fn get_temporal_seed_map(string: &str) -> Vec<Vec<MatrixIndex>> {
+    TemporalSeedMap::from_init_matrix(string).into()
+}
+
+/// Returns the indices of `character` in `string` as [`MatrixIndex`](crate::matrix::MatrixIndex).
+fn get_char_indices(string: &str, character: char) -> Vec<MatrixIndex> {
+    let mut result = Vec::new();
+    for (row, line) in string.lines().enumerate() {
+        for (col, ch) in line.chars().enumerate() {
+            if ch == character {
+                result.push(MatrixIndex::new(row as isize, col as isize));
+            }
+        }
+    }
+
+    result
+}
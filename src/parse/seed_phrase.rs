@@ -0,0 +1,123 @@
+// 2025 Steven Chiacchira
+//! Seed phrases: keys written over the base-32 alphabet `A` to `Z` and `2` to `7` used by the init
+//! matrices, which are easier to read out and type than decimal integers or hexadecimal.
+//!
+//! Bytes are written most significant bit first, five bits per character as in the unpadded
+//! Base32 encoding of [RFC 4648](https://datatracker.ietf.org/doc/html/rfc4648#section-6), with a
+//! [`SEPARATOR`] between each group of [`GROUP_CHARS`] characters. A 32 bit key becomes a phrase
+//! such as `AEBA-GBA`. Decoding ignores case, separators and whitespace.
+use crate::matrix::BitOrder;
+use crate::parse::{BitUnpacker, DEFAULT_KEYS};
+use std::{error, fmt};
+
+/// Number of characters between separators in an encoded phrase.
+pub const GROUP_CHARS: usize = 4;
+
+/// Character separating groups in an encoded phrase.
+pub const SEPARATOR: char = '-';
+
+/// Number of bits written per character.
+const BITS_PER_CHAR: usize = 5;
+
+/// Error arising from decoding a seed phrase.
+#[derive(Debug)]
+pub enum SeedPhraseError {
+    /// A phrase may only hold base-32 digits, separators and whitespace. Holds the first invalid
+    /// character.
+    InvalidCharacter(char),
+    /// A phrase must have a number of digits which some number of bytes encodes to, and for a
+    /// 32 bit key exactly 7. Holds the number of digits given.
+    InvalidLength(usize),
+    /// The bits of the last digit past the end of the final byte must be `0`.
+    NonzeroPadding(),
+}
+
+impl fmt::Display for SeedPhraseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SeedPhraseError::InvalidCharacter(c) => {
+                write!(f, "seed phrase has invalid character {:?}", c)
+            }
+            SeedPhraseError::InvalidLength(len) => {
+                write!(f, "seed phrase has invalid length {}", len)
+            }
+            SeedPhraseError::NonzeroPadding() => write!(f, "seed phrase has trailing bits set"),
+        }
+    }
+}
+
+impl error::Error for SeedPhraseError {}
+
+/// Encodes `bytes` as a seed phrase.
+pub fn encode(bytes: &[u8]) -> String {
+    let alphabet = DEFAULT_KEYS.as_bytes();
+    let mut bits = BitUnpacker::new(bytes.iter().copied(), BitOrder::MsbFirst).peekable();
+
+    let mut result = String::new();
+    let mut n_digits = 0;
+    while bits.peek().is_some() {
+        if n_digits > 0 && n_digits % GROUP_CHARS == 0 {
+            result.push(SEPARATOR);
+        }
+        // a final partial digit is padded with 0 bits
+        let digit = (0..BITS_PER_CHAR).fold(0, |digit, _| {
+            digit << 1 | bits.next().unwrap_or(false) as usize
+        });
+        result.push(alphabet[digit] as char);
+        n_digits += 1;
+    }
+
+    result
+}
+
+/// Decodes a seed phrase written by [`encode`] into its bytes.
+pub fn decode(phrase: &str) -> Result<Vec<u8>, SeedPhraseError> {
+    let digits = phrase
+        .chars()
+        .filter(|c| *c != SEPARATOR && !c.is_whitespace())
+        .map(|c| {
+            DEFAULT_KEYS
+                .find(c.to_ascii_uppercase())
+                .ok_or(SeedPhraseError::InvalidCharacter(c))
+        })
+        .collect::<Result<Vec<usize>, _>>()?;
+
+    let n_bits = digits.len() * BITS_PER_CHAR;
+    let n_bytes = n_bits / u8::BITS as usize;
+    // encoding n bytes gives the fewest digits holding 8n bits, which leaves fewer than 5 over
+    if n_bits - n_bytes * u8::BITS as usize >= BITS_PER_CHAR {
+        return Err(SeedPhraseError::InvalidLength(digits.len()));
+    }
+
+    let mut bytes = vec![0_u8; n_bytes];
+    for (i, digit) in digits.iter().enumerate() {
+        for bit in 0..BITS_PER_CHAR {
+            let position = i * BITS_PER_CHAR + bit;
+            let value = (digit >> (BITS_PER_CHAR - 1 - bit)) & 1;
+            match bytes.get_mut(position / u8::BITS as usize) {
+                Some(byte) => *byte |= (value as u8) << (u8::BITS as usize - 1 - position % 8),
+                None if value != 0 => return Err(SeedPhraseError::NonzeroPadding()),
+                None => {}
+            }
+        }
+    }
+
+    Ok(bytes)
+}
+
+/// Encodes a 32 bit key, such as one passed to
+/// [`KeySchedule::from`](crate::encrypt::KeySchedule), as a 7 digit seed phrase of its
+/// big-endian bytes.
+pub fn encode_u32(key: u32) -> String {
+    encode(&key.to_be_bytes())
+}
+
+/// Decodes a seed phrase written by [`encode_u32`] into its 32 bit key.
+pub fn decode_u32(phrase: &str) -> Result<u32, SeedPhraseError> {
+    let bytes = decode(phrase)?;
+    let bytes: [u8; 4] = bytes.try_into().map_err(|bytes: Vec<u8>| {
+        SeedPhraseError::InvalidLength((bytes.len() * u8::BITS as usize).div_ceil(BITS_PER_CHAR))
+    })?;
+
+    Ok(u32::from_be_bytes(bytes))
+}
@@ -1,28 +1,182 @@
 // 2025 Steven Chiacchira
-use crate::matrix::MatrixIndex;
+use crate::parse::CharMap;
+use rand::seq::index;
+use rand::RngCore;
 use std::collections::HashMap;
+use std::io::{self, BufRead};
 use std::iter::zip;
-/// Error occurring during the reading of a string defining a table of `bool` values.
+use std::{error, fmt};
+/// Error occurring during the reading of a string defining a table of `bool` values. Lines and
+/// columns are counted from 1, with columns counted in characters.
 #[derive(Debug)]
 pub enum TableReadError {
     /// Error occurring from using an invalid character in the file read
-    InvalidCharacter(char),
-    /// Error occurring from a non-uniform table
-    RaggedTable(),
+    InvalidCharacter {
+        character: char,
+        line: usize,
+        column: usize,
+    },
+    /// Error occurring from a non-uniform table. `expected` is the length of the first row and
+    /// `found` the length of the row on `line`.
+    RaggedTable {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// Error occurring from failing to read the table from a reader
+    Io(io::Error),
 }
 
-const DEFAULT_KEYS: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+impl fmt::Display for TableReadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TableReadError::InvalidCharacter {
+                character,
+                line,
+                column,
+            } => write!(
+                f,
+                "invalid character {:?} at line {}, column {}",
+                character, line, column
+            ),
+            TableReadError::RaggedTable {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "line {} has {} columns but the first line has {}",
+                line, found, expected
+            ),
+            TableReadError::Io(e) => write!(f, "I/O failed: {}", e),
+        }
+    }
+}
+
+impl error::Error for TableReadError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            TableReadError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for TableReadError {
+    fn from(e: io::Error) -> Self {
+        TableReadError::Io(e)
+    }
+}
+
+/// Error occurring during the writing of a table of `bool` values as a string.
+#[derive(Debug)]
+pub enum TableWriteError {
+    /// Every value in the table must have a character bound to it. Holds the unbound value.
+    UnboundValue(bool),
+}
+
+impl fmt::Display for TableWriteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TableWriteError::UnboundValue(value) => {
+                write!(f, "no character is bound to {}", value)
+            }
+        }
+    }
+}
+
+impl error::Error for TableWriteError {}
+
+/// Options relaxing what [`parse_bool_table_with`] and [`parse_bool_table_from_reader_with`]
+/// accept, so that table files can carry annotations. The default accepts only table rows, as
+/// [`parse_bool_table`] does. Skipped lines still count towards the line numbers of errors.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct TableParseOptions {
+    /// Whether to skip lines which are empty, after trimming if `trim_trailing_whitespace` is set.
+    pub skip_blank_lines: bool,
+    /// Whether to remove whitespace from the end of each line before reading it.
+    pub trim_trailing_whitespace: bool,
+    /// Prefix marking a line as a comment to be skipped. Should not start with a character bound
+    /// in the [`CharMap`], or rows starting with it will be skipped too.
+    pub comment_prefix: Option<String>,
+}
+
+impl TableParseOptions {
+    /// Returns options skipping blank lines, trimming trailing whitespace and skipping comments
+    /// starting with `!`, as in the plaintext pattern format.
+    pub fn tolerant() -> Self {
+        Self {
+            skip_blank_lines: true,
+            trim_trailing_whitespace: true,
+            comment_prefix: Some("!".to_string()),
+        }
+    }
+
+    /// Returns the part of `line` to read as a row, or `None` if it should be skipped.
+    fn row_of<'a>(&self, line: &'a str) -> Option<&'a str> {
+        if self
+            .comment_prefix
+            .as_ref()
+            .is_some_and(|prefix| line.starts_with(prefix.as_str()))
+        {
+            return None;
+        }
+        let line = if self.trim_trailing_whitespace {
+            line.trim_end()
+        } else {
+            line
+        };
+        if self.skip_blank_lines && line.is_empty() {
+            return None;
+        }
+
+        Some(line)
+    }
+}
+
+pub(crate) const DEFAULT_KEYS: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
 
 /// Generates a map from base-32 digits to boolean values from a u32.
 /// Ex.
 /// The number 1, represented as `00000000000000000000000000000001` with 32 digits, would create a
 /// `HashMap` containing `false` for all characters except `A`, or 0 in base 32.
+/// See also [`CharMap::seeded`], which also binds the matrix symbols.
 pub fn gen_char_map(seed: u32) -> HashMap<char, bool> {
-    zip(
-        DEFAULT_KEYS.chars(),
-        (0..DEFAULT_KEYS.len()).map(|n| (seed >> n) & 1 != 0),
-    )
-    .collect::<HashMap<char, bool>>()
+    seed_bindings(DEFAULT_KEYS.chars(), seed).collect::<HashMap<char, bool>>()
+}
+
+/// Pairs each character of `alphabet` with a bit of `seed`, the `n`th character taking bit `n`.
+/// Characters past the 32nd are given `false`.
+pub(crate) fn seed_bindings<I>(alphabet: I, seed: u32) -> impl Iterator<Item = (char, bool)>
+where
+    I: IntoIterator<Item = char>,
+{
+    zip(alphabet, 0..).map(move |(c, n)| (c, seed.checked_shr(n).unwrap_or(0) & 1 != 0))
+}
+
+/// Returns a `rows` by `cols` bool table with cells drawn from `rng`, of which exactly
+/// `density * rows * cols`, rounded to the nearest integer, are `true`. A seeded `rng` makes the
+/// table reproducible.
+///
+/// # Panics
+/// If `density` is not between 0 and 1.
+pub fn random_table<R>(rows: usize, cols: usize, density: f64, rng: &mut R) -> Vec<Vec<bool>>
+where
+    R: RngCore + ?Sized,
+{
+    assert!(
+        (0.0..=1.0).contains(&density),
+        "density must be between 0 and 1"
+    );
+    let n_cells = rows * cols;
+    let n_true = (density * n_cells as f64).round() as usize;
+
+    let mut cells = vec![false; n_cells];
+    for i in index::sample(rng, n_cells, n_true) {
+        cells[i] = true;
+    }
+
+    cells.chunks(cols.max(1)).map(|row| row.to_vec()).collect()
 }
 
 /// Reads a string as a bool table state with characters.
@@ -41,53 +195,136 @@ pub fn gen_char_map(seed: u32) -> HashMap<char, bool> {
 /// FFFTF
 /// FTTTF
 /// ```
-/// with char_map { '#': true, '.': false }.
+/// with a `char_map` binding `'#'` to `true` and `'.'` to `false`.
 /// Returns a [`Result`] containing either the new bool table on a success, or a
-/// [`TableReadError`] on a failure.
+/// [`TableReadError`] naming the offending line on a failure, including when a row has a
+/// different length than the first.
 pub fn parse_bool_table(
     string: &str,
-    char_map: &HashMap<char, bool>,
+    char_map: &CharMap,
+) -> Result<Vec<Vec<bool>>, TableReadError> {
+    parse_bool_table_with(string, char_map, &TableParseOptions::default())
+}
+
+/// Reads a string as a bool table state as [`parse_bool_table`] does, skipping and trimming lines
+/// as set by `options`.
+pub fn parse_bool_table_with(
+    string: &str,
+    char_map: &CharMap,
+    options: &TableParseOptions,
 ) -> Result<Vec<Vec<bool>>, TableReadError> {
     let mut table: Vec<Vec<bool>> = Vec::new();
-    for line in string.lines() {
-        let val_row: Vec<bool> = line
-            .chars()
-            .map(|c| match char_map.get(&c) {
-                Some(v) => Ok(v.to_owned()),
-                None => Err(TableReadError::InvalidCharacter(c)),
-            })
-            .collect::<Result<Vec<bool>, TableReadError>>()?;
-
-        table.push(val_row);
+    for (i, line) in string.lines().enumerate() {
+        if let Some(row) = options.row_of(line) {
+            push_bool_row(&mut table, row, i + 1, char_map)?;
+        }
     }
 
     Ok(table)
 }
 
-/// Returns a vector of vectors specifying the
-/// [`ToroidalBinaryMatrix`](crate::matrix::ToroidalBinaryMatrix) positions corresponding to
-/// each bit of a key.
-/// Ex.
-/// The first entry of the returned vector is a list of matrix indices associated with the first
-/// bit index of the key.
-pub fn get_temporal_seed_map(string: &str) -> Vec<Vec<MatrixIndex>> {
-    let mut result = Vec::new();
-    for character in DEFAULT_KEYS.chars() {
-        result.push(get_char_indices(string, character))
+/// Reads a bool table state from `reader` one line at a time, so that the whole text never has to
+/// be held in memory. Accepts the same input as [`parse_bool_table`], and additionally returns a
+/// [`TableReadError::Io`] if `reader` fails.
+pub fn parse_bool_table_from_reader<R>(
+    reader: R,
+    char_map: &CharMap,
+) -> Result<Vec<Vec<bool>>, TableReadError>
+where
+    R: BufRead,
+{
+    parse_bool_table_from_reader_with(reader, char_map, &TableParseOptions::default())
+}
+
+/// Reads a bool table state from `reader` as [`parse_bool_table_from_reader`] does, skipping and
+/// trimming lines as set by `options`.
+pub fn parse_bool_table_from_reader_with<R>(
+    mut reader: R,
+    char_map: &CharMap,
+    options: &TableParseOptions,
+) -> Result<Vec<Vec<bool>>, TableReadError>
+where
+    R: BufRead,
+{
+    let mut table: Vec<Vec<bool>> = Vec::new();
+    let mut line = String::new();
+    let mut line_number = 1;
+    while reader.read_line(&mut line)? != 0 {
+        // strip the line ending as str::lines does
+        let row = line.strip_suffix('\n').unwrap_or(&line);
+        let row = row.strip_suffix('\r').unwrap_or(row);
+        if let Some(row) = options.row_of(row) {
+            push_bool_row(&mut table, row, line_number, char_map)?;
+        }
+        line.clear();
+        line_number += 1;
     }
-    result
+
+    Ok(table)
 }
 
-/// Returns the indices of `character` in `string` as [`MatrixIndex`](crate::matrix::MatrixIndex).
-fn get_char_indices(string: &str, character: char) -> Vec<MatrixIndex> {
-    let mut result = Vec::new();
-    for (row, line) in string.lines().enumerate() {
-        for (col, ch) in line.chars().enumerate() {
-            if ch == character {
-                result.push((row as isize, col as isize));
-            }
+/// Reads `line`, the `line_number`th line of a bool table state, and appends it to `table`.
+fn push_bool_row(
+    table: &mut Vec<Vec<bool>>,
+    line: &str,
+    line_number: usize,
+    char_map: &CharMap,
+) -> Result<(), TableReadError> {
+    let row = line
+        .chars()
+        .enumerate()
+        .map(|(i, c)| match char_map.get(c) {
+            Some(v) => Ok(v),
+            None => Err(TableReadError::InvalidCharacter {
+                character: c,
+                line: line_number,
+                column: i + 1,
+            }),
+        })
+        .collect::<Result<Vec<bool>, TableReadError>>()?;
+    if let Some(first) = table.first() {
+        if first.len() != row.len() {
+            return Err(TableReadError::RaggedTable {
+                line: line_number,
+                expected: first.len(),
+                found: row.len(),
+            });
+        }
+    }
+
+    table.push(row);
+    Ok(())
+}
+
+/// Writes a bool table as a string readable by [`parse_bool_table`], one line per row, each
+/// ending in a newline. Values are written with the characters given by [`CharMap::char_for`].
+/// Ex:
+/// the table
+/// ```txt
+/// FTF
+/// TTF
+/// ```
+/// with a `char_map` binding `'#'` to `true` and `'.'` to `false` is written as
+/// ```txt
+/// .#.
+/// ##.
+/// ```
+/// Returns a [`TableWriteError`] if a value in the table has no character bound to it.
+pub fn format_bool_table(
+    table: &[Vec<bool>],
+    char_map: &CharMap,
+) -> Result<String, TableWriteError> {
+    let true_char = char_map.char_for(true);
+    let false_char = char_map.char_for(false);
+
+    let mut result = String::new();
+    for row in table {
+        for value in row {
+            let c = if *value { true_char } else { false_char };
+            result.push(c.ok_or(TableWriteError::UnboundValue(*value))?);
         }
+        result.push('\n');
     }
 
-    result
+    Ok(result)
 }
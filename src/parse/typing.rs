@@ -1,58 +1,87 @@
 // 2025 Steven Chiacchira
+use crate::matrix::{BitOrder, BitWord};
+use crate::parse::{BitPacker, BitUnpacker};
 
-/// Transforms a `u8` into a `Vec<bool>` containing its binary representation.
-/// See also [`concat_bool_to_u8`].
-pub fn explode_u8_to_bool(byte: u8) -> Vec<bool> {
-    let mut result = Vec::with_capacity(u8::BITS as usize);
-    for i in 0..(u8::BITS as usize) {
-        result.push((byte >> i) & 1 == 1);
-    }
-
-    result
-}
-
-/// Transforms a series of bytes into a series of bools containing the binary representation of
-/// the bytes.
+/// Transforms `word` into a `Vec<bool>` of its `T::BITS` bits, starting from its least or most
+/// significant bit as given by `order`.
 /// ex.
 /// ```txt
-/// [1, 2] -> [0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 1, 0]
+/// explode_bits(0b0000_0110_u8, BitOrder::LsbFirst) -> [0, 1, 1, 0, 0, 0, 0, 0]
+/// explode_bits(0b0000_0110_u8, BitOrder::MsbFirst) -> [0, 0, 0, 0, 0, 1, 1, 0]
 /// ```
-/// 
-/// See also [`concat_bool_to_u8_vec`].
-pub fn explode_u8_to_bool_vec(bytes: Vec<u8>) -> Vec<bool> {
-    bytes
-        .iter()
-        .map(|b| explode_u8_to_bool(*b))
-        .flatten()
+///
+/// See also [`concat_bits`].
+pub fn explode_bits<T>(word: T, order: BitOrder) -> Vec<bool>
+where
+    T: BitWord,
+{
+    let word = match order {
+        BitOrder::LsbFirst => word,
+        BitOrder::MsbFirst => word.reverse_bits(),
+    };
+
+    (0..T::BITS)
+        .map(|i| (word >> i) & T::ONE != T::ZERO)
         .collect()
 }
 
-/// Concatenates a bitstring represented as a `Vec<bool>` into a `u8`.
-/// See also [`explode_u8_to_bool`].
-pub fn concat_bool_to_u8(bits: Vec<bool>) -> u8 {
-    debug_assert!(bits.len() <= 8);
-    let mut result = 0;
-    for (i, bit) in bits.into_iter().enumerate() {
-        result += 2_u8.pow(i as u32) * bit as u8
+/// Concatenates at most `T::BITS` bits into a word, the first stored in its least or most
+/// significant bit as given by `order`, matching [`BitOrder`] in
+/// [`ToroidalBitMatrix::to_words`](crate::matrix::ToroidalBitMatrix::to_words). Bits of the word
+/// past the end of `bits` are `0`, so with [`BitOrder::MsbFirst`] fewer than `T::BITS` bits fill
+/// the top of the word.
+/// See also [`explode_bits`].
+pub fn concat_bits<T>(bits: &[bool], order: BitOrder) -> T
+where
+    T: BitWord,
+{
+    debug_assert!(bits.len() <= T::BITS);
+    let word = bits
+        .iter()
+        .enumerate()
+        .fold(T::ZERO, |word, (i, bit)| match bit {
+            true => word | T::ONE << i,
+            false => word,
+        });
+
+    match order {
+        BitOrder::LsbFirst => word,
+        BitOrder::MsbFirst => word.reverse_bits(),
     }
+}
 
-    result
+/// Transforms a `u8` into a `Vec<bool>` containing its binary representation, starting from its
+/// least or most significant bit as given by `order`. This is [`explode_bits`] for a `u8`.
+/// See also [`concat_bool_to_u8`].
+pub fn explode_u8_to_bool(byte: u8, order: BitOrder) -> Vec<bool> {
+    explode_bits(byte, order)
 }
 
-/// Concatenates a bitstring represented as a `Vec<bool>` into a series of `u8`s.
-/// See also [`explode_u8_to_bool_vec`].
-pub fn concat_bool_to_u8_vec(bits: Vec<bool>) -> Vec<u8> {
-    bits.chunks(u8::BITS as usize)
-        .map(|b| concat_bool_to_u8(b.to_vec()))
-        .collect()
+/// Transforms a series of bytes into a series of bools containing the binary representation of
+/// the bytes, each starting from its least or most significant bit as given by `order`.
+/// ex.
+/// ```txt
+/// [1, 2], BitOrder::LsbFirst -> [1, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0]
+/// [1, 2], BitOrder::MsbFirst -> [0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 1, 0]
+/// ```
+///
+/// See also [`concat_bool_to_u8_vec`], and [`BitUnpacker`] for splitting bytes without collecting
+/// the bits.
+pub fn explode_u8_to_bool_vec(bytes: Vec<u8>, order: BitOrder) -> Vec<bool> {
+    BitUnpacker::new(bytes, order).collect()
 }
 
-#[allow(dead_code)]
-fn concat_u8_to_u32(bytes: Vec<u8>) -> u32 {
-    let mut result = 0;
-    for (i, byte) in bytes.into_iter().enumerate() {
-        result += 16_u32.pow(i as u32) * byte as u32;
-    }
+/// Concatenates a bitstring represented as a `Vec<bool>` into a `u8`, the first bit stored in its
+/// least or most significant bit as given by `order`. This is [`concat_bits`] for a `u8`.
+/// See also [`explode_u8_to_bool`].
+pub fn concat_bool_to_u8(bits: Vec<bool>, order: BitOrder) -> u8 {
+    concat_bits(&bits, order)
+}
 
-    result
+/// Concatenates a bitstring represented as a `Vec<bool>` into a series of `u8`s, eight bits per
+/// byte in `order`, padding the final byte with `0` bits.
+/// See also [`explode_u8_to_bool_vec`], and [`BitPacker`] for joining bits without collecting
+/// them first.
+pub fn concat_bool_to_u8_vec(bits: Vec<bool>, order: BitOrder) -> Vec<u8> {
+    BitPacker::new(bits, order).collect()
 }
@@ -0,0 +1,86 @@
+// 2025 Steven Chiacchira
+use crate::encrypt::{Key, Rfc0Scrambler, Scrambler, TalosCipher};
+use crate::matrix::{ToroidalBinaryMatrix, ToroidalBoolMatrix};
+use std::{error, fmt};
+
+/// Key of the known-answer test, written in hexadecimal.
+const KNOWN_ANSWER_KEY: &str = "000102030405060708090a0b0c0d0e0f";
+/// Nonce of the known-answer test.
+const KNOWN_ANSWER_NONCE: u64 = 0x0123_4567_89ab_cdef;
+/// Message of the known-answer test.
+const KNOWN_ANSWER_MESSAGE: &[u8] = b"Talos self-test";
+/// Expected ciphertext of the known-answer test.
+const KNOWN_ANSWER_CIPHERTEXT: [u8; 32] = [
+    0xf8, 0x5b, 0xfe, 0xc4, 0x79, 0x9d, 0x92, 0xef, 0xde, 0x2c, 0xa1, 0x63, 0x70, 0xcd, 0xf9, 0x9d,
+    0x92, 0x13, 0xb9, 0xb5, 0x5e, 0x44, 0xbb, 0x57, 0x3d, 0x17, 0xfb, 0xd8, 0x3c, 0x5d, 0x25, 0x06,
+];
+
+/// Error arising from [`self_test`], naming the check which failed.
+#[derive(Debug)]
+pub enum SelfTestError {
+    /// Decrypting an encrypted message must return the original message.
+    RoundTripFailed(),
+    /// Encrypting the known-answer message must produce the recorded ciphertext.
+    KnownAnswerMismatch(),
+    /// Unscrambling a scrambled block must return the original block.
+    ScrambleNotInverted(),
+}
+
+impl fmt::Display for SelfTestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SelfTestError::RoundTripFailed() => {
+                write!(f, "decryption did not return the encrypted message")
+            }
+            SelfTestError::KnownAnswerMismatch() => {
+                write!(f, "encryption did not produce the known answer")
+            }
+            SelfTestError::ScrambleNotInverted() => {
+                write!(f, "unscrambling did not invert scrambling")
+            }
+        }
+    }
+}
+
+impl error::Error for SelfTestError {}
+
+/// Checks that this build of Talos works as intended, returning the first [`SelfTestError`]
+/// found. The checks are an encrypt/decrypt round trip over several message lengths, a
+/// known-answer test against a recorded ciphertext and a check that [`Rfc0Scrambler`] inverts
+/// its own scrambling.
+pub fn self_test() -> Result<(), SelfTestError> {
+    let key: Key = KNOWN_ANSWER_KEY.parse().unwrap();
+    let schedule = key.schedule();
+
+    for len in [0, 1, 31, 32, 33, 100] {
+        let message = (0..len).map(|i| (i * 7) as u8).collect::<Vec<u8>>();
+        let mut encryptor: TalosCipher = TalosCipher::new(&schedule, KNOWN_ANSWER_NONCE);
+        let mut decryptor: TalosCipher = TalosCipher::new(&schedule, KNOWN_ANSWER_NONCE);
+        let ciphertext = encryptor.encrypt(&message);
+        if decryptor.decrypt(&ciphertext).ok().as_deref() != Some(message.as_slice()) {
+            return Err(SelfTestError::RoundTripFailed());
+        }
+    }
+
+    let mut cipher: TalosCipher = TalosCipher::new(&schedule, KNOWN_ANSWER_NONCE);
+    if cipher.encrypt(KNOWN_ANSWER_MESSAGE) != KNOWN_ANSWER_CIPHERTEXT {
+        return Err(SelfTestError::KnownAnswerMismatch());
+    }
+
+    for seed in 0..8_u8 {
+        let bytes = (0..32_u8)
+            .map(|i| seed.wrapping_mul(31).wrapping_add(i.wrapping_mul(13)))
+            .collect::<Vec<u8>>();
+        let key_bytes = bytes.iter().rev().map(|b| b ^ 0x5a).collect::<Vec<u8>>();
+        let block = ToroidalBoolMatrix::from_bytes(16, 16, &bytes).unwrap();
+        let scramble_key = ToroidalBoolMatrix::from_bytes(16, 16, &key_bytes).unwrap();
+        let mut scrambled = block.clone();
+        Rfc0Scrambler.scramble(&mut scrambled, &scramble_key);
+        Rfc0Scrambler.unscramble(&mut scrambled, &scramble_key);
+        if scrambled != block {
+            return Err(SelfTestError::ScrambleNotInverted());
+        }
+    }
+
+    Ok(())
+}